@@ -1,7 +1,10 @@
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use rand::Rng;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
@@ -53,11 +56,22 @@ fn main() {
 
     fs::write(&dest_path, code).unwrap();
 
+    // Sprite archive — packed from pokemon-colorscripts sprite files
+    let sprites_dir = env::var("POKEMON_SPRITES_DIR")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("data/sprites"));
+    let archive = pack_sprites(&sprites_dir);
+    fs::write(Path::new(&out_dir).join("sprites.bin"), archive).unwrap();
+
     // Always rerun to ensure the secret is never stale
     println!("cargo:rerun-if-env-changed=BUILD_SECRET_KEY");
     println!("cargo:rerun-if-env-changed=CATCH_POKEMON_API_URL");
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=.build_secret");
+    println!("cargo:rerun-if-env-changed=POKEMON_SPRITES_DIR");
+    println!("cargo:rerun-if-changed={}", sprites_dir.display());
 }
 
 /// Hash any string into 32 bytes using a simple but effective mixing function.
@@ -104,3 +118,206 @@ fn generate_and_cache(cache_path: &Path) -> [u8; 32] {
     let _ = fs::write(cache_path, &key);
     hash_key(&key)
 }
+
+// Pixel rows of a decoded sprite, None = transparent
+type PixelRows = Vec<Vec<Option<[u8; 3]>>>;
+
+/// Pack `<dir>/regular/<name>` and `<dir>/shiny/<name>` (the layout of
+/// pokemon-colorscripts' `colorscripts/small`) into a zlib-compressed archive.
+///
+/// Archive layout (all integers little-endian):
+///   b"CPSP", version u8, count u16, then per sprite:
+///   name_len u8, name, flags u8 (bit 0 = shiny), width u8, height u8,
+///   palette_len u8, palette (RGB triples), width*height palette indices
+///   (0 = transparent, n = palette[n - 1])
+fn pack_sprites(dir: &Path) -> Vec<u8> {
+    let mut sprites: Vec<(String, bool, PixelRows)> = Vec::new();
+    for (sub, shiny) in [("regular", false), ("shiny", true)] {
+        let Ok(entries) = fs::read_dir(dir.join(sub)) else { continue };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+        for path in paths {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+            let Ok(text) = fs::read_to_string(&path) else { continue };
+            let pixels = parse_colorscript(&text);
+            if !pixels.is_empty() && pixels[0].len() <= 255 && pixels.len() <= 255 {
+                sprites.push((name.trim_end_matches(".txt").to_lowercase(), shiny, pixels));
+            }
+        }
+    }
+
+    if sprites.is_empty() {
+        println!(
+            "cargo:warning=No sprites found in {} — building without embedded sprites (run scripts/fetch-sprites.sh)",
+            dir.display()
+        );
+    }
+
+    let mut raw: Vec<u8> = b"CPSP".to_vec();
+    raw.push(1);
+    raw.extend_from_slice(&(sprites.len() as u16).to_le_bytes());
+    for (name, shiny, pixels) in &sprites {
+        let height = pixels.len();
+        let width = pixels[0].len();
+
+        let mut palette: Vec<[u8; 3]> = Vec::new();
+        let mut indices: Vec<u8> = Vec::with_capacity(width * height);
+        for row in pixels {
+            for px in row {
+                indices.push(match px {
+                    None => 0,
+                    Some(rgb) => match palette.iter().position(|p| p == rgb) {
+                        Some(i) => i as u8 + 1,
+                        None if palette.len() < 255 => {
+                            palette.push(*rgb);
+                            palette.len() as u8
+                        }
+                        None => nearest_palette_index(&palette, *rgb) as u8 + 1,
+                    },
+                });
+            }
+        }
+
+        raw.push(name.len() as u8);
+        raw.extend_from_slice(name.as_bytes());
+        raw.push(if *shiny { 1 } else { 0 });
+        raw.push(width as u8);
+        raw.push(height as u8);
+        raw.push(palette.len() as u8);
+        for rgb in &palette {
+            raw.extend_from_slice(rgb);
+        }
+        raw.extend_from_slice(&indices);
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&raw).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// Decode a half-block ANSI sprite into rows of pixels, cropped to the opaque area.
+/// Each text line holds two pixel rows: `▀` draws fg on top / bg below, `▄` the reverse.
+fn parse_colorscript(text: &str) -> PixelRows {
+    let mut rows: PixelRows = Vec::new();
+
+    for line in text.lines() {
+        let mut top = Vec::new();
+        let mut bottom = Vec::new();
+        let mut fg: Option<[u8; 3]> = None;
+        let mut bg: Option<[u8; 3]> = None;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                let mut seq = String::new();
+                for n in chars.by_ref() {
+                    if n.is_ascii_alphabetic() {
+                        break;
+                    }
+                    seq.push(n);
+                }
+                let params: Vec<u32> = seq
+                    .trim_start_matches('[')
+                    .split(';')
+                    .map(|p| p.parse().unwrap_or(0))
+                    .collect();
+                let mut i = 0;
+                while i < params.len() {
+                    match params[i] {
+                        0 => { fg = None; bg = None; }
+                        39 => fg = None,
+                        49 => bg = None,
+                        code @ (38 | 48) => {
+                            let color = match params.get(i + 1) {
+                                Some(5) => {
+                                    let c = params.get(i + 2).map(|&n| xterm_to_rgb(n as u8));
+                                    i += 2;
+                                    c
+                                }
+                                Some(2) => {
+                                    let c = match (params.get(i + 2), params.get(i + 3), params.get(i + 4)) {
+                                        (Some(&r), Some(&g), Some(&b)) => Some([r as u8, g as u8, b as u8]),
+                                        _ => None,
+                                    };
+                                    i += 4;
+                                    c
+                                }
+                                _ => None,
+                            };
+                            if code == 38 { fg = color; } else { bg = color; }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+
+            let (t, b) = match c {
+                '▀' => (fg, bg),
+                '▄' => (bg, fg),
+                '█' => (fg, fg),
+                ' ' => (bg, bg),
+                _ => (None, None),
+            };
+            top.push(t);
+            bottom.push(b);
+        }
+
+        rows.push(top);
+        rows.push(bottom);
+    }
+
+    // Crop to the bounding box of opaque pixels
+    let opaque: Vec<(usize, usize)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, p)| p.is_some()).map(move |(x, _)| (x, y)))
+        .collect();
+    if opaque.is_empty() {
+        return Vec::new();
+    }
+    let min_x = opaque.iter().map(|p| p.0).min().unwrap();
+    let max_x = opaque.iter().map(|p| p.0).max().unwrap();
+    let min_y = opaque.iter().map(|p| p.1).min().unwrap();
+    let max_y = opaque.iter().map(|p| p.1).max().unwrap();
+
+    rows[min_y..=max_y]
+        .iter()
+        .map(|row| (min_x..=max_x).map(|x| row.get(x).copied().flatten()).collect())
+        .collect()
+}
+
+/// Standard xterm 256-colour palette
+fn xterm_to_rgb(n: u8) -> [u8; 3] {
+    const BASIC: [[u8; 3]; 16] = [
+        [0, 0, 0], [128, 0, 0], [0, 128, 0], [128, 128, 0],
+        [0, 0, 128], [128, 0, 128], [0, 128, 128], [192, 192, 192],
+        [128, 128, 128], [255, 0, 0], [0, 255, 0], [255, 255, 0],
+        [0, 0, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+    ];
+    match n {
+        0..=15 => BASIC[n as usize],
+        16..=231 => {
+            let i = n - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            [level(i / 36), level((i / 6) % 6), level(i % 6)]
+        }
+        _ => {
+            let v = 8 + (n - 232) * 10;
+            [v, v, v]
+        }
+    }
+}
+
+fn nearest_palette_index(palette: &[[u8; 3]], rgb: [u8; 3]) -> usize {
+    let dist = |p: &[u8; 3]| -> u32 {
+        (0..3).map(|i| (p[i] as i32 - rgb[i] as i32).pow(2) as u32).sum()
+    };
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, p)| dist(p))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
@@ -4,7 +4,7 @@
 # POKEMON CATCHING SYSTEM
 # ============================================================================
 # Advanced Pokemon catching mechanics with command tracking and escape logic
-# Integrates with pokemon-colorscripts and catch-pokemon CLI tools
+# Integrates with the catch-pokemon CLI
 
 # --- POKEMON ENCOUNTER SYSTEM ---
 
@@ -12,6 +12,7 @@
 pokemon_encounter() {
     local current_pokemon
     local category=""
+    local form=""
 
     local is_shiny="false"
 
@@ -26,6 +27,32 @@ pokemon_encounter() {
         # Extract session token (third line)
         local session_token
         session_token=$(echo "$encounter_output" | grep "^Token:" | sed 's/Token: //')
+        # Extract form (only present for regional, Mega and alternate forms)
+        form=$(echo "$encounter_output" | grep "^Form:" | sed 's/Form: //')
+        # Extract active time-of-day and seasonal modifiers
+        local modifiers
+        modifiers=$(echo "$encounter_output" | grep "^Modifier:" | sed 's/Modifier: //')
+        # Extract the outbreak window (only present for today's outbreak species)
+        local outbreak
+        outbreak=$(echo "$encounter_output" | grep "^Outbreak:" | sed 's/Outbreak: //')
+        # Extract the shiny odds and whatever is boosting them
+        local shiny_odds
+        shiny_odds=$(echo "$encounter_output" | grep "^Shiny odds:" | sed 's/Shiny odds: //')
+        # Extract active items (incense, repel, lure)
+        local items
+        items=$(echo "$encounter_output" | grep "^Item:" | sed 's/Item: //')
+        # Extract the pity boost (only present while it is ramping)
+        local pity
+        pity=$(echo "$encounter_output" | grep "^Pity:" | sed 's/Pity: //')
+        # Extract a roaming legendary's carried-over HP and status
+        local roaming
+        roaming=$(echo "$encounter_output" | grep "^Roaming:" | sed 's/Roaming: //')
+        # Extract eggs ready to hatch
+        local eggs
+        eggs=$(echo "$encounter_output" | grep "^Egg:" | sed 's/Egg: //')
+        # Extract the habitat detected from the current directory
+        local habitat_line
+        habitat_line=$(echo "$encounter_output" | grep "^Habitat:" | sed 's/Habitat: //')
         # Extract category, stripping ANSI color codes
         category=$(echo "$encounter_output" | grep "^Category:" | sed 's/Category: //' | sed 's/\x1b\[[0-9;]*m//g' | tr '[:upper:]' '[:lower:]')
         # Extract type line (keep ANSI colors for display)
@@ -33,21 +60,27 @@ pokemon_encounter() {
         pokemon_type=$(echo "$encounter_output" | grep "^Type:")
     fi
 
-    # Fallback to pokemon-colorscripts random if catch-pokemon is not available
     if [[ -z "$current_pokemon" ]]; then
-        local pokemon_output=$(pokemon-colorscripts -r)
-        current_pokemon=$(echo "$pokemon_output" | head -1)
+        return 1
     fi
 
     # Store current pokemon and reset states
     export CURRENT_WILD_POKEMON="$current_pokemon"
     export POKEMON_IS_SHINY="$is_shiny"
+    export POKEMON_FORM="$form"
+    export POKEMON_HABITAT="${habitat_line% \[boosted\]}"
     export POKEMON_SESSION_TOKEN="$session_token"
     export POKEMON_ATTEMPT=1
     export POKEMON_ESCAPED=false
     export POKEMON_RAN_AWAY=false
     export POKEMON_CAUGHT=false
 
+    # Forms are shown as e.g. vulpix-alola
+    local display_name="$current_pokemon"
+    if [[ -n "$form" ]]; then
+        display_name="$current_pokemon-$form"
+    fi
+
     # Shiny tag only appears if shiny
     local shiny_tag=""
     if [[ "$is_shiny" == "true" ]]; then
@@ -59,36 +92,84 @@ pokemon_encounter() {
     if [[ "$category" == "legendary" ]]; then
         echo -e "\033[1;5;31m⚡ A LEGENDARY POKEMON HAS APPEARED! ⚡\033[0m"
         echo -e "\033[1;31m════════════════════════════════════════\033[0m"
-        echo -e "A wild \033[1;31m$current_pokemon\033[0m appeared! \033[1;31m[Legendary]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;31m$display_name\033[0m appeared! \033[1;31m[Legendary]\033[0m$shiny_tag"
     elif [[ "$category" == "mythical" ]]; then
         echo -e "\033[1;5;35m✨ A MYTHICAL POKEMON HAS APPEARED! ✨\033[0m"
         echo -e "\033[1;35m════════════════════════════════════════\033[0m"
-        echo -e "A wild \033[1;35m$current_pokemon\033[0m appeared! \033[1;35m[Mythical]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;35m$display_name\033[0m appeared! \033[1;35m[Mythical]\033[0m$shiny_tag"
     elif [[ "$category" == "pseudo-legendary" ]]; then
-        echo -e "A wild \033[1;33m$current_pokemon\033[0m appeared! \033[1;33m[Pseudo-Legendary]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;33m$display_name\033[0m appeared! \033[1;33m[Pseudo-Legendary]\033[0m$shiny_tag"
     elif [[ "$category" == "starter" ]]; then
-        echo -e "A wild \033[1;32m$current_pokemon\033[0m appeared! \033[1;32m[Starter]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;32m$display_name\033[0m appeared! \033[1;32m[Starter]\033[0m$shiny_tag"
     elif [[ "$category" == "starter evolution" ]]; then
-        echo -e "A wild \033[1;32m$current_pokemon\033[0m appeared! \033[1;32m[Starter Evolution]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;32m$display_name\033[0m appeared! \033[1;32m[Starter Evolution]\033[0m$shiny_tag"
     elif [[ "$category" == "rare" ]]; then
-        echo -e "A wild \033[1;36m$current_pokemon\033[0m appeared! \033[1;36m[Rare]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;36m$display_name\033[0m appeared! \033[1;36m[Rare]\033[0m$shiny_tag"
     elif [[ "$category" == "baby" ]]; then
-        echo -e "A wild \033[1;35m$current_pokemon\033[0m appeared! \033[1;35m[Baby]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;35m$display_name\033[0m appeared! \033[1;35m[Baby]\033[0m$shiny_tag"
     elif [[ "$category" == "uncommon" ]]; then
-        echo -e "A wild \033[1;33m$current_pokemon\033[0m appeared! \033[2m[Uncommon]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;33m$display_name\033[0m appeared! \033[2m[Uncommon]\033[0m$shiny_tag"
     else
-        echo -e "A wild \033[1;33m$current_pokemon\033[0m appeared! \033[2m[Common]\033[0m$shiny_tag"
+        echo -e "A wild \033[1;33m$display_name\033[0m appeared! \033[2m[Common]\033[0m$shiny_tag"
     fi
 
-    # Display the Pokemon sprite (shiny version if shiny)
-    if command -v pokemon-colorscripts &>/dev/null; then
-        if [[ "$is_shiny" == "true" ]]; then
-            pokemon-colorscripts -n "$current_pokemon" --no-title -s 2>/dev/null
+    if [[ -n "$outbreak" ]]; then
+        echo -e "\033[1;31m🔥 MASS OUTBREAK!\033[0m \033[1;33m$display_name is swarming $outbreak - shiny odds boosted!\033[0m"
+    fi
+
+    if [[ -n "$roaming" ]]; then
+        echo -e "\033[1;35m🐾 ROAMING!\033[0m \033[1;33m$display_name is roaming at $roaming - it flees after one turn!\033[0m"
+    fi
+
+    # Only mention the shiny odds when a chain, charm or outbreak improves them
+    if [[ "$shiny_odds" == *" - "* ]]; then
+        echo -e "\033[1;33m✨ Shiny odds ${shiny_odds% - *}\033[0m \033[2m(${shiny_odds#* - })\033[0m"
+    fi
+
+    if [[ -n "$items" ]]; then
+        while IFS= read -r item; do
+            echo -e "\033[1;36m🎒 $item\033[0m"
+        done <<< "$items"
+    fi
+
+    if [[ -n "$eggs" ]]; then
+        echo -e "\033[1;35m🥚 ${eggs}! Run: catch-pokemon eggs\033[0m"
+    fi
+
+    if [[ -n "$pity" ]]; then
+        if [[ "$pity" == *"[boosted]" ]]; then
+            echo -e "\033[1;32m🍀 The drought is over! Pity ${pity% \[boosted\]}\033[0m"
         else
-            pokemon-colorscripts -n "$current_pokemon" --no-title 2>/dev/null
+            echo -e "\033[2m🍀 Pity: rare Pokemon ${pity}\033[0m"
         fi
     fi
 
+    # Say which encounter modifiers are active
+    if [[ -n "$modifiers" ]]; then
+        while IFS= read -r modifier; do
+            if [[ "$modifier" == *"[boosted]" ]]; then
+                echo -e "\033[1;35m🌙 ${modifier% \[boosted\]}\033[0m \033[1;33m[Boosted]\033[0m"
+            else
+                echo -e "\033[2m🌙 $modifier\033[0m"
+            fi
+        done <<< "$modifiers"
+    fi
+
+    if [[ -n "$habitat_line" ]]; then
+        if [[ "$habitat_line" == *"[boosted]" ]]; then
+            echo -e "\033[1;32m📍 Habitat: $POKEMON_HABITAT\033[0m \033[1;33m[Boosted]\033[0m"
+        else
+            echo -e "\033[2m📍 Habitat: $POKEMON_HABITAT\033[0m"
+        fi
+    fi
+
+    # Display the Pokemon sprite (shiny version if shiny)
+    if [[ "$is_shiny" == "true" ]]; then
+        catch-pokemon sprite "$display_name" --shiny 2>/dev/null
+    else
+        catch-pokemon sprite "$display_name" 2>/dev/null
+    fi
+
     # Display type
     if [[ -n "$pokemon_type" ]]; then
         echo -e "$pokemon_type"
@@ -106,7 +187,7 @@ pokemon_encounter() {
         fi
     fi
 
-    echo -e "\033[2mUse 'catch' to attempt capture!\033[0m"
+    echo -e "\033[2mUse 'catch' to attempt capture, or 'fight' to weaken it first!\033[0m"
 }
 
 # --- CATCH MECHANICS ---
@@ -141,6 +222,13 @@ catch() {
         return 1
     fi
 
+    # Optionally feed a berry with the throw
+    local berry="$1"
+    if [[ -n "$berry" && "$berry" != "razz" && "$berry" != "nanab" && "$berry" != "pinap" ]]; then
+        echo -e "\033[1;31m❌ Unknown berry '$berry'. Use razz, nanab or pinap.\033[0m"
+        return 1
+    fi
+
     # Attempt to catch the current pokemon
     echo -e "\033[1;36m🎯 Attempting to catch $CURRENT_WILD_POKEMON...\033[0m"
 
@@ -155,8 +243,21 @@ catch() {
     if [[ "$POKEMON_IS_SHINY" == "true" ]]; then
         catch_cmd="$catch_cmd --shiny"
     fi
+    if [[ -n "$POKEMON_FORM" ]]; then
+        catch_cmd="$catch_cmd --form $POKEMON_FORM"
+    fi
+    if [[ -n "$POKEMON_HABITAT" ]]; then
+        catch_cmd="$catch_cmd --habitat $(printf '%q' "$POKEMON_HABITAT")"
+    fi
+    if [[ -n "$berry" ]]; then
+        catch_cmd="$catch_cmd --berry $berry"
+    fi
 
-    # Run the command with tee to show output live AND capture it
+    # Run the command with tee to show output live AND capture it. tee hides
+    # the terminal from catch-pokemon, so tell it images will reach one.
+    if [[ -t 1 ]]; then
+        catch_cmd="CATCH_POKEMON_TTY=1 $catch_cmd"
+    fi
     eval "$catch_cmd" 2>&1 | tee "$temp_output"
     local catch_result=${PIPESTATUS[0]}
 
@@ -164,6 +265,11 @@ catch() {
     local catch_output=$(cat "$temp_output")
     rm -f "$temp_output"
 
+    # No ball was thrown without the berry, so the attempt doesn't count
+    if echo "$catch_output" | grep "You don't have a" > /dev/null; then
+        return 1
+    fi
+
     # Check if the Pokemon ran away based on the CLI output
     if echo "$catch_output" | grep -i "ran away\|broke free and ran away" > /dev/null; then
         echo -e "\033[1;31m💨 The Pokemon has fled! No more attempts possible this session.\033[0m"
@@ -185,6 +291,42 @@ catch() {
     return $catch_result
 }
 
+# Battle the current wild Pokemon before throwing
+# Usage: fight [attack|sleep|paralyze] [team pokemon]
+fight() {
+    if [[ -z "$CURRENT_WILD_POKEMON" || -z "$POKEMON_SESSION_TOKEN" ]]; then
+        echo -e "\033[1;31m❌ No wild Pokemon to fight!\033[0m"
+        return 1
+    fi
+
+    if [[ "$POKEMON_ESCAPED" == "true" || "$POKEMON_RAN_AWAY" == "true" || "$POKEMON_CAUGHT" == "true" ]]; then
+        echo -e "\033[1;31m💨 This encounter is over. Open a new terminal for a new encounter.\033[0m"
+        return 1
+    fi
+
+    local fight_cmd="catch-pokemon fight $CURRENT_WILD_POKEMON --move ${1:-attack} --attempt $POKEMON_ATTEMPT --token $POKEMON_SESSION_TOKEN"
+    if [[ -n "$2" ]]; then
+        fight_cmd="$fight_cmd --with $2"
+    fi
+    if [[ -n "$POKEMON_FORM" ]]; then
+        fight_cmd="$fight_cmd --form $POKEMON_FORM"
+    fi
+
+    local fight_output
+    fight_output=$(eval "$fight_cmd" 2>&1)
+    local fight_result=$?
+    echo "$fight_output"
+
+    if echo "$fight_output" | grep -i "fainted\|ran away" > /dev/null; then
+        echo -e "\033[1;31m💨 The encounter is over. No more attempts possible this session.\033[0m"
+        export POKEMON_ESCAPED=true
+        export POKEMON_RAN_AWAY=true
+        export CURRENT_WILD_POKEMON=""
+    fi
+
+    return $fight_result
+}
+
 # --- PC SHORTCUT ---
 
 # View your Pokemon collection (shortcut for catch-pokemon pc)
@@ -294,7 +436,8 @@ pokemon_check() {
 
 pokemon_help() {
     echo -e "\033[1;36m🎮 Pokemon Catching System Commands:\033[0m"
-    echo -e "  \033[1;33mcatch\033[0m               - Attempt to catch the current wild Pokemon"
+    echo -e "  \033[1;33mcatch [berry]\033[0m       - Attempt to catch the current wild Pokemon (razz, nanab, pinap)"
+    echo -e "  \033[1;33mfight [move] [name]\033[0m - Weaken it first (attack, sleep, paralyze)"
     echo -e "  \033[1;33mpc\033[0m                  - View your Pokemon collection"
     echo -e "  \033[1;33mpokedex\033[0m             - Browse the full Pokedex"
     echo -e "  \033[1;33mpokemon_status\033[0m      - Show current Pokemon status"
@@ -303,13 +446,60 @@ pokemon_help() {
     echo -e "  \033[1;33mpokemon_clear\033[0m       - Clear current Pokemon (for testing)"
     echo -e "  \033[1;33mpokemon_help\033[0m        - Show this help message"
     echo ""
+    echo -e "\033[2mToday's mass outbreak: catch-pokemon events\033[0m"
+    echo -e "\033[2mHunt a shiny: catch-pokemon hunt <species>\033[0m"
+    echo -e "\033[2mYour items: catch-pokemon bag\033[0m"
+    echo -e "\033[2mEvolve a Pokemon: catch-pokemon evolve <name>\033[0m"
+    echo -e "\033[2mHatch eggs: catch-pokemon eggs\033[0m"
+    echo -e "\033[2mToday's research tasks: catch-pokemon research\033[0m"
+    echo -e "\033[2mYour buddy in your prompt: catch-pokemon buddy\033[0m"
+    echo -e "\033[2mEncounters too frequent? See: catch-pokemon schedule --help\033[0m"
+    echo ""
     echo -e "\033[2mNote: Pokemon may escape based on CLI behavior!\033[0m"
     echo -e "\033[2mGame ends when Pokemon is caught or runs away.\033[0m"
     echo -e "\033[2mOwnership status is shown when wild Pokemon appear.\033[0m"
 }
 
+# --- ENCOUNTER SCHEDULING ---
+# The catch-pokemon binary decides when encounters happen (frequency, quiet
+# hours, command triggers) so every shell shares the same schedule.
+# Configure it with: catch-pokemon schedule --help
+
+# Prompt hook: counts commands and asks the scheduler for an encounter.
+# Only installed when the command trigger is on (schedule --after-commands).
+_pokemon_prompt_hook() {
+    # The first prompt after sourcing is not a command
+    if [[ -z "$_POKEMON_HOOK_ARMED" ]]; then
+        _POKEMON_HOOK_ARMED=1
+        return
+    fi
+
+    # Don't replace a wild Pokemon that can still be caught
+    if [[ -n "$CURRENT_WILD_POKEMON" && "$POKEMON_RAN_AWAY" != "true" && "$POKEMON_CAUGHT" != "true" ]]; then
+        return
+    fi
+
+    if command -v catch-pokemon &>/dev/null && catch-pokemon schedule --check prompt 2>/dev/null; then
+        pokemon_encounter
+    fi
+}
+
+if command -v catch-pokemon &>/dev/null && catch-pokemon schedule --commands-enabled 2>/dev/null; then
+    if [[ -n "$ZSH_VERSION" ]]; then
+        autoload -Uz add-zsh-hook 2>/dev/null && add-zsh-hook precmd _pokemon_prompt_hook
+    elif [[ -n "$BASH_VERSION" && "$PROMPT_COMMAND" != *_pokemon_prompt_hook* ]]; then
+        PROMPT_COMMAND="_pokemon_prompt_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+    fi
+fi
+
 # --- AUTO ENCOUNTER ON SHELL START ---
-# Trigger a Pokemon encounter when this file is sourced (new terminal)
+# Ask the scheduler whether this new terminal gets an encounter
 if [[ -z "$CURRENT_WILD_POKEMON" ]]; then
-    pokemon_encounter
+    if command -v catch-pokemon &>/dev/null; then
+        if catch-pokemon schedule --check shell 2>/dev/null; then
+            pokemon_encounter
+        fi
+    else
+        pokemon_encounter
+    fi
 fi
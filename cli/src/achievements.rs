@@ -0,0 +1,370 @@
+use chrono::{DateTime, Local};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{stdout, Write};
+
+use crossterm::{
+    cursor, terminal, ExecutableCommand,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+};
+
+use crate::battle::BattleHistory;
+use crate::candy::CandyLedger;
+use crate::catch::encounter_pool;
+use crate::display::progress_bar;
+use crate::models::{PcStorage, Pokedex, PokemonData, ACHIEVEMENTS, POKEMON_DATA};
+use crate::storage::{get_achievements_path, load_signed, save_signed};
+
+/// Width of the progress bars, in cells
+const BAR_WIDTH: usize = 20;
+
+/// What an achievement asks for. Loaded from data/achievements.json.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Rule {
+    /// Pokemon in the PC
+    Catches { goal: u64 },
+    /// Species caught in the Pokedex
+    Species { goal: u64 },
+    Shinies { goal: u64 },
+    /// Every species of a type in the encounter pool caught
+    TypeComplete {
+        #[serde(rename = "type")]
+        type_name: String,
+    },
+    /// A Pokemon of this category in the PC
+    Category { category: String },
+    /// A Pokemon of this category caught with this ball, e.g. "Poké Ball"
+    CategoryWithBall { category: String, ball: String },
+    /// Ranked battles played to the end
+    Battles { goal: u64 },
+    BattlesWon { goal: u64 },
+    /// Pokemon transferred to the Professor
+    Transferred { goal: u64 },
+    Evolutions { goal: u64 },
+    Hatched { goal: u64 },
+    /// Different habitats with a catch
+    Habitats { goal: u64 },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub badge: String,
+    pub description: String,
+    rule: Rule,
+}
+
+fn achievements() -> Vec<Achievement> {
+    serde_json::from_str(ACHIEVEMENTS).unwrap_or_default()
+}
+
+/// Everything the rules are checked against
+struct Records {
+    storage: PcStorage,
+    pokedex: Pokedex,
+    pokemon_db: HashMap<String, PokemonData>,
+    battles: BattleHistory,
+    transferred: u64,
+}
+
+impl Records {
+    fn load() -> Option<Self> {
+        Some(Records {
+            storage: PcStorage::load_verified().ok()?,
+            pokedex: Pokedex::load(),
+            pokemon_db: serde_json::from_str(POKEMON_DATA).ok()?,
+            battles: BattleHistory::load(),
+            transferred: CandyLedger::load().transferred,
+        })
+    }
+
+    fn category(&self, name: &str) -> Option<&str> {
+        self.pokemon_db.get(&name.to_lowercase().replace('-', "_")).map(|d| d.category.as_str())
+    }
+}
+
+impl Rule {
+    /// Progress toward the rule as (current, goal)
+    fn progress(&self, records: &Records) -> (u64, u64) {
+        let pc = &records.storage.pokemon;
+        let count = |n: usize, goal: u64| (n as u64, goal);
+        match self {
+            Rule::Catches { goal } => count(pc.len(), *goal),
+            Rule::Species { goal } => count(records.pokedex.entries.values().filter(|e| e.caught).count(), *goal),
+            Rule::Shinies { goal } => count(pc.iter().filter(|p| p.shiny).count(), *goal),
+            Rule::TypeComplete { type_name } => {
+                let species: Vec<String> = encounter_pool(&records.pokemon_db)
+                    .iter()
+                    .filter(|s| s.form.is_none() && s.types.contains(type_name))
+                    .map(|s| s.name.replace('_', "-"))
+                    .collect();
+                let caught = species
+                    .iter()
+                    .filter(|name| records.pokedex.entries.get(*name).is_some_and(|e| e.caught))
+                    .count();
+                (caught as u64, species.len() as u64)
+            }
+            Rule::Category { category } => {
+                let found = pc.iter().any(|p| records.category(&p.name) == Some(category.as_str()));
+                (found as u64, 1)
+            }
+            Rule::CategoryWithBall { category, ball } => {
+                let found = pc
+                    .iter()
+                    .any(|p| p.ball_used == *ball && records.category(&p.name) == Some(category.as_str()));
+                (found as u64, 1)
+            }
+            Rule::Battles { goal } => count(records.battles.battles.len(), *goal),
+            Rule::BattlesWon { goal } => count(records.battles.wins(), *goal),
+            Rule::Transferred { goal } => (records.transferred, *goal),
+            Rule::Evolutions { goal } => count(pc.iter().map(|p| p.evolutions.len()).sum(), *goal),
+            Rule::Hatched { goal } => count(pc.iter().filter(|p| p.ball_used == "Egg").count(), *goal),
+            Rule::Habitats { goal } => {
+                let habitats: BTreeSet<&str> = pc.iter().filter_map(|p| p.habitat.as_deref()).collect();
+                count(habitats.len(), *goal)
+            }
+        }
+    }
+}
+
+/// Achievements earned and when
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AchievementState {
+    #[serde(default)]
+    pub unlocked: BTreeMap<String, DateTime<Local>>,
+}
+
+impl AchievementState {
+    pub fn load() -> Self {
+        load_signed(&get_achievements_path(), "Achievements").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_achievements_path(), self)
+    }
+}
+
+/// Unlock every achievement whose rule now passes, returning the new ones
+fn unlock(state: &mut AchievementState, records: &Records) -> Vec<Achievement> {
+    let now = Local::now();
+    let mut unlocked = Vec::new();
+    for achievement in achievements() {
+        if state.unlocked.contains_key(&achievement.id) {
+            continue;
+        }
+        let (current, goal) = achievement.rule.progress(records);
+        if goal > 0 && current >= goal {
+            state.unlocked.insert(achievement.id.clone(), now);
+            unlocked.push(achievement);
+        }
+    }
+    unlocked
+}
+
+/// Unlock achievements without printing, for callers inside a TUI.
+/// Returns the names of the new ones.
+pub fn unlock_quietly() -> Vec<String> {
+    let Some(records) = Records::load() else {
+        return Vec::new();
+    };
+    let mut state = AchievementState::load();
+    let unlocked = unlock(&mut state, &records);
+    if !unlocked.is_empty() {
+        let _ = state.save();
+    }
+    unlocked.into_iter().map(|a| a.name).collect()
+}
+
+/// Check the rules after a catch, release or battle and announce new unlocks
+pub fn check_achievements() {
+    let Some(records) = Records::load() else {
+        return;
+    };
+    let mut state = AchievementState::load();
+    let unlocked = unlock(&mut state, &records);
+    if unlocked.is_empty() {
+        return;
+    }
+    if let Err(e) = state.save() {
+        eprintln!("{}", format!("Could not save achievements: {}", e).red());
+        return;
+    }
+    for achievement in &unlocked {
+        println!(
+            "{} {} {} {}",
+            "Achievement unlocked!".yellow().bold(),
+            achievement.badge,
+            achievement.name.green().bold(),
+            format!("({})", achievement.description).dimmed()
+        );
+    }
+}
+
+/// One line of the achievements list
+struct Row {
+    achievement: Achievement,
+    current: u64,
+    goal: u64,
+    unlocked_at: Option<DateTime<Local>>,
+}
+
+fn format_row(row: &Row) -> String {
+    let a = &row.achievement;
+    match row.unlocked_at {
+        Some(at) => format!(
+            "{} {:<24} {} {}",
+            a.badge,
+            a.name.green().bold(),
+            progress_bar(row.goal, row.goal, BAR_WIDTH).green(),
+            format!("Unlocked {}", at.format("%Y-%m-%d")).dimmed()
+        ),
+        None => format!(
+            "{} {:<24} {} {}",
+            "· ".dimmed(),
+            a.name,
+            progress_bar(row.current, row.goal, BAR_WIDTH).yellow(),
+            format!("{}/{}", row.current.min(row.goal), row.goal).dimmed()
+        ),
+    }
+}
+
+/// Browse achievements and their progress, or print them with `list`
+pub fn achievements_command(list: bool) {
+    let Some(records) = Records::load() else {
+        eprintln!("{}", "PC integrity check FAILED. Run 'catch-pokemon verify' for details.".red().bold());
+        return;
+    };
+    let mut state = AchievementState::load();
+    if !unlock(&mut state, &records).is_empty() {
+        let _ = state.save();
+    }
+
+    let rows: Vec<Row> = achievements()
+        .into_iter()
+        .map(|achievement| {
+            let (current, goal) = achievement.rule.progress(&records);
+            let unlocked_at = state.unlocked.get(&achievement.id).copied();
+            Row { achievement, current, goal, unlocked_at }
+        })
+        .collect();
+    let unlocked = rows.iter().filter(|r| r.unlocked_at.is_some()).count();
+
+    if list {
+        println!("{}", "  Achievements".cyan().bold());
+        println!("{}", "  ════════════".cyan());
+        println!();
+        for row in &rows {
+            println!("  {}", format_row(row));
+        }
+        println!();
+        println!("  {}", format!("{}/{} unlocked", unlocked, rows.len()).yellow());
+        return;
+    }
+
+    if let Err(e) = run_tui(&rows, unlocked) {
+        eprintln!("TUI error: {}", e);
+    }
+}
+
+fn run_tui(rows: &[Row], unlocked: usize) -> Result<(), Box<dyn std::error::Error>> {
+    stdout().execute(terminal::EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    stdout().execute(cursor::Hide)?;
+
+    let result = tui_loop(rows, unlocked);
+
+    stdout().execute(cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    stdout().execute(terminal::LeaveAlternateScreen)?;
+    result
+}
+
+fn tui_loop(rows: &[Row], unlocked: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let filters = ["All", "Unlocked", "In progress"];
+    let mut filter = 0;
+    let mut selected = 0usize;
+    let mut scroll = 0usize;
+
+    loop {
+        let shown: Vec<&Row> = rows
+            .iter()
+            .filter(|r| match filter {
+                1 => r.unlocked_at.is_some(),
+                2 => r.unlocked_at.is_none(),
+                _ => true,
+            })
+            .collect();
+        if selected >= shown.len() {
+            selected = shown.len().saturating_sub(1);
+        }
+
+        let (tw, th) = terminal::size()?;
+        let tw = tw as usize;
+        // Header, separator, two detail lines, separator, footer
+        let list_height = (th as usize).saturating_sub(7);
+        if selected >= scroll + list_height {
+            scroll = selected + 1 - list_height;
+        }
+        if selected < scroll {
+            scroll = selected;
+        }
+
+        stdout().execute(cursor::MoveTo(0, 0))?;
+        print!(
+            " {} | {} | {}\x1B[K\r\n",
+            "Achievements".cyan().bold(),
+            format!("{}/{} unlocked", unlocked, rows.len()).yellow(),
+            format!("Showing: {}", filters[filter]).dimmed()
+        );
+        print!(" {}\x1B[K\r\n", "─".repeat(tw.saturating_sub(2)).dimmed());
+
+        for i in 0..list_height {
+            let idx = scroll + i;
+            match shown.get(idx) {
+                Some(row) => {
+                    let arrow = if idx == selected { ">".cyan().bold().to_string() } else { " ".to_string() };
+                    print!(" {} {}\x1B[K\r\n", arrow, format_row(row));
+                }
+                None => print!("\x1B[K\r\n"),
+            }
+        }
+
+        print!(" {}\x1B[K\r\n", "─".repeat(tw.saturating_sub(2)).dimmed());
+        match shown.get(selected) {
+            Some(row) => {
+                let a = &row.achievement;
+                print!(" {} {}\x1B[K\r\n", a.badge, a.name.green().bold());
+                print!(
+                    " {} {}\x1B[K\r\n",
+                    a.description,
+                    format!("({}/{})", row.current.min(row.goal), row.goal).dimmed()
+                );
+            }
+            None => print!("\x1B[K\r\n\x1B[K\r\n"),
+        }
+        print!(" {}\x1B[K\r\n", "─".repeat(tw.saturating_sub(2)).dimmed());
+        print!(" {}\x1B[K", "↑↓ Navigate | Tab: Filter | Q: Quit".dimmed());
+        stdout().flush()?;
+
+        if let Ok(Event::Key(KeyEvent { code, modifiers, .. })) = event::read() {
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Tab => {
+                    filter = (filter + 1) % filters.len();
+                    selected = 0;
+                    scroll = 0;
+                }
+                KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') if selected + 1 < shown.len() => selected += 1,
+                KeyCode::Home => selected = 0,
+                KeyCode::End => selected = shown.len().saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
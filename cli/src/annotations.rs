@@ -0,0 +1,212 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::models::{form_name, CaughtPokemon, PcStorage};
+use crate::storage::{get_annotations_path, load_signed, save_signed};
+
+const MAX_NICKNAME_LEN: usize = 20;
+
+/// What a player wrote about one caught Pokemon
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Locked Pokemon can't be transferred
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl Annotation {
+    fn is_empty(&self) -> bool {
+        *self == Annotation::default()
+    }
+}
+
+/// Annotations live beside the PC rather than in it, so writing a nickname
+/// never touches catch signatures.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Annotations {
+    #[serde(default)]
+    entries: BTreeMap<String, Annotation>,
+}
+
+/// Key for a PC entry. The catch time survives re-signing and evolution,
+/// unlike the entry's position or signature.
+pub fn entry_key(pokemon: &CaughtPokemon) -> String {
+    pokemon.caught_at.to_rfc3339()
+}
+
+impl Annotations {
+    pub fn load() -> Self {
+        load_signed(&get_annotations_path(), "Annotations").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_annotations_path(), self)
+    }
+
+    pub fn get(&self, pokemon: &CaughtPokemon) -> Option<&Annotation> {
+        self.entries.get(&entry_key(pokemon))
+    }
+
+    pub fn is_locked(&self, pokemon: &CaughtPokemon) -> bool {
+        self.get(pokemon).is_some_and(|a| a.locked)
+    }
+
+    pub fn nickname(&self, pokemon: &CaughtPokemon) -> Option<&str> {
+        self.get(pokemon).and_then(|a| a.nickname.as_deref())
+    }
+
+    /// Drop the annotations of Pokemon that left the PC for good
+    pub fn forget(&mut self, released: &[CaughtPokemon]) {
+        for pokemon in released {
+            self.entries.remove(&entry_key(pokemon));
+        }
+    }
+
+    /// Index of the PC entry with this nickname
+    pub fn find_nickname(&self, storage: &PcStorage, nickname: &str) -> Option<usize> {
+        storage
+            .pokemon
+            .iter()
+            .position(|p| self.nickname(p).is_some_and(|n| n.eq_ignore_ascii_case(nickname)))
+    }
+
+    /// Nickname for a team member: the one on the Pokemon whose stats the
+    /// team uses, or else any nicknamed Pokemon of that species
+    pub fn team_nickname(&self, storage: &PcStorage, name: &str, shiny: bool) -> Option<&str> {
+        let key = name.to_lowercase().replace('-', "_");
+        let mut matching: Vec<&CaughtPokemon> = storage
+            .pokemon
+            .iter()
+            .filter(|p| p.name.to_lowercase().replace('-', "_") == key && (p.shiny || !shiny))
+            .collect();
+        matching.sort_by_key(|p| std::cmp::Reverse(p.ivs.map(|ivs| ivs.percent())));
+        matching.into_iter().find_map(|p| self.nickname(p))
+    }
+}
+
+/// Changes asked for by `catch-pokemon annotate`
+pub struct AnnotationChanges {
+    pub nickname: Option<String>,
+    pub tags: Vec<String>,
+    pub untags: Vec<String>,
+    pub note: Option<String>,
+    pub lock: bool,
+    pub unlock: bool,
+    pub clear: bool,
+}
+
+impl AnnotationChanges {
+    fn is_empty(&self) -> bool {
+        self.nickname.is_none()
+            && self.tags.is_empty()
+            && self.untags.is_empty()
+            && self.note.is_none()
+            && !self.lock
+            && !self.unlock
+            && !self.clear
+    }
+
+    fn apply(self, annotation: &mut Annotation) {
+        if self.clear {
+            *annotation = Annotation::default();
+        }
+        if let Some(nickname) = self.nickname {
+            let nickname = nickname.trim().to_string();
+            annotation.nickname = (!nickname.is_empty()).then_some(nickname);
+        }
+        annotation.tags.extend(self.tags.iter().map(|t| normalize_tag(t)).filter(|t| !t.is_empty()));
+        for tag in &self.untags {
+            annotation.tags.remove(&normalize_tag(tag));
+        }
+        if let Some(note) = self.note {
+            let note = note.trim().to_string();
+            annotation.notes = (!note.is_empty()).then_some(note);
+        }
+        if self.lock {
+            annotation.locked = true;
+        }
+        if self.unlock {
+            annotation.locked = false;
+        }
+    }
+}
+
+/// Tags are lowercase words joined by hyphens, e.g. "shiny-hunt"
+fn normalize_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<Vec<_>>().join("-").to_lowercase()
+}
+
+fn print_annotation(number: usize, pokemon: &CaughtPokemon, annotation: Option<&Annotation>) {
+    let name = form_name(&pokemon.name.to_lowercase(), pokemon.form.as_deref());
+    let nickname = annotation.and_then(|a| a.nickname.as_deref());
+    let title = match nickname {
+        Some(nickname) => format!("{} ({})", nickname.green().bold(), name),
+        None => name.green().bold().to_string(),
+    };
+    let shiny = if pokemon.shiny { " [Shiny]".yellow().bold().to_string() } else { String::new() };
+    let locked = if annotation.is_some_and(|a| a.locked) { " [Locked]".cyan().bold().to_string() } else { String::new() };
+    println!("  {} {}{}{}", format!("#{}", number).dimmed(), title, shiny, locked);
+    println!("  {}", format!("Caught {} in a {}", pokemon.caught_at.format("%Y-%m-%d %H:%M"), pokemon.ball_used).dimmed());
+    if let Some(annotation) = annotation {
+        if !annotation.tags.is_empty() {
+            let tags: Vec<String> = annotation.tags.iter().map(|t| t.magenta().to_string()).collect();
+            println!("  Tags:  {}", tags.join(", "));
+        }
+        if let Some(notes) = &annotation.notes {
+            println!("  Notes: {}", notes);
+        }
+    }
+}
+
+/// Show or change the annotations of one Pokemon
+pub fn annotate_command(target: String, changes: AnnotationChanges) {
+    if changes.lock && changes.unlock {
+        eprintln!("{}", "Pick one of --lock and --unlock.".red());
+        return;
+    }
+    if changes.nickname.as_ref().is_some_and(|n| n.trim().chars().count() > MAX_NICKNAME_LEN) {
+        eprintln!("{}", format!("Nicknames can be up to {} characters long.", MAX_NICKNAME_LEN).red());
+        return;
+    }
+
+    // Annotations are keyed by PC entries, so never trust a tampered PC
+    let Some(storage) = PcStorage::load_checked() else {
+        return;
+    };
+
+    let mut annotations = Annotations::load();
+    let Some(index) = storage.find_entry(&target).or_else(|| annotations.find_nickname(&storage, &target)) else {
+        match target.parse::<usize>() {
+            Ok(number) => eprintln!("{}", format!("There's no Pokemon #{} in your PC.", number).red()),
+            Err(_) => eprintln!("{}", format!("You don't have a {} in your PC.", target).red()),
+        }
+        return;
+    };
+    let pokemon = &storage.pokemon[index];
+
+    if changes.is_empty() {
+        print_annotation(index + 1, pokemon, annotations.get(pokemon));
+        return;
+    }
+
+    let key = entry_key(pokemon);
+    let mut annotation = annotations.entries.get(&key).cloned().unwrap_or_default();
+    changes.apply(&mut annotation);
+    if annotation.is_empty() {
+        annotations.entries.remove(&key);
+    } else {
+        annotations.entries.insert(key, annotation);
+    }
+    if let Err(e) = annotations.save() {
+        eprintln!("{}", format!("Could not save your annotations: {}", e).red());
+        return;
+    }
+    print_annotation(index + 1, pokemon, annotations.get(pokemon));
+}
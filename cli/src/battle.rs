@@ -1,4 +1,6 @@
+use chrono::{DateTime, Local};
 use colored::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{stdout, Write};
 use std::thread;
@@ -9,17 +11,59 @@ use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
 };
 
+use crate::achievements::check_achievements;
 use crate::api::{api_get, api_post, get_api_url, get_github_token};
 use crate::display::color_type;
-use crate::models::{BattleTeam, PcStorage, PokemonData, POKEMON_DATA};
+use crate::models::{BattleTeam, Ivs, PcStorage, PokemonData, POKEMON_DATA};
+use crate::storage::{get_battle_history_path, load_signed, save_signed};
+use crate::training::{best_ivs, effective_power, reward, TeamTraining, ROUND_XP, WIN_XP};
 
 // --- Data types ---
 
+/// A ranked battle played to the end
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BattleRecord {
+    pub at: DateTime<Local>,
+    pub opponent: String,
+    pub won: bool,
+    pub our_wins: u64,
+    pub opp_wins: u64,
+}
+
+/// Finished ranked battles, for achievements
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BattleHistory {
+    pub battles: Vec<BattleRecord>,
+}
+
+impl BattleHistory {
+    pub fn load() -> Self {
+        load_signed(&get_battle_history_path(), "Battle history").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_battle_history_path(), self)
+    }
+
+    pub fn wins(&self) -> usize {
+        self.battles.iter().filter(|b| b.won).count()
+    }
+}
+
 struct BattlePokemon {
     name: String,
     types: Vec<String>,
     power_rank: u8,
     shiny: bool,
+    /// Our team's training; 0 for the opponent's Pokemon
+    level: u32,
+    ivs: Option<Ivs>,
+}
+
+impl BattlePokemon {
+    fn power(&self) -> u32 {
+        effective_power(self.power_rank, self.level, self.ivs)
+    }
 }
 
 #[derive(PartialEq)]
@@ -52,6 +96,9 @@ struct BattleContext {
     right_selected: usize,
     right_scroll: usize,
     chosen: Vec<bool>,
+    /// Team members that fought in a round, for the win bonus
+    fought: Vec<bool>,
+    level_ups: Vec<(String, u32)>,
     current_round: usize,
     our_wins: u64,
     opp_wins: u64,
@@ -247,12 +294,17 @@ pub fn battle_tui() {
     println!("  {} Matched against {} (ELO: {})", "OK".green().bold(), opponent_id.magenta().bold(), opp_rating.to_string().yellow());
 
     // Build our team from BattleTeam
+    let training = TeamTraining::load();
     let our_team: Vec<BattlePokemon> = team.pokemon.iter().map(|p| {
         let normalized = p.name.replace("-", "_");
         let (types, power) = pokemon_db.get(&normalized)
             .map(|d| (d.types.clone(), d.power_rank))
             .unwrap_or((vec![], 0));
-        BattlePokemon { name: p.name.clone(), types, power_rank: power, shiny: p.shiny }
+        BattlePokemon {
+            name: p.name.clone(), types, power_rank: power, shiny: p.shiny,
+            level: training.get(&p.name).level(),
+            ivs: best_ivs(&storage, &p.name, p.shiny),
+        }
     }).collect();
 
     // Build opponent PC
@@ -263,11 +315,14 @@ pub fn battle_tui() {
                 types: p["types"].as_array().map(|a| a.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect()).unwrap_or_default(),
                 power_rank: p["power_rank"].as_u64().unwrap_or(0) as u8,
                 shiny: p["shiny"].as_bool().unwrap_or(false),
+                level: 0,
+                ivs: None,
             }
         }).collect())
         .unwrap_or_default();
 
     let chosen = vec![false; our_team.len()];
+    let fought = vec![false; our_team.len()];
 
     let mut ctx = BattleContext {
         state: BattleState::TeamSelection,
@@ -277,7 +332,8 @@ pub fn battle_tui() {
         active_pane: Pane::Left,
         left_selected: 0, left_scroll: 0,
         right_selected: 0, right_scroll: 0,
-        chosen,
+        chosen, fought,
+        level_ups: Vec::new(),
         current_round: 1,
         our_wins: 0, opp_wins: 0,
         last_our_score: 0.0, last_opp_score: 0.0,
@@ -294,6 +350,22 @@ pub fn battle_tui() {
     if let Err(e) = run_battle_tui(&mut ctx) {
         eprintln!("Battle TUI error: {}", e);
     }
+
+    // Best of 5: only battles played to three wins count
+    if ctx.our_wins >= 3 || ctx.opp_wins >= 3 {
+        let mut history = BattleHistory::load();
+        history.battles.push(BattleRecord {
+            at: Local::now(),
+            opponent: ctx.opponent_id.clone(),
+            won: ctx.our_wins > ctx.opp_wins,
+            our_wins: ctx.our_wins,
+            opp_wins: ctx.opp_wins,
+        });
+        if let Err(e) = history.save() {
+            eprintln!("{}", format!("Could not save battle history: {}", e).red());
+        }
+        check_achievements();
+    }
 }
 
 // --- Main TUI loop ---
@@ -350,9 +422,9 @@ fn format_pokemon_cell(p: &BattlePokemon, width: usize, highlight: bool) -> Stri
     let padded = format!("{:<w$}", truncated, w = name_w);
 
     if highlight {
-        format!("\x1B[7m {}{} P:{:<3}\x1B[0m", padded, shiny, p.power_rank)
+        format!("\x1B[7m {}{} P:{:<3}\x1B[0m", padded, shiny, p.power())
     } else {
-        format!(" \x1B[32m{}\x1B[0m{} \x1B[33mP:{:<3}\x1B[0m", padded, shiny, p.power_rank)
+        format!(" \x1B[32m{}\x1B[0m{} \x1B[33mP:{:<3}\x1B[0m", padded, shiny, p.power())
     }
 }
 
@@ -360,6 +432,35 @@ fn selected_count(ctx: &BattleContext) -> usize {
     ctx.chosen.iter().filter(|&&c| c).count()
 }
 
+/// The six that fought this round gain XP if they won it
+fn train_after_round(ctx: &mut BattleContext) {
+    for (fought, &chosen) in ctx.fought.iter_mut().zip(&ctx.chosen) {
+        *fought |= chosen;
+    }
+    if !ctx.last_round_won {
+        return;
+    }
+    let winners: Vec<String> = ctx.our_team.iter().zip(&ctx.chosen)
+        .filter(|(_, &c)| c)
+        .map(|(p, _)| p.name.clone())
+        .collect();
+    let level_ups = reward(&winners, ROUND_XP);
+    apply_level_ups(ctx, level_ups);
+}
+
+/// Show new levels in the selection pane and on the final screen
+fn apply_level_ups(ctx: &mut BattleContext, level_ups: Vec<(String, u32)>) {
+    for (name, level) in level_ups {
+        if let Some(p) = ctx.our_team.iter_mut().find(|p| p.name == name) {
+            p.level = level;
+        }
+        match ctx.level_ups.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = level,
+            None => ctx.level_ups.push((name, level)),
+        }
+    }
+}
+
 // --- Team Selection ---
 
 fn render_selection(ctx: &BattleContext) -> Result<(), Box<dyn std::error::Error>> {
@@ -396,7 +497,7 @@ fn render_selection(ctx: &BattleContext) -> Result<(), Box<dyn std::error::Error
     print!(" {}\x1B[K\r\n", "\x1B[90m─\x1B[0m".repeat(tw.saturating_sub(2)));
 
     // Body
-    let left_name_w = left_width.saturating_sub(12);
+    let left_name_w = left_width.saturating_sub(19);
     let right_name_w = (tw.saturating_sub(left_width + 2)).saturating_sub(16);
 
     for row in 0..list_height {
@@ -426,9 +527,9 @@ fn render_selection(ctx: &BattleContext) -> Result<(), Box<dyn std::error::Error
             }).collect::<Vec<_>>().join("/");
 
             if left_idx == ctx.left_selected && ctx.active_pane == Pane::Left {
-                format!(" {}{}\x1B[7m {}{} P:{:<3}\x1B[0m {}", check, arrow, padded, shiny, p.power_rank, types_str)
+                format!(" {}{}\x1B[7m {}{} Lv{:<3} P:{:<3}\x1B[0m {}", check, arrow, padded, shiny, p.level, p.power(), types_str)
             } else {
-                format!(" {}{} \x1B[32m{}\x1B[0m{} \x1B[33mP:{:<3}\x1B[0m {}", check, arrow, padded, shiny, p.power_rank, types_str)
+                format!(" {}{} \x1B[32m{}\x1B[0m{} \x1B[36mLv{:<3}\x1B[0m \x1B[33mP:{:<3}\x1B[0m {}", check, arrow, padded, shiny, p.level, p.power(), types_str)
             }
         } else {
             format!("{:<w$}", "", w = left_width)
@@ -459,9 +560,9 @@ fn render_selection(ctx: &BattleContext) -> Result<(), Box<dyn std::error::Error
             }).collect::<Vec<_>>().join("/");
 
             if right_idx == ctx.right_selected && ctx.active_pane == Pane::Right {
-                format!(" {}\x1B[7m {}{} P:{:<3}\x1B[0m {}", arrow, padded, shiny, p.power_rank, types_str)
+                format!(" {}\x1B[7m {}{} P:{:<3}\x1B[0m {}", arrow, padded, shiny, p.power(), types_str)
             } else {
-                format!(" {} \x1B[32m{}\x1B[0m{} \x1B[33mP:{:<3}\x1B[0m {}", arrow, padded, shiny, p.power_rank, types_str)
+                format!(" {} \x1B[32m{}\x1B[0m{} \x1B[33mP:{:<3}\x1B[0m {}", arrow, padded, shiny, p.power(), types_str)
             }
         } else {
             String::new()
@@ -564,6 +665,8 @@ fn handle_selection_input(ctx: &mut BattleContext) -> Result<bool, Box<dyn std::
                         .filter(|(_, &c)| c)
                         .map(|(i, _)| {
                             let p = &ctx.our_team[i];
+                            // The server can't check training, so ranked rounds
+                            // are scored on the base power rank
                             serde_json::json!({
                                 "name": p.name, "types": p.types, "power_rank": p.power_rank, "shiny": p.shiny
                             })
@@ -726,6 +829,7 @@ fn handle_waiting_input(ctx: &mut BattleContext) -> Result<bool, Box<dyn std::er
                         opp_score: ctx.last_opp_score,
                         won: ctx.last_round_won,
                     });
+                    train_after_round(ctx);
 
                     ctx.state = BattleState::RoundResults;
                     return Ok(false);
@@ -833,6 +937,12 @@ fn handle_results_input(ctx: &mut BattleContext) -> Result<bool, Box<dyn std::er
         if ctx.our_wins >= 3 || ctx.opp_wins >= 3 {
             if ctx.our_wins >= 3 {
                 ctx.result_message = format!("YOU WIN! Final score: {}-{}", ctx.our_wins, ctx.opp_wins);
+                let fought: Vec<String> = ctx.our_team.iter().zip(&ctx.fought)
+                    .filter(|(_, &f)| f)
+                    .map(|(p, _)| p.name.clone())
+                    .collect();
+                let level_ups = reward(&fought, WIN_XP);
+                apply_level_ups(ctx, level_ups);
             } else {
                 ctx.result_message = format!("YOU LOSE. Final score: {}-{}", ctx.our_wins, ctx.opp_wins);
             }
@@ -910,6 +1020,16 @@ fn render_complete(ctx: &BattleContext) -> Result<(), Box<dyn std::error::Error>
         lines.push(format!(" {}", "═".repeat(tw.saturating_sub(2))));
     }
 
+    // Level ups from rounds won and the win bonus
+    if !ctx.level_ups.is_empty() {
+        lines.push(String::new());
+        for (name, level) in &ctx.level_ups {
+            let line = format!("{} grew to Lv. {}!", name, level);
+            let pad = tw.saturating_sub(line.chars().count()) / 2;
+            lines.push(format!("{}\x1B[1;36m{}\x1B[0m", " ".repeat(pad), line));
+        }
+    }
+
     lines.push(String::new());
     let exit_msg = "Press any key to exit";
     let pad = tw.saturating_sub(exit_msg.len()) / 2;
@@ -0,0 +1,227 @@
+use chrono::Local;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::models::{BattleTeam, PokemonData, POKEMON_DATA};
+use crate::storage::get_prompt_path;
+use crate::training::{TeamTraining, MAX_FRIENDSHIP};
+
+/// How long the prompt shows a wild Pokemon, matching the encounter token
+const WILD_SECS: i64 = 1800;
+
+/// Escapes for wherever the prompt segment ends up
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum PromptFormat {
+    /// Raw ANSI colours (starship custom modules, fish, most status bars)
+    Ansi,
+    /// Colours marked non-printing for PS1 command substitution
+    Bash,
+    /// Colours wrapped in %{ %} for PROMPT
+    Zsh,
+    /// tmux #[fg=...] styles for status-right
+    Tmux,
+    /// No colours
+    Plain,
+}
+
+/// Everything `catch-pokemon prompt` prints, precomputed. Plain JSON: it's
+/// only a copy of signed state for display, and the prompt can't afford to
+/// derive the signing key.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PromptCache {
+    #[serde(default)]
+    pub buddy: Option<String>,
+    /// Buddy's first type, which picks the glyph's colour
+    #[serde(default)]
+    pub buddy_type: Option<String>,
+    #[serde(default)]
+    pub shiny: bool,
+    #[serde(default)]
+    pub friendship: u8,
+    /// Wild Pokemon waiting to be caught, and when it appeared
+    #[serde(default)]
+    pub wild: Option<String>,
+    #[serde(default)]
+    pub wild_at: Option<i64>,
+}
+
+impl PromptCache {
+    pub fn load() -> Self {
+        fs::read_to_string(get_prompt_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = get_prompt_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn wild_now(&self) -> bool {
+        self.wild.is_some() && self.wild_at.is_some_and(|at| Local::now().timestamp() - at < WILD_SECS)
+    }
+}
+
+/// Rebuild the buddy part of the prompt cache from the signed training state
+pub fn refresh_prompt() {
+    let training = TeamTraining::load();
+    let mut cache = PromptCache::load();
+    cache.buddy = training.buddy.clone();
+    cache.buddy_type = None;
+    cache.shiny = false;
+    cache.friendship = 0;
+    if let Some(buddy) = &training.buddy {
+        let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
+        cache.buddy_type = pokemon_db.get(buddy).and_then(|d| d.types.first().cloned());
+        cache.shiny = BattleTeam::load()
+            .pokemon
+            .iter()
+            .any(|p| p.name.to_lowercase().replace('-', "_") == *buddy && p.shiny);
+        cache.friendship = training.get(buddy).friendship;
+    }
+    let _ = cache.save();
+}
+
+/// Show or hide the wild-encounter indicator
+pub fn set_wild(pokemon: Option<&str>) {
+    let mut cache = PromptCache::load();
+    if cache.wild.is_none() && pokemon.is_none() {
+        return;
+    }
+    cache.wild = pokemon.map(str::to_string);
+    cache.wild_at = pokemon.map(|_| Local::now().timestamp());
+    let _ = cache.save();
+}
+
+/// How the buddy feels, from its friendship
+fn mood(friendship: u8) -> (&'static str, &'static str) {
+    match friendship {
+        220.. => ("♥", "ecstatic"),
+        150..=219 => ("♪", "happy"),
+        100..=149 => ("☺", "content"),
+        _ => ("·", "calm"),
+    }
+}
+
+/// ANSI colour code and tmux colour name for a type, like `color_type`
+fn type_colour(t: &str) -> (&'static str, &'static str) {
+    match t {
+        "fire" | "fighting" => ("31", "red"),
+        "water" | "dragon" => ("34", "blue"),
+        "grass" | "bug" => ("32", "green"),
+        "electric" | "ground" | "rock" => ("33", "yellow"),
+        "ice" | "flying" => ("36", "cyan"),
+        "poison" | "psychic" | "ghost" | "fairy" => ("35", "magenta"),
+        "dark" => ("90", "brightblack"),
+        _ => ("37", "white"),
+    }
+}
+
+fn paint(text: &str, colour: (&str, &str), format: PromptFormat) -> String {
+    let (ansi, tmux) = colour;
+    match format {
+        PromptFormat::Ansi => format!("\x1B[{}m{}\x1B[0m", ansi, text),
+        // \[ and \] aren't read from command output, so use the bytes they stand for
+        PromptFormat::Bash => format!("\x01\x1B[{}m\x02{}\x01\x1B[0m\x02", ansi, text),
+        PromptFormat::Zsh => format!("%{{\x1B[{}m%}}{}%{{\x1B[0m%}}", ansi, text),
+        PromptFormat::Tmux => format!("#[fg={}]{}#[default]", tmux, text),
+        PromptFormat::Plain => text.to_string(),
+    }
+}
+
+/// Print the buddy's prompt segment. Reads only the cache, so it stays fast
+/// enough to run on every prompt. Prints nothing without a buddy or a wild
+/// Pokemon.
+pub fn prompt_command(format: PromptFormat, name: bool) {
+    let cache = PromptCache::load();
+    let mut parts = Vec::new();
+    if let Some(buddy) = &cache.buddy {
+        let glyph = if cache.shiny { "✦" } else { "●" };
+        let colour = type_colour(cache.buddy_type.as_deref().unwrap_or(""));
+        let mut segment = paint(glyph, colour, format);
+        segment.push_str(&paint(mood(cache.friendship).0, ("35", "magenta"), format));
+        if name {
+            segment.push(' ');
+            segment.push_str(&buddy.replace('_', "-"));
+        }
+        parts.push(segment);
+    }
+    if cache.wild_now() {
+        parts.push(paint("!", ("1;33", "yellow"), format));
+    }
+    if !parts.is_empty() {
+        println!("{}", parts.join(" "));
+    }
+}
+
+/// Pick a buddy from the battle team, or clear it with None
+pub fn set_buddy(name: Option<String>) {
+    let mut training = TeamTraining::load();
+    match &name {
+        Some(name) => {
+            let normalized = name.to_lowercase().replace('-', "_");
+            if !BattleTeam::load()
+                .pokemon
+                .iter()
+                .any(|p| p.name.to_lowercase().replace('-', "_") == normalized)
+            {
+                println!(
+                    "{}",
+                    format!("{} is not on your battle team. Add it with: catch-pokemon team --add {}", name, name).red()
+                );
+                return;
+            }
+            training.set_buddy(Some(name));
+        }
+        None => training.set_buddy(None),
+    }
+    if let Err(e) = training.save() {
+        eprintln!("{}", format!("Error saving buddy: {}", e).red());
+        return;
+    }
+    refresh_prompt();
+    match name {
+        Some(name) => println!(
+            "{}",
+            format!("{} is now your buddy! It grows friendlier every day you play.", name).green()
+        ),
+        None => println!("{}", "You no longer have a buddy.".green()),
+    }
+}
+
+/// Show the buddy and how to put it in a prompt
+pub fn buddy_command() {
+    let training = TeamTraining::load();
+    let Some(buddy) = &training.buddy else {
+        println!("{}", "You don't have a buddy yet.".yellow());
+        println!("Pick one from your battle team with: catch-pokemon buddy set <name>");
+        return;
+    };
+    let progress = training.get(buddy);
+    let (symbol, feeling) = mood(progress.friendship);
+
+    println!("{}", "  Buddy".cyan().bold());
+    println!("{}", "  ═════".cyan());
+    println!();
+    println!("  {}  {}", buddy.replace('_', "-").green().bold(), format!("Lv. {}", progress.level()).cyan().bold());
+    println!(
+        "  Friendship:    {} {}",
+        progress.hearts().magenta(),
+        format!("({}/{})", progress.friendship, MAX_FRIENDSHIP).dimmed()
+    );
+    println!("  Mood:          {} {}", symbol.magenta(), feeling);
+    println!();
+    println!("{}", "  Show your buddy in your prompt:".dimmed());
+    println!("{}", "    PS1='$(catch-pokemon prompt --format bash) '\"$PS1\"".dimmed());
+    println!("{}", "    PROMPT='$(catch-pokemon prompt --format zsh) '$PROMPT   # with setopt PROMPT_SUBST".dimmed());
+    println!("{}", "    set -g status-right '#(catch-pokemon prompt --format tmux)'".dimmed());
+}
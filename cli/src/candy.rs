@@ -0,0 +1,182 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{Ivs, PcStorage, PokemonData, POKEMON_DATA};
+use crate::storage::{get_candy_path, load_signed, save_signed};
+
+/// Candy the Professor gives for each transferred Pokemon
+pub const CANDY_PER_TRANSFER: u32 = 1;
+
+/// Candy spent to reroll one Pokemon's stats
+pub const REROLL_COST: u32 = 5;
+
+/// Candy per evolution family, earned by transferring Pokemon
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct CandyLedger {
+    /// Balance keyed by the family's first species, e.g. "charmander"
+    pub candy: BTreeMap<String, u32>,
+    /// Pokemon transferred to the Professor, all time
+    pub transferred: u64,
+}
+
+impl CandyLedger {
+    pub fn load() -> Self {
+        load_signed(&get_candy_path(), "Candy ledger").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_candy_path(), self)
+    }
+
+    pub fn balance(&self, family: &str) -> u32 {
+        self.candy.get(family).copied().unwrap_or(0)
+    }
+
+    /// Credit candy for Pokemon transferred to the Professor. Returns the
+    /// candy received.
+    pub fn transfer(&mut self, family: &str, count: usize) -> u32 {
+        let earned = CANDY_PER_TRANSFER * count as u32;
+        *self.candy.entry(family.to_string()).or_default() += earned;
+        self.transferred += count as u64;
+        earned
+    }
+
+    /// Spend candy, returning false if the balance is too low
+    pub fn spend(&mut self, family: &str, amount: u32) -> bool {
+        match self.candy.get_mut(family) {
+            Some(n) if *n >= amount => {
+                *n -= amount;
+                if *n == 0 {
+                    self.candy.remove(family);
+                }
+                true
+            }
+            _ => amount == 0,
+        }
+    }
+}
+
+/// First species of a Pokemon's evolution family in pokemon.json key format,
+/// e.g. "charmander" for Charizard. Accepts display names ("mr-mime").
+pub fn family(species: &str, pokemon_db: &HashMap<String, PokemonData>) -> String {
+    let mut current = species.to_lowercase().replace('-', "_");
+    // Chains are at most three deep; the bound guards against bad data
+    for _ in 0..8 {
+        match pokemon_db.get(&current).and_then(|d| d.evolution.as_ref()) {
+            Some(evolution) => current = evolution.from.clone(),
+            None => break,
+        }
+    }
+    current
+}
+
+/// Display name of a family's candy, e.g. "mr-mime candy"
+pub fn candy_name(family: &str) -> String {
+    format!("{} candy", family.replace('_', "-"))
+}
+
+/// Show candy balances
+pub fn candy_command() {
+    let ledger = CandyLedger::load();
+
+    println!("{}", "  Candy".cyan().bold());
+    println!("{}", "  ═════".cyan());
+    println!();
+    if ledger.candy.is_empty() {
+        println!("  {}", "No candy yet. Transfer duplicates with 'catch-pokemon release <name>'.".dimmed());
+    }
+    for (family, count) in &ledger.candy {
+        println!("  {:<24} {}", candy_name(family).green(), count.to_string().yellow().bold());
+    }
+    println!();
+    println!("  Transferred:   {}", format!("{} Pokemon", ledger.transferred).yellow());
+    println!();
+    println!(
+        "{}",
+        format!(
+            "  Spend candy with 'catch-pokemon evolve' or reroll stats for {} with 'catch-pokemon reroll'.",
+            REROLL_COST
+        )
+        .dimmed()
+    );
+}
+
+/// Spend candy to reroll one Pokemon's individual stats
+pub fn reroll_command(target: String, rng: &mut impl rand::Rng) {
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+
+    let Some(mut storage) = PcStorage::load_checked() else {
+        return;
+    };
+
+    let Some(index) = storage.find_entry(&target) else {
+        match target.parse::<usize>() {
+            Ok(number) => eprintln!("{}", format!("There's no Pokemon #{} in your PC.", number).red()),
+            Err(_) => eprintln!("{}", format!("You don't have a {} in your PC.", target).red()),
+        }
+        return;
+    };
+
+    let name = storage.pokemon[index].name.clone();
+    let family = family(&name, &pokemon_db);
+    let mut ledger = CandyLedger::load();
+    if !ledger.spend(&family, REROLL_COST) {
+        println!(
+            "{}",
+            format!(
+                "Rerolling {}'s stats takes {} {}. You have {}.",
+                name,
+                REROLL_COST,
+                candy_name(&family),
+                ledger.balance(&family)
+            )
+            .red()
+        );
+        return;
+    }
+
+    let old = storage.pokemon[index].ivs;
+    let new = Ivs::roll(rng);
+    storage.pokemon[index].ivs = Some(new);
+    storage.resign_chain();
+    if let Err(e) = storage.save() {
+        eprintln!("{}", format!("SAVE FAILED: {}. Nothing was spent.", e).red().bold());
+        return;
+    }
+    if let Err(e) = ledger.save() {
+        eprintln!("{}", format!("Could not save your candy: {}", e).red());
+    }
+
+    println!("{}", format!("{}'s stats were rerolled!", name).green().bold());
+    match old {
+        Some(old) => println!("  Before: {}", old.to_string().dimmed()),
+        None => println!("  Before: {}", "unknown".dimmed()),
+    }
+    println!("  After:  {}", new.to_string().yellow().bold());
+    println!(
+        "{}",
+        format!("  {} {} left.", ledger.balance(&family), candy_name(&family)).dimmed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_walks_back_to_the_first_stage() {
+        let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap();
+        assert_eq!(family("charizard", &pokemon_db), "charmander");
+        assert_eq!(family("Charmeleon", &pokemon_db), "charmander");
+        assert_eq!(family("mr-mime", &pokemon_db), "mime_jr");
+        assert_eq!(family("pidgey", &pokemon_db), "pidgey");
+        assert_eq!(candy_name(&family("mr-mime", &pokemon_db)), "mime-jr candy");
+    }
+}
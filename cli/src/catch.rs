@@ -4,17 +4,34 @@ use hmac::Mac as HmacMac;
 use rand::Rng;
 use std::collections::HashMap;
 use std::io::{stdout, Write};
-use std::process::Command;
 use std::thread;
 use std::time::Duration;
 
 use crossterm::{cursor, terminal, ExecutableCommand};
 
+use crate::config::Config;
 use crate::crypto::{derive_signing_key, HmacSha256};
+use crate::eggs::Incubator;
 use crate::models::{
-    PcStorage, Pokedex, PokeballType, PokemonData, POKEBALL_CAUGHT, POKEBALL_LEFT,
-    POKEBALL_NOT_CAUGHT, POKEBALL_RIGHT, POKEBALL_STILL, POKEMON_DATA, VALID_POKEMON,
+    form_name, Ivs, PcStorage, Pokedex, PokeballType, PokemonData, StatusCondition, POKEBALL_CAUGHT,
+    POKEBALL_LEFT, POKEBALL_NOT_CAUGHT, POKEBALL_RIGHT, POKEBALL_STILL, POKEMON_DATA, valid_pokemon,
 };
+use crate::events::{active_modifiers, weight_multiplier, Outbreak, OUTBREAK_SHINY_ODDS, OUTBREAK_WEIGHT};
+use crate::graphics;
+use crate::habitat::current_habitat;
+use crate::items::{find_items, Bag, Berry, NANAB_FLEE_FACTOR, RAZZ_CATCH_BONUS};
+use crate::hunt::{has_shiny_charm, HuntState, ShinyOdds, HUNT_SHARE};
+use crate::pity::{is_pity_category, PityState};
+use crate::achievements::check_achievements;
+use crate::encounter_log::{self, encounter_id, LogEvent, Outcome};
+use crate::roaming::{check_unlocks, RoamingState};
+use crate::wild_battle::WildBattle;
+use crate::sprites::{self, print_sprite};
+
+/// One in this many encounters is shiny
+pub const SHINY_ODDS: u32 = 4096;
+/// How long an encounter token can be used to throw
+pub const TOKEN_LIFETIME_SECS: i64 = 30 * 60;
 
 pub fn load_pokeball_art(art_type: &str) -> Vec<String> {
     let content = match art_type {
@@ -46,7 +63,7 @@ pub fn display_pokeball_art(lines: &[String]) {
     stdout().flush().unwrap();
 }
 
-pub fn get_pokemon_catch_rate(pokemon_name: &str) -> u8 {
+pub fn get_pokemon_catch_rate(pokemon_name: &str, form: Option<&str>) -> u8 {
     // Parse the embedded Pokemon data once
     let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
         Ok(data) => data,
@@ -63,7 +80,7 @@ pub fn get_pokemon_catch_rate(pokemon_name: &str) -> u8 {
 
     // Look up the Pokemon in our database
     match pokemon_db.get(&normalized_name) {
-        Some(data) => data.catch_rate,
+        Some(data) => data.form(form).catch_rate,
         None => 120, // Default catch rate for unknown Pokemon
     }
 }
@@ -88,13 +105,179 @@ pub fn get_flee_rate(pokemon_name: &str) -> f32 {
     }
 }
 
-pub fn calculate_catch_chance(pokemon_name: &str, ball: PokeballType) -> f32 {
-    let base_catch_rate = get_pokemon_catch_rate(pokemon_name) as f32;
-    let ball_modifier = ball.catch_modifier();
+/// Conditions of a single throw that feed the catch formula
+#[derive(Debug, Clone, Copy)]
+pub struct ThrowConditions {
+    /// Remaining HP as a fraction of max HP (0.0 - 1.0)
+    pub hp_fraction: f32,
+    pub status: StatusCondition,
+    pub ball: PokeballType,
+    /// Extra catch rate multiplier, e.g. from a Razz Berry
+    pub bonus: f32,
+}
+
+impl Default for ThrowConditions {
+    fn default() -> Self {
+        ThrowConditions { hp_fraction: 1.0, status: StatusCondition::None, ball: PokeballType::Pokeball, bonus: 1.0 }
+    }
+}
+
+/// Outcome of a throw. `shakes` is how many shake checks passed before the
+/// Pokemon broke free, capped at the 3 shakes the ball shows before it clicks.
+pub struct ThrowResult {
+    pub shakes: u8,
+    pub caught: bool,
+    pub critical: bool,
+}
+
+/// Gen III+ modified catch rate:
+/// a = (3·HPmax − 2·HPcur) · catch_rate · ball / (3·HPmax) · status · bonus
+pub fn modified_catch_rate(catch_rate: u8, conditions: &ThrowConditions) -> f32 {
+    let hp = conditions.hp_fraction.clamp(0.0, 1.0);
+    (3.0 - 2.0 * hp) * catch_rate as f32 * conditions.ball.catch_modifier() / 3.0
+        * conditions.status.catch_bonus()
+        * conditions.bonus
+}
+
+/// Shake check threshold out of 65536: b = 65536 / (255 / a)^(3/16)
+fn shake_threshold(a: f32) -> u32 {
+    if a >= 255.0 {
+        65536
+    } else if a <= 0.0 {
+        0
+    } else {
+        (65536.0 / (255.0 / a as f64).powf(0.1875)) as u32
+    }
+}
+
+/// Critical capture multiplier from the number of species caught (Gen V)
+fn critical_capture_multiplier(dex_caught: usize) -> f32 {
+    match dex_caught {
+        n if n > 600 => 2.5,
+        n if n > 450 => 2.0,
+        n if n > 300 => 1.5,
+        n if n > 150 => 1.0,
+        n if n > 30 => 0.5,
+        _ => 0.0,
+    }
+}
+
+/// Critical capture threshold out of 256
+fn critical_threshold(a: f32, dex_caught: usize) -> u32 {
+    (a.min(255.0) * critical_capture_multiplier(dex_caught) / 6.0) as u32
+}
+
+/// Throw a ball: a critical capture needs one shake check to pass, a normal
+/// throw needs four independent checks.
+pub fn throw_ball(catch_rate: u8, conditions: &ThrowConditions, dex_caught: usize, rng: &mut impl Rng) -> ThrowResult {
+    let a = modified_catch_rate(catch_rate, conditions);
+    if a >= 255.0 {
+        return ThrowResult { shakes: 3, caught: true, critical: false };
+    }
+
+    let b = shake_threshold(a);
+    let critical = rng.gen_range(0..256) < critical_threshold(a, dex_caught);
+    let checks = if critical { 1 } else { 4 };
+    let mut passed = 0;
+    while passed < checks && rng.gen_range(0..65536) < b {
+        passed += 1;
+    }
+
+    ThrowResult { shakes: passed.min(3), caught: passed == checks, critical }
+}
+
+/// Exact chance (percent) that a throw catches, including critical captures
+pub fn capture_chance(catch_rate: u8, conditions: &ThrowConditions, dex_caught: usize) -> f32 {
+    let a = modified_catch_rate(catch_rate, conditions);
+    if a >= 255.0 {
+        return 100.0;
+    }
+    let p = shake_threshold(a) as f64 / 65536.0;
+    let crit = critical_threshold(a, dex_caught) as f64 / 256.0;
+    ((crit * p + (1.0 - crit) * p.powi(4)) * 100.0) as f32
+}
+
+/// Catch chance (percent) for a pokemon.json catch_rate against a Pokemon at
+/// full HP with no status, before critical captures
+pub fn catch_chance_for_rate(catch_rate: u8, ball: PokeballType) -> f32 {
+    let conditions = ThrowConditions { ball, ..Default::default() };
+    capture_chance(catch_rate, &conditions, 0)
+}
+
+/// Chance that an encounter is from this species' category and a Poké Ball
+/// catches it. The PC sorts by this to put the rarest catches first.
+pub fn true_odds(data: &PokemonData, pool: &[EncounterSlot]) -> f64 {
+    let total: u32 = pool.iter().map(|s| s.weight).sum();
+    let category: u32 = pool.iter().filter(|s| s.data.category == data.category).map(|s| s.weight).sum();
+    (category as f64 / total as f64) * (catch_chance_for_rate(data.catch_rate, PokeballType::Pokeball) as f64 / 100.0)
+}
+
+/// Rolling flee rate: base + 5% per additional attempt, capped at 80%
+pub fn flee_chance(base_flee: f32, attempt: u32) -> f32 {
+    let flee_bonus = (attempt.saturating_sub(1) as f32) * 5.0;
+    (base_flee + flee_bonus).min(80.0)
+}
+
+/// One species/form combination that can be encountered
+pub struct EncounterSlot<'a> {
+    pub name: &'a String,
+    pub form: Option<&'a String>,
+    pub data: &'a PokemonData,
+    /// Types of this form
+    pub types: &'a [String],
+    /// Catch rate for this form
+    pub catch_rate: u8,
+    /// Encounter weight: the species' catch_rate shared among its slots,
+    /// times SLOT_WEIGHT_SCALE
+    pub weight: u32,
+}
 
-    let modified_rate = (base_catch_rate * ball_modifier).min(255.0);
+/// Divisible by every slot count up to six (Rotom has six), so a species'
+/// weight splits evenly among its forms
+pub const SLOT_WEIGHT_SCALE: u32 = 60;
 
-    (modified_rate / 255.0 * 100.0).min(100.0)
+/// Megas are battle-only forms: they never appear in the wild and the Pokedex
+/// doesn't list them
+pub fn is_battle_only(form: &str) -> bool {
+    form.starts_with("mega")
+}
+
+/// Every encounterable Pokemon and wild form (those with a sprite), sorted by
+/// name so weighted rolls are stable across runs. Each species weighs its
+/// catch_rate in total, however many forms it has.
+pub fn encounter_pool(pokemon_db: &HashMap<String, PokemonData>) -> Vec<EncounterSlot<'_>> {
+    let valid_names = valid_pokemon();
+    // Source builds without embedded sprites can't tell which forms have art
+    let check_form_sprites = !sprites::embedded_names().is_empty();
+
+    let mut names: Vec<&String> = pokemon_db.keys().filter(|n| valid_names.contains(*n)).collect();
+    names.sort();
+
+    let mut pool = Vec::new();
+    for name in names {
+        let data = &pokemon_db[name];
+        let forms: Vec<_> = data
+            .forms
+            .iter()
+            .filter(|(form, _)| !is_battle_only(form))
+            .filter(|(form, _)| {
+                !check_form_sprites || sprites::has_sprite(&form_name(&name.replace('_', "-"), Some(form)))
+            })
+            .collect();
+        let weight = data.catch_rate as u32 * SLOT_WEIGHT_SCALE / (forms.len() as u32 + 1);
+        pool.push(EncounterSlot { name, form: None, data, types: &data.types, catch_rate: data.catch_rate, weight });
+        for (form, form_data) in forms {
+            pool.push(EncounterSlot {
+                name,
+                form: Some(form),
+                data,
+                types: &form_data.types,
+                catch_rate: form_data.catch_rate,
+                weight,
+            });
+        }
+    }
+    pool
 }
 
 pub fn throw_pokeball_animation(ball: PokeballType) {
@@ -177,87 +360,90 @@ pub fn wiggle_animation(wiggle_num: u8, ball: PokeballType, caught: bool) {
     }
 }
 
+/// An encounter handed back by the shell function for a catch attempt
+pub struct Encounter {
+    pub pokemon: String,
+    pub form: Option<String>,
+    pub shiny: bool,
+    pub token: Option<String>,
+    /// Throw number within this encounter, starting at 1
+    pub attempt: u32,
+    /// Habitat the encounter happened in, recorded on the catch
+    pub habitat: Option<String>,
+}
+
 pub fn catch_pokemon(
-    pokemon: String,
+    encounter: Encounter,
+    berry: Option<Berry>,
     skip_animation: bool,
     hide_pokemon: bool,
-    shiny: bool,
-    token: Option<String>,
-    attempt: u32,
+    rng: &mut impl Rng,
 ) {
+    let Encounter { pokemon, form, shiny, token, attempt, habitat } = encounter;
+
     // Validate session token — prevents manual catching
-    match &token {
-        None => {
-            println!(
-                "{}",
-                "You can't catch Pokemon directly! Use 'pokemon_encounter' first, then 'catch'."
-                    .red()
-                    .bold()
-            );
-            return;
-        }
-        Some(t) => {
-            // Token format: "timestamp:hmac_hex"
-            let parts: Vec<&str> = t.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                println!("{}", "Invalid session token.".red());
-                return;
-            }
-            let timestamp: i64 = match parts[0].parse() {
-                Ok(ts) => ts,
-                Err(_) => {
-                    println!("{}", "Invalid session token.".red());
-                    return;
-                }
-            };
+    if !verify_encounter_token(&pokemon, form.as_deref(), token.as_deref()) {
+        return;
+    }
 
-            // Check token is not too old (30 minutes max)
-            let now = Local::now().timestamp();
-            if (now - timestamp).abs() > 1800 {
-                println!(
-                    "{}",
-                    "Session expired. Start a new encounter with 'pokemon_encounter'.".red()
-                );
-                return;
-            }
+    // HP and status left by a wild battle before this throw
+    let battle = WildBattle::for_token(token.as_deref());
+    if battle.as_ref().is_some_and(|b| b.fainted) {
+        println!("{}", format!("The wild {} has fainted. Wait for a new encounter.", pokemon).red());
+        return;
+    }
 
-            // Verify HMAC
-            let token_data = format!("encounter:{}:{}", pokemon.to_lowercase(), timestamp);
-            let key = derive_signing_key();
-            let mut mac = <HmacSha256 as HmacMac>::new_from_slice(&key)
-                .expect("HMAC accepts any key length");
-            mac.update(token_data.as_bytes());
-            let expected = hex::encode(mac.finalize().into_bytes());
+    // Display name includes the form, e.g. "vulpix-alola"
+    let full_name = form_name(&pokemon, form.as_deref());
 
-            if parts[1] != expected {
-                println!("{}", "Invalid session token. Nice try.".red().bold());
-                return;
-            }
+    // Roaming legendaries keep their HP and status between appearances
+    let mut roaming = RoamingState::load();
+    if roaming.active(&pokemon).is_some_and(|r| !r.is_present(token.as_deref())) {
+        return;
+    }
+    let roamer_conditions = roaming.active(&pokemon).map(|r| r.throw_conditions());
+    let is_roamer = roamer_conditions.is_some();
+
+    let mut conditions = roamer_conditions
+        .or_else(|| battle.as_ref().map(|b| b.throw_conditions()))
+        .unwrap_or_default();
+
+    // A berry is used up with the throw, whether or not it works
+    let mut bag = Bag::load();
+    if let Some(berry) = berry {
+        if !bag.take(berry.item()) {
+            println!("{}", format!("You don't have a {}.", berry.item().display_name()).red());
+            return;
+        }
+        if let Err(e) = bag.save() {
+            eprintln!("{}", format!("Could not save your bag: {}", e).red());
+            return;
+        }
+        if berry == Berry::Razz {
+            conditions.bonus = RAZZ_CATCH_BONUS;
         }
     }
-
-    let ball = PokeballType::Pokeball;
-    let catch_chance = calculate_catch_chance(&pokemon, ball);
+    let ball = conditions.ball;
+    let catch_rate = get_pokemon_catch_rate(&pokemon, form.as_deref());
+    // Critical captures get more likely as the Pokedex fills up
+    let dex_caught = Pokedex::load().entries.values().filter(|e| e.caught).count();
+    let catch_chance = capture_chance(catch_rate, &conditions, dex_caught);
 
     if !hide_pokemon {
         println!();
-        println!("A wild {} appeared!", pokemon.green().bold());
-
-        let output = Command::new("pokemon-colorscripts")
-            .args(&["-n", &pokemon, "--no-title"])
-            .output();
+        println!("A wild {} appeared!", full_name.green().bold());
+        print_sprite(&full_name, shiny);
+    }
 
-        if let Ok(result) = output {
-            if result.status.success() {
-                print!("{}", String::from_utf8_lossy(&result.stdout));
-            }
-        }
+    if let Some(berry) = berry {
+        println!();
+        println!("{}", format!("You fed the wild {} a {}.", full_name, berry.item().display_name()).magenta());
     }
 
     println!();
     println!(
         "{}",
-        format!("Throwing {} at {}!", ball.display_name(), pokemon)
+        format!("Throwing {} at {}!", ball.display_name(), full_name)
             .cyan()
             .bold()
     );
@@ -267,23 +453,21 @@ pub fn catch_pokemon(
     );
     println!();
 
-    let mut rng = rand::thread_rng();
-    let catch_roll = rng.gen_range(0.0..100.0);
-    let caught = catch_roll < catch_chance;
+    let throw = throw_ball(catch_rate, &conditions, dex_caught, rng);
+    let caught = throw.caught;
+    // Battle turns count toward the rolling flee rate like throws
+    let attempt = attempt + battle.as_ref().map(|b| b.turns).unwrap_or(0);
 
     if !skip_animation {
         throw_pokeball_animation(ball);
+        if throw.critical {
+            println!("{}", "Critical capture!".yellow().bold());
+        }
 
-        let wiggles = if catch_chance > 90.0 {
-            2
-        } else if catch_chance > 50.0 {
-            3
-        } else {
-            4
-        };
-
-        // Single wiggle animation that handles all wiggles
-        wiggle_animation(wiggles, ball, caught);
+        // The ball shakes once for every shake check that passed
+        wiggle_animation(throw.shakes, ball, caught);
+    } else if throw.critical {
+        println!("{}", "Critical capture!".yellow().bold());
     }
 
     // Clear the animation completely
@@ -292,61 +476,112 @@ pub fn catch_pokemon(
     println!();
 
     if caught {
+        WildBattle::clear();
         println!();
         if shiny {
             println!(
                 "{}",
-                format!("Gotcha! A shiny {} was caught!", pokemon)
+                format!("Gotcha! A shiny {} was caught!", full_name)
                     .yellow()
                     .bold()
             );
         } else {
             println!(
                 "{}",
-                format!("Gotcha! {} was caught!", pokemon).green().bold()
+                format!("Gotcha! {} was caught!", full_name).green().bold()
             );
         }
         println!();
+        if !graphics::print_image(&full_name, shiny, 12) {
+            print_sprite(&full_name, shiny);
+        }
 
         let mut storage = PcStorage::load();
-        storage.add_pokemon(pokemon.clone(), ball, shiny);
+        storage.add_pokemon(pokemon.clone(), ball, shiny, form.clone(), habitat, Ivs::roll(rng));
         if let Err(e) = storage.save() {
             eprintln!("{}", format!("SAVE FAILED: {}. Catch does NOT count!", e).red().bold());
             eprintln!("{}", "Your PC file may be corrupted. Run: catch-pokemon verify".red());
             return;
         } else {
+            encounter_log::record(encounter_id(token.as_deref()), LogEvent::Throw { ball: ball.display_name().to_string(), outcome: Outcome::Caught });
             println!();
             if shiny {
                 println!(
                     "{}",
-                    format!("A shiny {} has been sent to your PC!", pokemon)
+                    format!("A shiny {} has been sent to your PC!", full_name)
                         .yellow()
                         .bold()
                 );
             } else {
-                println!("{} has been sent to your PC!", pokemon.cyan());
+                println!("{} has been sent to your PC!", full_name.cyan());
             }
 
             // Track in Pokedex as caught
             let mut pokedex = Pokedex::load();
-            pokedex.mark_caught(&pokemon);
+            pokedex.mark_caught(&pokemon, form.as_deref());
             let _ = pokedex.save();
+
+            if let Some(roamer) = roaming.active_mut(&pokemon) {
+                roamer.caught = true;
+                if let Err(e) = roaming.save() {
+                    eprintln!("{}", format!("Could not save roaming state: {}", e).red());
+                }
+            }
+            check_unlocks(&pokedex);
+
+            let found = find_items(berry == Some(Berry::Pinap), rng);
+            if !found.is_empty() {
+                for item in &found {
+                    bag.add(*item, 1);
+                    println!("{}", format!("You found a {}!", item.display_name()).cyan());
+                }
+                if let Err(e) = bag.save() {
+                    eprintln!("{}", format!("Could not save your bag: {}", e).red());
+                }
+            }
+
+            // Every 25th catch earns an egg
+            let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
+            let mut incubator = Incubator::load();
+            let milestone = incubator.record_catch(&pokemon_db, rng);
+            if let Err(e) = incubator.save() {
+                eprintln!("{}", format!("Could not save your incubator: {}", e).red());
+            } else if milestone.is_some() {
+                println!(
+                    "{}",
+                    format!("That was catch #{}! You received an Egg. (catch-pokemon eggs)", incubator.catches)
+                        .magenta()
+                        .bold()
+                );
+            }
+            crate::buddy::refresh_prompt();
+            check_achievements();
+            crate::research::check_research();
         }
     } else {
-        // Rolling flee rate: base + 5% per additional attempt, capped at 80%
-        let base_flee = get_flee_rate(&pokemon);
-        let flee_bonus = (attempt.saturating_sub(1) as f32) * 5.0;
-        let flee_rate = (base_flee + flee_bonus).min(80.0);
+        let mut flee_rate = flee_chance(get_flee_rate(&pokemon), attempt);
+        if berry == Some(Berry::Nanab) {
+            flee_rate *= NANAB_FLEE_FACTOR;
+        }
         let run_away_chance = rng.gen_range(0.0..100.0);
-        if run_away_chance < flee_rate {
+        // Roamers never stick around for a second ball
+        let fled = is_roamer || run_away_chance < flee_rate;
+        let outcome = if fled { Outcome::Fled } else { Outcome::BrokeFree };
+        encounter_log::record(encounter_id(token.as_deref()), LogEvent::Throw { ball: ball.display_name().to_string(), outcome });
+        if fled {
+            WildBattle::clear();
+            if let Some(roamer) = roaming.active_mut(&pokemon) {
+                roamer.token = None;
+                let _ = roaming.save();
+            }
             println!(
                 "{}",
-                format!("Oh no! The wild {} broke free and ran away!", pokemon).red()
+                format!("Oh no! The wild {} broke free and ran away!", full_name).red()
             );
         } else {
             println!(
                 "{}",
-                format!("Oh no! The wild {} broke free!", pokemon).red()
+                format!("Oh no! The wild {} broke free!", full_name).red()
             );
 
             // Show what the Pokemon is doing after breaking free
@@ -369,7 +604,72 @@ pub fn catch_pokemon(
     }
 }
 
-pub fn encounter_pokemon(show_pokemon: bool) {
+/// Check an encounter token from the shell function, printing why it was
+/// rejected. Tokens prove the Pokemon came from a real encounter.
+pub fn verify_encounter_token(pokemon: &str, form: Option<&str>, token: Option<&str>) -> bool {
+    match token {
+        None => {
+            println!(
+                "{}",
+                "You can't catch Pokemon directly! Use 'pokemon_encounter' first, then 'catch'."
+                    .red()
+                    .bold()
+            );
+            return false;
+        }
+        Some(t) => {
+            // Token format: "timestamp:hmac_hex"
+            let parts: Vec<&str> = t.splitn(2, ':').collect();
+            if parts.len() != 2 {
+                println!("{}", "Invalid session token.".red());
+                return false;
+            }
+            let timestamp: i64 = match parts[0].parse() {
+                Ok(ts) => ts,
+                Err(_) => {
+                    println!("{}", "Invalid session token.".red());
+                    return false;
+                }
+            };
+
+            // Check token is not too old
+            let now = Local::now().timestamp();
+            if (now - timestamp).abs() > TOKEN_LIFETIME_SECS {
+                println!(
+                    "{}",
+                    "Session expired. Start a new encounter with 'pokemon_encounter'.".red()
+                );
+                return false;
+            }
+
+            // Verify HMAC
+            let token_data = encounter_token_data(pokemon, form, timestamp);
+            let key = derive_signing_key();
+            let mut mac = <HmacSha256 as HmacMac>::new_from_slice(&key)
+                .expect("HMAC accepts any key length");
+            mac.update(token_data.as_bytes());
+            let expected = hex::encode(mac.finalize().into_bytes());
+
+            if parts[1] != expected {
+                println!("{}", "Invalid session token. Nice try.".red().bold());
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Data signed into an encounter token. Forms are included so a token for a
+/// regular encounter can't be used to catch a Mega or regional form.
+fn encounter_token_data(pokemon: &str, form: Option<&str>, timestamp: i64) -> String {
+    match form {
+        Some(form) => format!("encounter:{}:{}:{}", pokemon.to_lowercase(), form, timestamp),
+        None => format!("encounter:{}:{}", pokemon.to_lowercase(), timestamp),
+    }
+}
+
+pub fn encounter_pokemon(show_pokemon: bool, rng: &mut impl Rng) {
     // Parse the Pokemon database
     let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
         Ok(data) => data,
@@ -379,54 +679,134 @@ pub fn encounter_pokemon(show_pokemon: bool) {
         }
     };
 
-    // Load valid pokemon-colorscripts names and filter to only encounterable Pokemon
-    let valid_names: std::collections::HashSet<&str> = VALID_POKEMON
-        .lines()
-        .filter(|l| !l.is_empty())
-        .collect();
-
-    // Build weighted list: only include Pokemon that pokemon-colorscripts supports
-    let pokemon_list: Vec<(&String, &PokemonData)> = pokemon_db
+    // Build weighted list of encounterable Pokemon. Time of day, weekday and
+    // season boost some types. Slot weights are already scaled to keep fractions.
+    let pokemon_list = encounter_pool(&pokemon_db);
+    // Roaming legendaries only show up through their own roll below
+    let mut roaming = RoamingState::load();
+    let modifiers = active_modifiers(Local::now());
+    // The project or directory the player is working in boosts more types
+    let habitat = current_habitat();
+    // Today's mass outbreak, if it's running right now
+    let now = Local::now();
+    let outbreak = Outbreak::for_date(now.date_naive(), &pokemon_list).filter(|o| o.is_active(now));
+    // Long droughts without a rare Pokemon ramp up rare weights (opt-in)
+    let mut pity = PityState::load();
+    let pity_boost = pity.multiplier(&Config::load().pity);
+    // Incense and repels from the bag
+    let mut bag = Bag::load();
+    let mut weights: Vec<u64> = pokemon_list
         .iter()
-        .filter(|(name, _)| valid_names.contains(name.as_str()))
+        .map(|slot| {
+            let habitat_boost = habitat.as_ref().map(|h| h.multiplier(slot.types)).unwrap_or(1.0);
+            let outbreak_boost = match outbreak {
+                Some(ref o) if &o.species == slot.name => OUTBREAK_WEIGHT,
+                _ => 1.0,
+            };
+            if slot.form.is_none() && roaming.is_roaming(slot.name) {
+                return 0;
+            }
+            let pity = if is_pity_category(&slot.data.category) { pity_boost } else { 1.0 };
+            let items = bag.weight_multiplier(slot.types, &slot.data.category);
+            let boost = weight_multiplier(&modifiers, slot.types) * habitat_boost * outbreak_boost * pity * items;
+            (slot.weight as f64 * boost as f64) as u64
+        })
         .collect();
-    let total_weight: u32 = pokemon_list
-        .iter()
-        .map(|(_, data)| data.catch_rate as u32)
-        .sum();
 
-    let mut rng = rand::thread_rng();
+    // A shiny hunt gives the hunted species a fixed share of all encounters
+    let mut hunt = HuntState::load();
+    if let Some(target) = hunt.target.clone() {
+        let is_target = |slot: &EncounterSlot| slot.name.replace('_', "-") == target;
+        let hunted: u64 = pokemon_list.iter().zip(&weights).filter(|(s, _)| is_target(s)).map(|(_, w)| *w).sum();
+        let others: u64 = weights.iter().sum::<u64>() - hunted;
+        if hunted > 0 {
+            let scale = others as f64 * HUNT_SHARE as f64 / (1.0 - HUNT_SHARE as f64) / hunted as f64;
+            for (slot, weight) in pokemon_list.iter().zip(weights.iter_mut()) {
+                if is_target(slot) {
+                    *weight = (*weight as f64 * scale) as u64;
+                }
+            }
+        }
+    }
+    let total_weight = weights.iter().fold(0u64, |total, &w| total.saturating_add(w));
+    if total_weight == 0 {
+        eprintln!("{}", "No wild Pokemon can appear here right now.".yellow());
+        return;
+    }
+
     let roll = rng.gen_range(0..total_weight);
 
-    let mut cumulative: u32 = 0;
-    let mut chosen_name = "";
-    for (name, data) in &pokemon_list {
-        cumulative += data.catch_rate as u32;
+    let mut cumulative: u64 = 0;
+    let mut chosen = &pokemon_list[0];
+    for (slot, weight) in pokemon_list.iter().zip(&weights) {
+        cumulative = cumulative.saturating_add(*weight);
         if roll < cumulative {
-            chosen_name = name;
+            chosen = slot;
             break;
         }
     }
+    // A roaming legendary occasionally takes the encounter's place
+    let roamer = roaming.roll_encounter(rng).map(|r| r.species.clone());
+    if let Some(species) = &roamer {
+        if let Some(slot) = pokemon_list.iter().find(|s| s.form.is_none() && s.name == species) {
+            chosen = slot;
+        }
+    }
+    let chosen_name = chosen.name.as_str();
+    let form = chosen.form.map(|f| f.as_str());
 
-    // Convert internal name format back to display format (underscores to hyphens for pokemon-colorscripts)
+    // Convert internal name format back to display format (underscores to hyphens)
     let display_name = chosen_name.replace('_', "-");
+    let full_name = form_name(&display_name, form);
 
-    // 3% chance of shiny encounter
-    // 1/4096 chance of shiny encounter (0.024%)
-    let is_shiny = rng.gen_range(0u32..4096) == 0;
+    bag.tick_encounter();
+    if let Err(e) = bag.save() {
+        eprintln!("{}", format!("Could not save your bag: {}", e).red());
+    }
+
+    let pity_count = pity.since_rare;
+    pity.record(&display_name, &chosen.data.category);
+    if let Err(e) = pity.save() {
+        eprintln!("{}", format!("Could not save pity counter: {}", e).red());
+    }
+
+    // Consecutive encounters of one species build a chain
+    hunt.record_encounter(&display_name);
+    if let Err(e) = hunt.save() {
+        eprintln!("{}", format!("Could not save hunt: {}", e).red());
+    }
+
+    // Every encounter is a step for eggs and the daycare
+    let mut incubator = Incubator::load();
+    if incubator.is_active() {
+        incubator.step(&pokemon_db);
+        if let Err(e) = incubator.save() {
+            eprintln!("{}", format!("Could not save your incubator: {}", e).red());
+        }
+    }
+    crate::training::walk_buddy();
+    crate::buddy::set_wild(Some(&display_name));
+
+    // 1/4096 chance of shiny encounter (0.024%), much better during an
+    // outbreak, with extra rolls for long chains and the shiny charm
+    let mut pokedex = Pokedex::load();
+    let in_outbreak = outbreak.as_ref().is_some_and(|o| o.species == chosen_name);
+    let base_odds = if in_outbreak { OUTBREAK_SHINY_ODDS } else { SHINY_ODDS };
+    let charm = has_shiny_charm(&pokedex, &pokemon_list);
+    let shiny_odds = ShinyOdds::new(base_odds, hunt.chain_for(&display_name), charm);
+    let is_shiny = rng.gen_range(0..shiny_odds.one_in) == 0;
 
     // Always print the name (for scripting use)
     println!("{}", display_name);
 
     // Track in Pokedex as seen
-    let mut pokedex = Pokedex::load();
-    pokedex.mark_seen(&display_name);
+    pokedex.mark_seen(&display_name, form);
     let _ = pokedex.save();
 
     // Generate session token: HMAC(signing_key, pokemon_name + timestamp)
     // This proves the encounter was real — can't forge without the key
     let timestamp = Local::now().timestamp();
-    let token_data = format!("encounter:{}:{}", display_name, timestamp);
+    let token_data = encounter_token_data(&display_name, form, timestamp);
     let key = derive_signing_key();
     let mut mac =
         <HmacSha256 as HmacMac>::new_from_slice(&key).expect("HMAC accepts any key length");
@@ -436,27 +816,58 @@ pub fn encounter_pokemon(show_pokemon: bool) {
         timestamp,
         hex::encode(mac.finalize().into_bytes())
     );
+    encounter_log::record(
+        timestamp,
+        LogEvent::Encounter { species: display_name.clone(), form: form.map(str::to_string), shiny: is_shiny },
+    );
 
     // Print shiny status and token (for shell function)
     println!("Shiny: {}", is_shiny);
     println!("Token: {}", token);
+    if shiny_odds.bonuses.is_empty() {
+        println!("Shiny odds: 1/{}", shiny_odds.one_in);
+    } else {
+        println!("Shiny odds: 1/{} - {}", shiny_odds.one_in, shiny_odds.bonuses.join(", "));
+    }
+    if let Some(form) = form {
+        println!("Form: {}", form);
+    }
+    for modifier in &modifiers {
+        let boosted = if modifier.multiplier(chosen.types) > 1.0 { " [boosted]" } else { "" };
+        println!("Modifier: {} - {}{}", modifier.name, modifier.description, boosted);
+    }
+    if let Some(o) = outbreak.as_ref().filter(|_| in_outbreak) {
+        println!("Outbreak: until {:02}:00", o.end_hour);
+    }
+    if let Some(r) = roamer.as_deref().and_then(|name| roaming.active_mut(name)).filter(|r| r.species == chosen_name) {
+        r.appearances += 1;
+        r.token = Some(token.clone());
+        let status = if r.status == StatusCondition::None { String::new() } else { format!(" {}", r.status.display_name()) };
+        println!("Roaming: {:.0}% HP{}", r.hp * 100.0, status);
+        let _ = roaming.save();
+    }
+    for effect in &bag.effects {
+        println!("Item: {}", effect.describe(now));
+    }
+    if pity_boost > 1.0 {
+        let boosted = if is_pity_category(&chosen.data.category) { " [boosted]" } else { "" };
+        println!("Pity: {:.1}x after {} encounters{}", pity_boost, pity_count, boosted);
+    }
+    if let Some(ref habitat) = habitat {
+        let boosted = if habitat.multiplier(chosen.types) > 1.0 { " [boosted]" } else { "" };
+        println!("Habitat: {}{}", habitat.name, boosted);
+    }
+    let ready = incubator.ready();
+    if ready > 0 {
+        println!("Egg: {} ready to hatch", ready);
+    }
 
     if show_pokemon {
-        let mut args = vec!["-n", &display_name, "--no-title"];
-        if is_shiny {
-            args.push("-s");
-        }
-
-        let output = Command::new("pokemon-colorscripts").args(&args).output();
-
-        if let Ok(result) = output {
-            if result.status.success() {
-                print!("{}", String::from_utf8_lossy(&result.stdout));
-            }
-        }
+        print_sprite(&full_name, is_shiny);
 
         // Show category and catch info
         if let Some(data) = pokemon_db.get(chosen_name) {
+            let form_data = data.form(form);
             let category_display = match data.category.as_str() {
                 "legendary" => format!("Legendary").red().bold().to_string(),
                 "mythical" => format!("Mythical").magenta().bold().to_string(),
@@ -472,8 +883,8 @@ pub fn encounter_pokemon(show_pokemon: bool) {
             println!("Category: {}", category_display);
 
             // Display types with color coding
-            if !data.types.is_empty() {
-                let type_strings: Vec<String> = data
+            if !form_data.types.is_empty() {
+                let type_strings: Vec<String> = form_data
                     .types
                     .iter()
                     .map(|t| match t.as_str() {
@@ -501,7 +912,7 @@ pub fn encounter_pokemon(show_pokemon: bool) {
                 println!("Type: {}", type_strings.join(" / "));
             }
 
-            let catch_pct = data.catch_rate as f32 / 255.0 * 100.0;
+            let catch_pct = catch_chance_for_rate(form_data.catch_rate, PokeballType::Pokeball);
             println!(
                 "Base catch rate: {}",
                 format!("{:.1}%", catch_pct).bright_yellow().bold()
@@ -509,3 +920,67 @@ pub fn encounter_pokemon(show_pokemon: bool) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn at(hp_fraction: f32, status: StatusCondition) -> ThrowConditions {
+        ThrowConditions { hp_fraction, status, ..Default::default() }
+    }
+
+    #[test]
+    fn modified_rate_follows_hp() {
+        // Full HP gives a third of the catch rate, no HP the whole of it
+        assert_eq!(modified_catch_rate(255, &at(1.0, StatusCondition::None)), 85.0);
+        assert_eq!(modified_catch_rate(255, &at(0.0, StatusCondition::None)), 255.0);
+        assert_eq!(modified_catch_rate(90, &at(1.0, StatusCondition::Sleep)), 75.0);
+    }
+
+    #[test]
+    fn shake_threshold_bounds() {
+        assert_eq!(shake_threshold(0.0), 0);
+        assert_eq!(shake_threshold(255.0), 65536);
+        assert_eq!(shake_threshold(1000.0), 65536);
+        assert!(shake_threshold(10.0) < shake_threshold(100.0));
+    }
+
+    #[test]
+    fn capture_chance_matches_known_values() {
+        let pikachu = catch_chance_for_rate(190, PokeballType::Pokeball);
+        let legendary = catch_chance_for_rate(3, PokeballType::Pokeball);
+        assert!((pikachu - 35.2).abs() < 0.5, "pikachu {}", pikachu);
+        assert!((legendary - 1.57).abs() < 0.05, "legendary {}", legendary);
+        assert_eq!(capture_chance(255, &at(0.0, StatusCondition::None), 0), 100.0);
+    }
+
+    #[test]
+    fn weakening_and_status_raise_the_chance() {
+        let full = capture_chance(45, &at(1.0, StatusCondition::None), 0);
+        let weak = capture_chance(45, &at(0.2, StatusCondition::None), 0);
+        let paralyzed = capture_chance(45, &at(0.2, StatusCondition::Paralysis), 0);
+        let asleep = capture_chance(45, &at(0.2, StatusCondition::Sleep), 0);
+        assert!(full < weak && weak < paralyzed && paralyzed < asleep);
+    }
+
+    #[test]
+    fn critical_captures_need_a_full_dex() {
+        assert_eq!(critical_threshold(100.0, 0), 0);
+        let base = capture_chance(45, &ThrowConditions::default(), 0);
+        let veteran = capture_chance(45, &ThrowConditions::default(), 700);
+        assert!(veteran > base);
+    }
+
+    #[test]
+    fn throws_agree_with_the_exact_chance() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let conditions = at(0.5, StatusCondition::Burn);
+        let throws = 20_000;
+        let caught = (0..throws).filter(|_| throw_ball(45, &conditions, 200, &mut rng).caught).count();
+        let simulated = caught as f32 * 100.0 / throws as f32;
+        let exact = capture_chance(45, &conditions, 200);
+        assert!((simulated - exact).abs() < 1.5, "simulated {} exact {}", simulated, exact);
+    }
+}
@@ -0,0 +1,126 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::storage::get_config_path;
+
+/// User settings stored in config.toml next to the PC storage.
+/// Every section has defaults so a missing or partial file is fine.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    pub encounters: EncounterConfig,
+    pub sprites: SpriteConfig,
+    pub pity: PityConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EncounterConfig {
+    /// Minimum minutes between encounters across all shells (0 = no limit)
+    pub interval_minutes: u32,
+    /// Percent chance that opening a new shell triggers an encounter
+    pub shell_start_chance: f32,
+    /// Local time window with no encounters, e.g. "22:00-08:00" (may wrap midnight)
+    pub quiet_hours: Option<String>,
+    /// Days with no encounters, e.g. ["sat", "sun"]
+    pub quiet_days: Vec<String>,
+    /// Trigger an encounter from the prompt hook after this many commands (0 = off)
+    pub after_commands: u32,
+}
+
+impl Default for EncounterConfig {
+    fn default() -> Self {
+        EncounterConfig {
+            interval_minutes: 0,
+            shell_start_chance: 100.0,
+            quiet_hours: None,
+            quiet_days: Vec::new(),
+            after_commands: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SpriteConfig {
+    /// Colour mode for sprites: "auto", "truecolor", "256" or "mono"
+    pub color: String,
+    /// Image protocol for full-resolution sprites: "auto", "kitty", "sixel", "iterm" or "ansi"
+    pub protocol: String,
+}
+
+impl Default for SpriteConfig {
+    fn default() -> Self {
+        SpriteConfig {
+            color: "auto".to_string(),
+            protocol: "auto".to_string(),
+        }
+    }
+}
+
+/// Opt-in rarity insurance for long droughts without a legendary,
+/// mythical or pseudo-legendary encounter
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PityConfig {
+    pub enabled: bool,
+    /// Encounters without a rare Pokemon before the weights start ramping
+    pub threshold: u32,
+    /// Added to the rare weight multiplier for every encounter past the threshold
+    pub ramp: f32,
+}
+
+/// Largest ramp `stats --pity-ramp` accepts
+pub const MAX_PITY_RAMP: f32 = 10.0;
+
+impl PityConfig {
+    /// config.toml is plain text, so bring hand-edited values back into range
+    fn clamped(mut self) -> Self {
+        self.threshold = self.threshold.max(1);
+        self.ramp = if self.ramp.is_nan() { PityConfig::default().ramp } else { self.ramp.clamp(0.0, MAX_PITY_RAMP) };
+        self
+    }
+}
+
+impl Default for PityConfig {
+    fn default() -> Self {
+        PityConfig {
+            enabled: false,
+            threshold: 150,
+            ramp: 0.1,
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let path = get_config_path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Config::default();
+        };
+
+        match toml::from_str::<Config>(&contents) {
+            Ok(mut config) => {
+                config.pity = config.pity.clamped();
+                config
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    format!("Ignoring invalid config {}: {}", path.display(), e).yellow()
+                );
+                Config::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = get_config_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use aes_gcm::aead::Aead;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use hmac::{Hmac, Mac as HmacMac};
 use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use sha2::Sha256;
 
 use crate::models::{BattleTeam, CaughtPokemon, PcStorage, Pokedex};
@@ -22,14 +24,22 @@ pub const KDF_DOMAIN: &[u8] = b"catch-pokemon:kdf:v1";
 pub const SIGN_DOMAIN: &[u8] = b"catch-pokemon:sign:v1";
 pub const CHAIN_DOMAIN: &[u8] = b"catch-pokemon:chain:v1";
 pub const ENCRYPTION_DOMAIN: &[u8] = b"catch-pokemon:encryption:v1";
+pub const SEEDED_DOMAIN: &[u8] = b"catch-pokemon:seeded:v1";
+pub const STATE_DOMAIN: &[u8] = b"catch-pokemon:state:v1";
+pub const LOG_DOMAIN: &[u8] = b"catch-pokemon:log:v1";
 
 /// Derive signing key from BUILD_SECRET only. No salt.
 /// Same key on every machine with the same binary.
 /// API uses the same BUILD_SECRET to verify.
+/// Seeded development runs mix in SEEDED_DOMAIN, so their PCs and encounter
+/// tokens are rejected everywhere else.
 pub fn derive_signing_key() -> Vec<u8> {
     let mut mac =
         <HmacSha256 as HmacMac>::new_from_slice(&BUILD_SECRET).expect("HMAC accepts any key length");
     mac.update(KDF_DOMAIN);
+    if crate::rng::is_seeded() {
+        mac.update(SEEDED_DOMAIN);
+    }
     let mut key = mac.finalize().into_bytes().to_vec();
 
     for round in 0u32..10_000 {
@@ -163,15 +173,63 @@ pub fn decrypt_battle_team(data: &[u8]) -> Option<BattleTeam> {
     serde_json::from_str(&json_str).ok()
 }
 
-/// Canonical data string for signing (excludes signature and prev_hash fields)
+/// Encrypt a state file as a random nonce followed by the AES-GCM ciphertext
+pub fn encrypt_state<T: Serialize>(state: &T) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let key = derive_encryption_key();
+    let cipher = Aes256Gcm::new_from_slice(&key)?;
+    let json = serde_json::to_string(state)?;
+    let mut rng = rand::thread_rng();
+    let mut nonce_bytes = [0u8; 12];
+    for b in nonce_bytes.iter_mut() {
+        *b = rng.gen();
+    }
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(nonce, json.as_bytes())
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+    let mut output = Vec::with_capacity(12 + ciphertext.len());
+    output.extend_from_slice(&nonce_bytes);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// State files written by encrypt_state never had a plain JSON format, so
+/// any nonce byte is fine here
+pub fn decrypt_state<T: DeserializeOwned>(data: &[u8]) -> Option<T> {
+    if data.len() < 13 {
+        return None;
+    }
+    let key = derive_encryption_key();
+    let cipher = Aes256Gcm::new_from_slice(&key).ok()?;
+    let nonce = Nonce::from_slice(&data[..12]);
+    let plaintext = cipher.decrypt(nonce, &data[12..]).ok()?;
+    let json_str = String::from_utf8(plaintext).ok()?;
+    serde_json::from_str(&json_str).ok()
+}
+
+/// Canonical data string for signing (excludes signature and prev_hash fields).
+/// Optional fields are appended only when set so older entries keep their signatures.
 pub fn entry_canonical_data(entry: &CaughtPokemon) -> String {
-    format!(
+    let mut data = format!(
         "{}|{}|{}|{}",
         entry.name,
         entry.caught_at.to_rfc3339(),
         entry.ball_used,
         entry.shiny
-    )
+    );
+    if let Some(ref form) = entry.form {
+        data.push_str(&format!("|form={}", form));
+    }
+    if let Some(ref habitat) = entry.habitat {
+        data.push_str(&format!("|habitat={}", habitat));
+    }
+    if let Some(ref ivs) = entry.ivs {
+        data.push_str(&format!("|ivs={}/{}/{}", ivs.attack, ivs.defense, ivs.stamina));
+    }
+    for evolution in &entry.evolutions {
+        data.push_str(&format!("|evolved={}@{}", evolution.from, evolution.at.to_rfc3339()));
+    }
+    data
 }
 
 /// Compute the chain hash for an entry (domain-separated)
@@ -193,6 +251,16 @@ pub fn sign_entry(key: &[u8], entry: &CaughtPokemon, prev_hash: &str) -> String
     hex::encode(mac.finalize().into_bytes())
 }
 
+/// HMAC-sign a local state file's JSON so hand edits are detected
+pub fn sign_state(json: &str) -> String {
+    let key = derive_signing_key();
+    let mut mac =
+        <HmacSha256 as HmacMac>::new_from_slice(&key).expect("HMAC accepts any key length");
+    mac.update(STATE_DOMAIN);
+    mac.update(json.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
 /// Verify the entire integrity chain. Returns Ok or an error description.
 pub fn verify_chain(storage: &PcStorage) -> Result<(), String> {
     let key = derive_signing_key();
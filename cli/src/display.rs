@@ -1,14 +1,21 @@
 use colored::*;
 use std::collections::HashMap;
 use std::io::{stdout, Write};
-use std::process::Command;
 
 use crossterm::{
     terminal,
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
 };
 
+use crate::annotations::Annotations;
 use crate::models::PcStorage;
+use crate::sprites::print_sprite;
+
+/// A bar of `width` cells, filled in proportion to `current` out of `goal`
+pub fn progress_bar(current: u64, goal: u64, width: usize) -> String {
+    let filled = (current.min(goal) * width as u64).checked_div(goal).unwrap_or(0) as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
 
 pub fn color_type(t: &str) -> String {
     match t {
@@ -68,7 +75,10 @@ pub fn fuzzy_match(text: &str, pattern: &str) -> bool {
     current_char.is_none()
 }
 
-pub fn interactive_pokemon_search(storage: &PcStorage) -> Result<(), Box<dyn std::error::Error>> {
+pub fn interactive_pokemon_search(
+    storage: &PcStorage,
+    annotations: &Annotations,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Get unique Pokemon names
     let mut pokemon_names: Vec<String> = storage.pokemon
         .iter()
@@ -77,6 +87,19 @@ pub fn interactive_pokemon_search(storage: &PcStorage) -> Result<(), Box<dyn std
     pokemon_names.sort();
     pokemon_names.dedup();
 
+    // Nicknames and tags match too
+    let mut nicknames: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut labels: HashMap<&str, Vec<&str>> = HashMap::new();
+    for p in &storage.pokemon {
+        if let Some(annotation) = annotations.get(p) {
+            if let Some(nickname) = &annotation.nickname {
+                nicknames.entry(&p.name).or_default().push(nickname);
+                labels.entry(&p.name).or_default().push(nickname);
+            }
+            labels.entry(&p.name).or_default().extend(annotation.tags.iter().map(String::as_str));
+        }
+    }
+
     let mut search_term = String::new();
     let mut selected_index = 0;
 
@@ -97,7 +120,10 @@ pub fn interactive_pokemon_search(storage: &PcStorage) -> Result<(), Box<dyn std
         } else {
             pokemon_names
                 .iter()
-                .filter(|name| fuzzy_match(name, &search_term))
+                .filter(|name| {
+                    fuzzy_match(name, &search_term)
+                        || labels.get(name.as_str()).is_some_and(|l| l.iter().any(|l| fuzzy_match(l, &search_term)))
+                })
                 .collect()
         };
 
@@ -122,14 +148,18 @@ pub fn interactive_pokemon_search(storage: &PcStorage) -> Result<(), Box<dyn std
         // Display results with clear highlighting
         let display_count = 8.min(filtered.len());
         for (i, pokemon_name) in filtered.iter().take(display_count).enumerate() {
+            let nickname = match nicknames.get(pokemon_name.as_str()) {
+                Some(names) => format!(" ({})", names.join(", ")),
+                None => String::new(),
+            };
             if i == selected_index {
                 // Bright highlighted selection with background
                 print!("\x1B[1;37;44m"); // Bold white text on blue background
-                println!(" ► {} ", pokemon_name);
+                println!(" ► {}{} ", pokemon_name, nickname);
                 print!("\x1B[0m"); // Reset
             } else {
                 // Regular white text
-                println!("\x1B[37m   {}\x1B[0m", pokemon_name);
+                println!("\x1B[37m   {}\x1B[90m{}\x1B[0m", pokemon_name, nickname);
             }
         }
 
@@ -210,16 +240,10 @@ pub fn show_pokemon_details(pokemon_name: &str, ball_counts: &HashMap<String, us
     println!("{}", format!("=== {} ===", pokemon_name).green().bold());
     println!();
 
-    // Show Pokemon sprite using pokemon-colorscripts
-    let output = Command::new("pokemon-colorscripts")
-        .args(&["-n", pokemon_name, "--no-title"])
-        .output();
-
-    if let Ok(result) = output {
-        if result.status.success() {
-            print!("{}", String::from_utf8_lossy(&result.stdout));
-        }
-    }
+    // Show the shiny sprite if any of them are shiny
+    let has_shiny = storage.pokemon.iter()
+        .any(|p| p.shiny && p.name.to_lowercase() == pokemon_name.to_lowercase());
+    print_sprite(pokemon_name, has_shiny);
 
     println!();
     println!("{}", format!("Total caught: {}", total_count).cyan().bold());
@@ -0,0 +1,409 @@
+use chrono::{DateTime, Local};
+use colored::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::achievements::check_achievements;
+use crate::candy::family;
+use crate::catch::{clear_lines, display_pokeball_art, SHINY_ODDS};
+use crate::graphics;
+use crate::models::{
+    CaughtPokemon, Ivs, PcStorage, PokeballType, Pokedex, PokemonData, EGG_CRACKED, EGG_LEFT, EGG_RIGHT, EGG_STILL,
+    POKEMON_DATA,
+};
+use crate::sprites::print_sprite;
+use crate::storage::{get_incubator_path, load_encrypted, save_encrypted};
+
+/// Eggs the incubator holds at once
+const INCUBATOR_SLOTS: usize = 3;
+/// Every this many catches earns an egg
+const MILESTONE_CATCHES: u64 = 25;
+/// Steps the daycare takes to find an egg
+const DAYCARE_STEPS: u32 = 20;
+
+/// Steps (encounters and new shells) an egg of this category takes to hatch
+fn hatch_steps(category: &str) -> u32 {
+    match category {
+        "baby" | "common" => 10,
+        "uncommon" => 15,
+        "pseudo_legendary" => 30,
+        _ => 20,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EggOrigin {
+    Milestone,
+    Daycare,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Egg {
+    /// Species that hatches, e.g. "pichu"
+    pub species: String,
+    pub origin: EggOrigin,
+    pub steps_left: u32,
+    pub steps_total: u32,
+    pub received_at: DateTime<Local>,
+}
+
+impl Egg {
+    fn new(species: &str, origin: EggOrigin, pokemon_db: &HashMap<String, PokemonData>) -> Self {
+        let key = species.replace('-', "_");
+        let steps = hatch_steps(pokemon_db.get(&key).map(|d| d.category.as_str()).unwrap_or(""));
+        Egg {
+            species: key.replace('_', "-"),
+            origin,
+            steps_left: steps,
+            steps_total: steps,
+            received_at: Local::now(),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.steps_left == 0
+    }
+}
+
+/// Two Pokemon of one family left at the daycare. They're taken out of the
+/// PC while they're here and go back unchanged when withdrawn.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Daycare {
+    pub parents: Vec<CaughtPokemon>,
+    /// Family whose first species hatches from the daycare's eggs
+    pub family: String,
+    pub steps: u32,
+}
+
+/// Eggs, the daycare and the catch count toward the next milestone egg.
+/// Encrypted like the PC, since it holds Pokemon.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Incubator {
+    #[serde(default)]
+    pub eggs: Vec<Egg>,
+    #[serde(default)]
+    pub daycare: Option<Daycare>,
+    #[serde(default)]
+    pub catches: u64,
+}
+
+impl Incubator {
+    pub fn load() -> Self {
+        load_encrypted(&get_incubator_path(), "egg incubator").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_encrypted(&get_incubator_path(), self)
+    }
+
+    pub fn has_room(&self) -> bool {
+        self.eggs.len() < INCUBATOR_SLOTS
+    }
+
+    pub fn ready(&self) -> usize {
+        self.eggs.iter().filter(|e| e.is_ready()).count()
+    }
+
+    /// Whether a step would change anything
+    pub fn is_active(&self) -> bool {
+        self.daycare.is_some() || self.eggs.iter().any(|e| !e.is_ready())
+    }
+
+    /// Count a step toward every egg and the daycare. Returns the species of
+    /// an egg the daycare found, if any.
+    pub fn step(&mut self, pokemon_db: &HashMap<String, PokemonData>) -> Option<String> {
+        for egg in &mut self.eggs {
+            egg.steps_left = egg.steps_left.saturating_sub(1);
+        }
+        let room = self.has_room();
+        let daycare = self.daycare.as_mut()?;
+        // A full incubator pauses the daycare until an egg hatches
+        if !room {
+            return None;
+        }
+        daycare.steps += 1;
+        if daycare.steps < DAYCARE_STEPS {
+            return None;
+        }
+        daycare.steps = 0;
+        let egg = Egg::new(&daycare.family, EggOrigin::Daycare, pokemon_db);
+        let species = egg.species.clone();
+        self.eggs.push(egg);
+        Some(species)
+    }
+
+    /// Count a catch. Every 25th earns an egg holding a random baby Pokemon.
+    pub fn record_catch(&mut self, pokemon_db: &HashMap<String, PokemonData>, rng: &mut impl Rng) -> Option<String> {
+        self.catches += 1;
+        if !self.catches.is_multiple_of(MILESTONE_CATCHES) || !self.has_room() {
+            return None;
+        }
+        let mut babies: Vec<&String> = pokemon_db.iter().filter(|(_, d)| d.category == "baby").map(|(n, _)| n).collect();
+        if babies.is_empty() {
+            return None;
+        }
+        babies.sort();
+        let egg = Egg::new(babies[rng.gen_range(0..babies.len())], EggOrigin::Milestone, pokemon_db);
+        let species = egg.species.clone();
+        self.eggs.push(egg);
+        Some(species)
+    }
+}
+
+/// Count a step for a new shell. Cheap when there's nothing incubating.
+pub fn step_session() {
+    if !get_incubator_path().exists() {
+        return;
+    }
+    let mut incubator = Incubator::load();
+    if !incubator.is_active() {
+        return;
+    }
+    let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
+    incubator.step(&pokemon_db);
+    let _ = incubator.save();
+}
+
+fn egg_art(frame: &str) -> Vec<String> {
+    let content = match frame {
+        "still" => EGG_STILL,
+        "left" => EGG_LEFT,
+        "right" => EGG_RIGHT,
+        "cracked" => EGG_CRACKED,
+        _ => return vec![],
+    };
+    content.lines().map(|line| line.to_string()).collect()
+}
+
+fn hatch_animation() {
+    let still = egg_art("still");
+    let height = still.len();
+
+    println!();
+    display_pokeball_art(&still);
+    thread::sleep(Duration::from_millis(600));
+    for (i, frame) in ["left", "right", "left", "still", "left", "right", "left", "right", "cracked"].iter().enumerate() {
+        clear_lines(height);
+        display_pokeball_art(&egg_art(frame));
+        // The wobbling speeds up as it gets ready to hatch
+        thread::sleep(Duration::from_millis(if i < 4 { 250 } else { 120 }));
+    }
+    thread::sleep(Duration::from_millis(700));
+    clear_lines(height);
+}
+
+fn describe_egg(egg: &Egg) -> String {
+    let origin = match egg.origin {
+        EggOrigin::Milestone => "milestone",
+        EggOrigin::Daycare => "daycare",
+    };
+    if egg.is_ready() {
+        format!("{} {}", "Ready to hatch!".yellow().bold(), format!("({} egg)", origin).dimmed())
+    } else {
+        let done = egg.steps_total - egg.steps_left;
+        let width = 10;
+        let filled = (done * width / egg.steps_total.max(1)) as usize;
+        format!(
+            "[{}{}] {} steps left {}",
+            "■".repeat(filled).green(),
+            "·".repeat(width as usize - filled).dimmed(),
+            egg.steps_left,
+            format!("({} egg)", origin).dimmed()
+        )
+    }
+}
+
+/// Show the incubator and hatch any eggs that are ready
+pub fn eggs_command(skip_animation: bool, rng: &mut impl Rng) {
+    let mut incubator = Incubator::load();
+    let ready: Vec<Egg> = incubator.eggs.iter().filter(|e| e.is_ready()).cloned().collect();
+
+    if !ready.is_empty() {
+        let mut storage = PcStorage::load();
+        let mut pokedex = Pokedex::load();
+        let mut hatched = Vec::new();
+        for egg in &ready {
+            let shiny = rng.gen_range(0..SHINY_ODDS) == 0;
+            storage.add_pokemon(egg.species.clone(), PokeballType::Egg, shiny, None, None, Ivs::roll(rng));
+            pokedex.mark_caught(&egg.species, None);
+            hatched.push((egg.species.clone(), shiny));
+        }
+        if let Err(e) = storage.save() {
+            eprintln!("{}", format!("SAVE FAILED: {}. Your eggs are still in the incubator.", e).red().bold());
+            return;
+        }
+        incubator.eggs.retain(|e| !e.is_ready());
+        if let Err(e) = incubator.save() {
+            eprintln!("{}", format!("Could not save your incubator: {}", e).red());
+        }
+        let _ = pokedex.save();
+
+        for (species, shiny) in hatched {
+            println!("{}", "Oh? The egg is hatching!".cyan().bold());
+            if !skip_animation {
+                hatch_animation();
+            }
+            println!();
+            if !graphics::print_image(&species, shiny, 12) {
+                print_sprite(&species, shiny);
+            }
+            println!();
+            if shiny {
+                println!("{}", format!("A shiny {} hatched from the egg!", species).yellow().bold());
+            } else {
+                println!("{}", format!("{} hatched from the egg!", species).green().bold());
+            }
+            println!("{} has been sent to your PC!", species.cyan());
+            println!();
+        }
+        check_achievements();
+    }
+
+    println!("{}", "  Egg Incubator".cyan().bold());
+    println!("{}", "  ═════════════".cyan());
+    println!();
+    if incubator.eggs.is_empty() {
+        println!("  {}", "No eggs. Eggs come from the daycare and every 25th catch.".dimmed());
+    }
+    for egg in &incubator.eggs {
+        println!("  🥚 {}", describe_egg(egg));
+    }
+    println!();
+    println!(
+        "  Next milestone: {}",
+        format!("{} more catches", MILESTONE_CATCHES - incubator.catches % MILESTONE_CATCHES).yellow()
+    );
+    match &incubator.daycare {
+        Some(daycare) => println!(
+            "  Daycare:        {} {}",
+            daycare.parents.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" & ").green(),
+            if incubator.has_room() {
+                format!("(egg in {} steps)", DAYCARE_STEPS - daycare.steps).dimmed()
+            } else {
+                "(waiting for room in the incubator)".dimmed()
+            }
+        ),
+        None => println!("  Daycare:        {}", "empty (catch-pokemon daycare <a> <b>)".dimmed()),
+    }
+    println!();
+    println!("{}", "  Eggs take a step with every encounter and every new terminal.".dimmed());
+}
+
+/// Leave two Pokemon of the same family at the daycare, or take them back
+pub fn daycare_command(pokemon: Vec<String>, withdraw: bool) {
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let mut incubator = Incubator::load();
+
+    if withdraw {
+        let Some(daycare) = incubator.daycare.take() else {
+            println!("{}", "The daycare is empty.".dimmed());
+            return;
+        };
+        let Some(mut storage) = PcStorage::load_checked() else {
+            return;
+        };
+        storage.pokemon.extend(daycare.parents.iter().cloned());
+        storage.resign_chain();
+        if let Err(e) = storage.save() {
+            eprintln!("{}", format!("SAVE FAILED: {}. Your Pokemon are still at the daycare.", e).red().bold());
+            return;
+        }
+        if let Err(e) = incubator.save() {
+            eprintln!("{}", format!("Could not save your incubator: {}", e).red());
+        }
+        let names: Vec<&str> = daycare.parents.iter().map(|p| p.name.as_str()).collect();
+        println!("{}", format!("{} came back to your PC.", names.join(" and ")).green().bold());
+        return;
+    }
+
+    if pokemon.len() != 2 {
+        match &incubator.daycare {
+            Some(daycare) => {
+                let names: Vec<&str> = daycare.parents.iter().map(|p| p.name.as_str()).collect();
+                println!("The daycare is looking after {}.", names.join(" and ").green());
+            }
+            None => println!("{}", "Leave two Pokemon of the same family: catch-pokemon daycare <a> <b>".dimmed()),
+        }
+        return;
+    }
+    if incubator.daycare.is_some() {
+        eprintln!("{}", "The daycare already has two Pokemon. Take them back with --withdraw first.".red());
+        return;
+    }
+
+    let Some(mut storage) = PcStorage::load_checked() else {
+        return;
+    };
+
+    let Some(first) = storage.find_entry(&pokemon[0]) else {
+        eprintln!("{}", format!("You don't have a {} in your PC.", pokemon[0]).red());
+        return;
+    };
+    // The same name twice means two different Pokemon
+    let second = if pokemon[1].parse::<usize>().is_err() && pokemon[0].eq_ignore_ascii_case(&pokemon[1]) {
+        let name = storage.pokemon[first].name.to_lowercase();
+        storage.pokemon.iter().enumerate().find(|(i, p)| *i != first && p.name.to_lowercase() == name).map(|(i, _)| i)
+    } else {
+        storage.find_entry(&pokemon[1])
+    };
+    let Some(second) = second.filter(|&i| i != first) else {
+        eprintln!("{}", format!("You need a second {} in your PC.", pokemon[1]).red());
+        return;
+    };
+
+    let parents = [storage.pokemon[first].clone(), storage.pokemon[second].clone()];
+    let families: Vec<String> = parents.iter().map(|p| family(&p.name, &pokemon_db)).collect();
+    if families[0] != families[1] {
+        eprintln!("{}", format!("{} and {} aren't from the same family.", parents[0].name, parents[1].name).red());
+        return;
+    }
+    let legendary = parents.iter().any(|p| {
+        let key = p.name.to_lowercase().replace('-', "_");
+        pokemon_db.get(&key).is_some_and(|d| matches!(d.category.as_str(), "legendary" | "mythical"))
+    });
+    if legendary {
+        eprintln!("{}", "Legendary and mythical Pokemon don't lay eggs.".red());
+        return;
+    }
+
+    // Highest index first so the other stays valid
+    storage.pokemon.remove(first.max(second));
+    storage.pokemon.remove(first.min(second));
+    storage.resign_chain();
+
+    incubator.daycare = Some(Daycare { parents: parents.to_vec(), family: families[0].clone(), steps: 0 });
+    if let Err(e) = incubator.save() {
+        eprintln!("{}", format!("Could not save your incubator: {}", e).red());
+        return;
+    }
+    if let Err(e) = storage.save() {
+        eprintln!("{}", format!("SAVE FAILED: {}. Nothing was left at the daycare.", e).red().bold());
+        incubator.daycare = None;
+        let _ = incubator.save();
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("You left {} and {} at the daycare.", parents[0].name, parents[1].name).green().bold()
+    );
+    println!(
+        "{}",
+        format!(
+            "They'll find a {} egg every {} steps. Take them back with 'catch-pokemon daycare --withdraw'.",
+            families[0].replace('_', "-"),
+            DAYCARE_STEPS
+        )
+        .dimmed()
+    );
+}
@@ -0,0 +1,464 @@
+use chrono::{DateTime, Local, NaiveDate};
+use colored::*;
+use hmac::Mac as HmacMac;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::catch::TOKEN_LIFETIME_SECS;
+use crate::crypto::{derive_signing_key, HmacSha256, LOG_DOMAIN};
+use crate::storage::{get_encounter_log_head_path, get_encounter_log_path};
+
+/// How one ball went
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    BrokeFree,
+    Caught,
+    /// Broke free and ran away
+    Fled,
+}
+
+/// How a `fight` turn ended an encounter
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FightEnd {
+    Fainted,
+    Fled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    Encounter {
+        /// PC format, e.g. "mr-mime"
+        species: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        form: Option<String>,
+        shiny: bool,
+    },
+    Throw { ball: String, outcome: Outcome },
+    /// A `fight` turn that ended the encounter
+    Fight { end: FightEnd },
+}
+
+/// One line of the log. Each line is signed together with the hash of the
+/// line before it, so lines can't be edited, removed or reordered, and the
+/// signed head (see LogHead) catches lines cut off the end.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogEntry {
+    pub at: DateTime<Local>,
+    /// Timestamp of the encounter token, shared by an encounter and its throws
+    pub encounter: i64,
+    #[serde(flatten)]
+    pub event: LogEvent,
+    pub prev_hash: String,
+    pub signature: String,
+}
+
+impl LogEntry {
+    fn canonical_data(&self) -> String {
+        let event = serde_json::to_string(&self.event).unwrap_or_default();
+        format!("{}|{}|{}", self.at.to_rfc3339(), self.encounter, event)
+    }
+
+    fn sign(&self, key: &[u8]) -> String {
+        let mut mac = <HmacSha256 as HmacMac>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(LOG_DOMAIN);
+        mac.update(self.canonical_data().as_bytes());
+        mac.update(self.prev_hash.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(LOG_DOMAIN);
+        hasher.update(self.canonical_data().as_bytes());
+        hasher.update(self.prev_hash.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Hash of the newest line, kept beside the log. The chain alone can't tell
+/// when lines are cut off the end.
+#[derive(Serialize, Deserialize, Debug)]
+struct LogHead {
+    hash: String,
+    signature: String,
+}
+
+impl LogHead {
+    fn sign(key: &[u8], hash: &str) -> String {
+        let mut mac = <HmacSha256 as HmacMac>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(LOG_DOMAIN);
+        mac.update(b"head|");
+        mac.update(hash.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn new(key: &[u8], hash: String) -> Self {
+        LogHead { signature: LogHead::sign(key, &hash), hash }
+    }
+
+    /// The recorded head, None before the first line. Err if it was edited.
+    fn load(key: &[u8]) -> Result<Option<String>, String> {
+        let Ok(contents) = fs::read_to_string(get_encounter_log_head_path()) else {
+            return Ok(None);
+        };
+        match serde_json::from_str::<LogHead>(&contents) {
+            Ok(head) if head.signature == LogHead::sign(key, &head.hash) => Ok(Some(head.hash)),
+            _ => Err("The encounter log's head record was edited.".to_string()),
+        }
+    }
+
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = get_encounter_log_head_path();
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Encounter id carried by a session token ("timestamp:hmac")
+pub fn encounter_id(token: Option<&str>) -> i64 {
+    token.and_then(|t| t.split(':').next()).and_then(|ts| ts.parse().ok()).unwrap_or(0)
+}
+
+/// The last line of the log, read from the end so appending stays cheap
+fn last_line(file: &mut fs::File) -> Option<String> {
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(4096))).ok()?;
+    let mut tail = String::new();
+    file.read_to_string(&mut tail).ok()?;
+    tail.lines().rev().find(|l| !l.trim().is_empty()).map(str::to_string)
+}
+
+/// Append an event. The log is JSON Lines and is never rewritten.
+pub fn record(encounter: i64, event: LogEvent) {
+    // Deriving the key is slow, so do it before taking the lock
+    let key = derive_signing_key();
+    let path = get_encounter_log_path();
+    let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&path)?;
+        // Shells often record at the same moment. Without the lock two lines
+        // could chain to the same one and break the log for good.
+        file.lock()?;
+        let prev_hash = match last_line(&mut file) {
+            Some(line) => match serde_json::from_str::<LogEntry>(&line) {
+                Ok(last) => last.hash(),
+                Err(_) => return Err("the encounter log is damaged; run 'catch-pokemon log' for details".into()),
+            },
+            None => String::from("genesis"),
+        };
+        let mut entry = LogEntry { at: Local::now(), encounter, event, prev_hash, signature: String::new() };
+        entry.signature = entry.sign(&key);
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        LogHead::new(&key, entry.hash()).save()?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        eprintln!("{}", format!("Could not write the encounter log: {}", e).red());
+    }
+}
+
+/// Read and verify the whole log. Errors name the first bad line.
+pub fn load() -> Result<Vec<LogEntry>, String> {
+    let key = derive_signing_key();
+    let mut contents = String::new();
+    let head = match fs::File::open(get_encounter_log_path()) {
+        Ok(mut file) => {
+            // Wait for a write in progress to finish
+            file.lock_shared().map_err(|e| format!("Could not read the encounter log: {}", e))?;
+            file.read_to_string(&mut contents).map_err(|e| format!("Could not read the encounter log: {}", e))?;
+            LogHead::load(&key)?
+        }
+        Err(_) => LogHead::load(&key)?,
+    };
+
+    let mut prev_hash = String::from("genesis");
+    // A crash between appending and saving the head leaves the head one line
+    // short, so the head only has to appear somewhere in the chain
+    let mut head_found = head.is_none();
+    let mut entries = Vec::new();
+    for (i, line) in contents.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+        let entry: LogEntry =
+            serde_json::from_str(line).map_err(|_| format!("Line {} of the encounter log can't be read.", i + 1))?;
+        if entry.prev_hash != prev_hash {
+            return Err(format!("Encounter log chain broken at line {}: lines were removed or reordered.", i + 1));
+        }
+        if entry.signature != entry.sign(&key) {
+            return Err(format!("Invalid signature at line {} of the encounter log. It may have been edited.", i + 1));
+        }
+        prev_hash = entry.hash();
+        head_found |= head.as_deref() == Some(prev_hash.as_str());
+        entries.push(entry);
+    }
+    if !head_found {
+        return Err("The encounter log ends early: lines were removed from the end.".to_string());
+    }
+    Ok(entries)
+}
+
+/// How an encounter ended
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum EncounterResult {
+    Caught,
+    /// Ran away after a ball or a fight turn
+    Fled,
+    /// Knocked out in a fight turn
+    Fainted,
+    /// Balls were thrown, but the encounter ran out before a catch
+    Escaped,
+    /// The encounter ran out before any ball was thrown
+    Skipped,
+    /// Still open: its session token hasn't expired yet
+    Active,
+}
+
+impl EncounterResult {
+    pub fn label(&self) -> ColoredString {
+        match self {
+            EncounterResult::Caught => "Caught".green().bold(),
+            EncounterResult::Fled => "Fled".red(),
+            EncounterResult::Fainted => "Fainted".red().dimmed(),
+            EncounterResult::Escaped => "Escaped".yellow(),
+            EncounterResult::Skipped => "Skipped".dimmed(),
+            EncounterResult::Active => "Active".cyan(),
+        }
+    }
+}
+
+/// An encounter with its throws, put together from the log
+#[derive(Debug, Clone)]
+pub struct EncounterRecord {
+    pub at: DateTime<Local>,
+    pub species: String,
+    pub form: Option<String>,
+    pub shiny: bool,
+    pub throws: Vec<(String, Outcome)>,
+    pub result: EncounterResult,
+}
+
+/// Group log lines into encounters, oldest first
+pub fn encounters(entries: &[LogEntry]) -> Vec<EncounterRecord> {
+    let mut records: Vec<(i64, EncounterRecord)> = Vec::new();
+    for entry in entries {
+        match &entry.event {
+            LogEvent::Encounter { species, form, shiny } => records.push((
+                entry.encounter,
+                EncounterRecord {
+                    at: entry.at,
+                    species: species.clone(),
+                    form: form.clone(),
+                    shiny: *shiny,
+                    throws: Vec::new(),
+                    result: EncounterResult::Active,
+                },
+            )),
+            LogEvent::Throw { ball, outcome } => {
+                // Terminals interleave, so match throws by encounter id
+                let Some((_, record)) = records.iter_mut().rev().find(|(id, _)| *id == entry.encounter) else {
+                    continue;
+                };
+                record.throws.push((ball.clone(), *outcome));
+                record.result = match outcome {
+                    Outcome::Caught => EncounterResult::Caught,
+                    Outcome::Fled => EncounterResult::Fled,
+                    Outcome::BrokeFree => record.result,
+                };
+            }
+            LogEvent::Fight { end } => {
+                let Some((_, record)) = records.iter_mut().rev().find(|(id, _)| *id == entry.encounter) else {
+                    continue;
+                };
+                record.result = match end {
+                    FightEnd::Fainted => EncounterResult::Fainted,
+                    FightEnd::Fled => EncounterResult::Fled,
+                };
+            }
+        }
+    }
+    // Encounters left open past their token's lifetime got away
+    let now = Local::now().timestamp();
+    records
+        .into_iter()
+        .map(|(id, mut record)| {
+            if record.result == EncounterResult::Active && now - id > TOKEN_LIFETIME_SECS {
+                record.result = if record.throws.is_empty() { EncounterResult::Skipped } else { EncounterResult::Escaped };
+            }
+            record
+        })
+        .collect()
+}
+
+/// What `catch-pokemon log` shows
+pub struct LogFilter {
+    pub species: Option<String>,
+    pub result: Option<EncounterResult>,
+    pub shiny: bool,
+    pub since: Option<NaiveDate>,
+    pub limit: usize,
+}
+
+impl LogFilter {
+    fn matches(&self, record: &EncounterRecord) -> bool {
+        let species = self.species.as_ref().map(|s| s.to_lowercase().replace('_', "-"));
+        species.is_none_or(|s| record.species == s)
+            && self.result.is_none_or(|r| record.result == r)
+            && (!self.shiny || record.shiny)
+            && self.since.is_none_or(|d| record.at.date_naive() >= d)
+    }
+}
+
+fn throw_marks(throws: &[(String, Outcome)]) -> String {
+    throws
+        .iter()
+        .map(|(_, outcome)| match outcome {
+            Outcome::BrokeFree => "○".yellow().to_string(),
+            Outcome::Caught => "●".green().to_string(),
+            Outcome::Fled => "✗".red().to_string(),
+        })
+        .collect()
+}
+
+/// Show recent encounters, newest first, with a summary of the matches
+pub fn log_command(filter: LogFilter) {
+    let entries = match load() {
+        Ok(entries) => entries,
+        Err(msg) => {
+            eprintln!("{}", format!("Encounter log integrity check FAILED: {}", msg).red().bold());
+            return;
+        }
+    };
+    let matching: Vec<EncounterRecord> = encounters(&entries).into_iter().filter(|r| filter.matches(r)).collect();
+
+    println!("{}", "  Encounter Log".cyan().bold());
+    println!("{}", "  ═════════════".cyan());
+    println!();
+    if matching.is_empty() {
+        println!("  {}", "No encounters match.".dimmed());
+        return;
+    }
+    for record in matching.iter().rev().take(filter.limit) {
+        let name = match &record.form {
+            Some(form) => format!("{}-{}", record.species, form),
+            None => record.species.clone(),
+        };
+        let shiny = if record.shiny { "✦".yellow().bold().to_string() } else { " ".to_string() };
+        // Pad by ball count, since the coloured marks hide their width
+        let pad = " ".repeat(8usize.saturating_sub(record.throws.len()));
+        println!(
+            "  {}  {:<22} {} {}{} {}",
+            record.at.format("%Y-%m-%d %H:%M").to_string().dimmed(),
+            name,
+            shiny,
+            throw_marks(&record.throws),
+            pad,
+            record.result.label()
+        );
+    }
+    if matching.len() > filter.limit {
+        println!("  {}", format!("… {} older (use --limit)", matching.len() - filter.limit).dimmed());
+    }
+
+    let count = |result: EncounterResult| matching.iter().filter(|r| r.result == result).count();
+    let throws: usize = matching.iter().map(|r| r.throws.len()).sum();
+    let caught = count(EncounterResult::Caught);
+    println!();
+    println!(
+        "  {} encounters: {} caught, {} fled, {} fainted, {} escaped, {} skipped",
+        matching.len().to_string().yellow(),
+        caught,
+        count(EncounterResult::Fled),
+        count(EncounterResult::Fainted),
+        count(EncounterResult::Escaped),
+        count(EncounterResult::Skipped)
+    );
+    let finished = matching.len() - count(EncounterResult::Active);
+    if finished > 0 && throws > 0 {
+        println!(
+            "  Catch rate {} of encounters, {} of {} balls",
+            format!("{:.1}%", caught as f64 * 100.0 / finished as f64).yellow(),
+            format!("{:.1}%", caught as f64 * 100.0 / throws as f64).yellow(),
+            throws
+        );
+    }
+    println!("  {}", "○ broke free  ● caught  ✗ fled".dimmed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(encounter: i64, event: LogEvent) -> LogEntry {
+        LogEntry { at: Local::now(), encounter, event, prev_hash: String::new(), signature: String::new() }
+    }
+
+    fn seen(encounter: i64, species: &str) -> LogEntry {
+        entry(encounter, LogEvent::Encounter { species: species.to_string(), form: None, shiny: false })
+    }
+
+    fn throw(encounter: i64, outcome: Outcome) -> LogEntry {
+        entry(encounter, LogEvent::Throw { ball: "pokeball".to_string(), outcome })
+    }
+
+    fn fight(encounter: i64, end: FightEnd) -> LogEntry {
+        entry(encounter, LogEvent::Fight { end })
+    }
+
+    #[test]
+    fn throws_join_their_own_encounter() {
+        let now = Local::now().timestamp();
+        let (pidgey, rattata) = (now - 20, now - 10);
+        let entries = vec![
+            seen(pidgey, "pidgey"),
+            seen(rattata, "rattata"),
+            throw(pidgey, Outcome::BrokeFree),
+            throw(rattata, Outcome::Fled),
+            throw(pidgey, Outcome::Caught),
+        ];
+        let records = encounters(&entries);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].species, "pidgey");
+        assert_eq!(records[0].throws.len(), 2);
+        assert_eq!(records[0].result, EncounterResult::Caught);
+        assert_eq!(records[1].throws.len(), 1);
+        assert_eq!(records[1].result, EncounterResult::Fled);
+    }
+
+    #[test]
+    fn open_encounters_expire_with_their_token() {
+        let now = Local::now().timestamp();
+        let old = now - TOKEN_LIFETIME_SECS - 60;
+        let entries = vec![
+            seen(old, "zubat"),
+            seen(old + 1, "geodude"),
+            throw(old + 1, Outcome::BrokeFree),
+            seen(now, "oddish"),
+        ];
+        let results: Vec<EncounterResult> = encounters(&entries).into_iter().map(|r| r.result).collect();
+        assert_eq!(results, [EncounterResult::Skipped, EncounterResult::Escaped, EncounterResult::Active]);
+    }
+
+    #[test]
+    fn fights_end_encounters() {
+        let old = Local::now().timestamp() - TOKEN_LIFETIME_SECS - 60;
+        let entries = vec![
+            seen(old, "rattata"),
+            fight(old, FightEnd::Fainted),
+            seen(old + 1, "abra"),
+            throw(old + 1, Outcome::BrokeFree),
+            fight(old + 1, FightEnd::Fled),
+        ];
+        let records = encounters(&entries);
+        assert_eq!(records[0].result, EncounterResult::Fainted);
+        assert!(records[0].throws.is_empty());
+        assert_eq!(records[1].result, EncounterResult::Fled);
+        assert_eq!(records[1].throws.len(), 1);
+    }
+}
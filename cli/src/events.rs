@@ -0,0 +1,241 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike};
+use colored::*;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::catch::{encounter_pool, EncounterSlot, SHINY_ODDS};
+use crate::models::{PokemonData, StatusCondition, ENCOUNTER_MODIFIERS, POKEMON_DATA};
+use crate::roaming::RoamingState;
+
+/// Encounter weight multiplier for the outbreak species while it's active
+pub const OUTBREAK_WEIGHT: f32 = 40.0;
+/// Shiny odds (one in N) for the outbreak species while it's active
+pub const OUTBREAK_SHINY_ODDS: u32 = 512;
+// Bumping the version reshuffles every future outbreak
+const OUTBREAK_DOMAIN: &str = "catch-pokemon:outbreak:v1";
+
+/// A clock- or calendar-driven rule that makes some types more common while
+/// it's active. Conditions that are left out always match.
+#[derive(Deserialize, Debug)]
+pub struct EncounterModifier {
+    pub name: String,
+    pub description: String,
+    /// Active hours as [start, end) in local time; wraps past midnight
+    #[serde(default)]
+    pub hours: Option<(u32, u32)>,
+    /// Lowercase three-letter weekday names ("mon", "sat")
+    #[serde(default)]
+    pub weekdays: Vec<String>,
+    #[serde(default)]
+    pub months: Vec<u32>,
+    /// Inclusive "MM-DD" date range; wraps past new year
+    #[serde(default)]
+    pub dates: Option<(String, String)>,
+    /// Encounter weight multiplier per type
+    pub types: HashMap<String, f32>,
+}
+
+impl EncounterModifier {
+    pub fn is_active(&self, now: DateTime<Local>) -> bool {
+        if let Some((start, end)) = self.hours {
+            let hour = now.hour();
+            let in_range = if start <= end { hour >= start && hour < end } else { hour >= start || hour < end };
+            if !in_range {
+                return false;
+            }
+        }
+
+        if !self.weekdays.is_empty() {
+            let day = now.format("%a").to_string().to_lowercase();
+            if !self.weekdays.contains(&day) {
+                return false;
+            }
+        }
+
+        if !self.months.is_empty() && !self.months.contains(&now.month()) {
+            return false;
+        }
+
+        if let Some((ref from, ref to)) = self.dates {
+            let today = now.format("%m-%d").to_string();
+            let (from, to) = (from.as_str(), to.as_str());
+            let in_range = if from <= to {
+                today.as_str() >= from && today.as_str() <= to
+            } else {
+                today.as_str() >= from || today.as_str() <= to
+            };
+            if !in_range {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Weight multiplier for a Pokemon with these types
+    pub fn multiplier(&self, types: &[String]) -> f32 {
+        best_multiplier(&self.types, types)
+    }
+}
+
+/// Multiplier of the best boosted type a Pokemon has (1.0 when none match)
+pub fn best_multiplier(boosts: &HashMap<String, f32>, types: &[String]) -> f32 {
+    types.iter().filter_map(|t| boosts.get(t)).fold(1.0, |a, &b| a.max(b))
+}
+
+/// Every modifier from the embedded table
+pub fn load_modifiers() -> Vec<EncounterModifier> {
+    serde_json::from_str(ENCOUNTER_MODIFIERS).unwrap_or_default()
+}
+
+/// Modifiers active at the given local time
+pub fn active_modifiers(now: DateTime<Local>) -> Vec<EncounterModifier> {
+    load_modifiers().into_iter().filter(|m| m.is_active(now)).collect()
+}
+
+/// Combined weight multiplier from all active modifiers
+pub fn weight_multiplier(modifiers: &[EncounterModifier], types: &[String]) -> f32 {
+    modifiers.iter().map(|m| m.multiplier(types)).product()
+}
+
+/// A daily mass outbreak. The species and window come from a hash of the
+/// date, so every player on the same binary chases the same target.
+pub struct Outbreak {
+    pub date: NaiveDate,
+    /// pokemon.json key (underscores)
+    pub species: String,
+    /// Local hours [start, end)
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl Outbreak {
+    /// Outbreak for a date, picked from the base species in the encounter
+    /// pool. Legendary and mythical Pokemon never break out.
+    pub fn for_date(date: NaiveDate, pool: &[EncounterSlot]) -> Option<Outbreak> {
+        let mut candidates: Vec<&String> = pool
+            .iter()
+            .filter(|slot| slot.form.is_none())
+            .filter(|slot| slot.data.category != "legendary" && slot.data.category != "mythical")
+            .map(|slot| slot.name)
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let digest = Sha256::digest(format!("{}:{}", OUTBREAK_DOMAIN, date.format("%Y-%m-%d")));
+        let pick = u64::from_le_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        // Start between 08:00 and 16:00 and last 4 to 8 hours
+        let start_hour = 8 + digest[8] as u32 % 9;
+        let end_hour = (start_hour + 4 + digest[9] as u32 % 5).min(24);
+
+        Some(Outbreak {
+            date,
+            species: candidates[(pick % candidates.len() as u64) as usize].clone(),
+            start_hour,
+            end_hour,
+        })
+    }
+
+    pub fn is_active(&self, now: DateTime<Local>) -> bool {
+        now.date_naive() == self.date && now.hour() >= self.start_hour && now.hour() < self.end_hour
+    }
+
+    pub fn display_name(&self) -> String {
+        self.species.replace('_', "-")
+    }
+
+    pub fn window(&self) -> String {
+        format!("{:02}:00-{:02}:00", self.start_hour, self.end_hour)
+    }
+}
+
+/// Show today's and upcoming outbreaks plus the modifiers active right now
+pub fn events_command(days: u32) {
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let pool = encounter_pool(&pokemon_db);
+    let now = Local::now();
+    let today = now.date_naive();
+
+    println!("{}", "  Mass Outbreaks".cyan().bold());
+    println!("{}", "  ══════════════".cyan());
+    println!(
+        "  {}",
+        format!(
+            "Outbreak species appear {}x as often with 1/{} shiny odds (normally 1/{}).",
+            OUTBREAK_WEIGHT, OUTBREAK_SHINY_ODDS, SHINY_ODDS
+        )
+        .dimmed()
+    );
+    println!();
+
+    for offset in 0..days.max(1) {
+        let Some(date) = today.checked_add_days(Days::new(offset as u64)) else {
+            break;
+        };
+        let Some(outbreak) = Outbreak::for_date(date, &pool) else {
+            println!("{}", "  No outbreaks: the encounter pool is empty.".yellow());
+            return;
+        };
+        let category = pokemon_db.get(&outbreak.species).map(|d| d.category.as_str()).unwrap_or("");
+
+        let day = if offset == 0 {
+            "Today".to_string()
+        } else if offset == 1 {
+            "Tomorrow".to_string()
+        } else {
+            date.format("%a %b %d").to_string()
+        };
+        let state = if offset > 0 {
+            String::new()
+        } else if outbreak.is_active(now) {
+            format!("{}", "ACTIVE NOW".green().bold())
+        } else if now.hour() < outbreak.start_hour {
+            format!("{}", "starts later today".yellow())
+        } else {
+            format!("{}", "over".dimmed())
+        };
+
+        println!(
+            "  {:<12} {:<20} {:<12} {:<18} {}",
+            day.bold(),
+            outbreak.display_name().green().bold(),
+            outbreak.window(),
+            category.replace('_', " ").dimmed(),
+            state
+        );
+    }
+
+    let modifiers = active_modifiers(now);
+    if !modifiers.is_empty() {
+        println!();
+        println!("{}", "  Active now".cyan().bold());
+        for modifier in &modifiers {
+            println!("  {:<20} {}", modifier.name.yellow(), modifier.description.dimmed());
+        }
+    }
+
+    let roaming = RoamingState::load();
+    let at_large: Vec<_> = roaming.roamers.iter().filter(|r| !r.caught).collect();
+    if !at_large.is_empty() {
+        println!();
+        println!("{}", "  Roaming".cyan().bold());
+        for r in at_large {
+            let status = if r.status == StatusCondition::None { String::new() } else { format!(", {}", r.status.display_name()) };
+            println!(
+                "  {:<20} {}",
+                r.species.magenta().bold(),
+                format!("{:.0}% HP{}, seen {} times", r.hp * 100.0, status, r.appearances).dimmed()
+            );
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::Local;
+use clap::ValueEnum;
+use colored::*;
+use std::collections::HashMap;
+
+use crate::achievements::check_achievements;
+use crate::buddy::refresh_prompt;
+use crate::candy::{candy_name, family, CandyLedger};
+use crate::graphics;
+use crate::items::{Bag, Item};
+use crate::models::{
+    form_name, BattleTeam, CaughtPokemon, EvolutionData, EvolutionMethod, EvolutionRecord, PcStorage, Pokedex,
+    PokemonData, POKEMON_DATA,
+};
+use crate::roaming::check_unlocks;
+use crate::sprites::print_sprite;
+use crate::training::TeamTraining;
+
+/// Candy for evolutions that aren't driven by an item or a level
+const FRIENDSHIP_CANDY: u32 = 2;
+
+/// What an evolution uses up
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cost {
+    Item(Item),
+    /// Candy of the Pokemon's family
+    Candy(u32),
+}
+
+impl Cost {
+    fn of(evolution: &EvolutionData) -> Option<Self> {
+        match evolution.method {
+            EvolutionMethod::Stone => {
+                let stone = evolution.requirement.as_deref()?;
+                Item::from_str(stone, true).ok().map(Cost::Item)
+            }
+            EvolutionMethod::Trade => Some(Cost::Item(Item::LinkingCord)),
+            // One candy per 20 levels, so early stages are cheap
+            EvolutionMethod::Level => {
+                let level: u32 = evolution.requirement.as_deref()?.parse().ok()?;
+                Some(Cost::Candy((level / 20).clamp(1, 3)))
+            }
+            EvolutionMethod::Friendship | EvolutionMethod::Other => Some(Cost::Candy(FRIENDSHIP_CANDY)),
+        }
+    }
+
+    fn describe(&self, family: &str) -> String {
+        match self {
+            Cost::Item(item) => format!("a {}", item.display_name()),
+            Cost::Candy(n) => format!("{} {}", n, candy_name(family)),
+        }
+    }
+}
+
+/// How the main games do it, e.g. "level 16" or "Fire Stone"
+fn describe_method(evolution: &EvolutionData) -> String {
+    let requirement = evolution.requirement.as_deref();
+    match evolution.method {
+        EvolutionMethod::Level => format!("level {}", requirement.unwrap_or("up")),
+        EvolutionMethod::Stone => requirement
+            .and_then(|r| Item::from_str(r, true).ok())
+            .map(|i| i.display_name().to_string())
+            .unwrap_or_else(|| "stone".to_string()),
+        EvolutionMethod::Trade => "trade".to_string(),
+        EvolutionMethod::Friendship => "friendship".to_string(),
+        EvolutionMethod::Other => requirement.unwrap_or("special").to_string(),
+    }
+}
+
+/// Species this entry can evolve into, sorted by name. A regional form only
+/// evolves into species that have that form or require it.
+fn evolution_options<'a>(
+    entry: &CaughtPokemon,
+    pokemon_db: &'a HashMap<String, PokemonData>,
+) -> Vec<(&'a String, &'a EvolutionData)> {
+    let key = entry.name.to_lowercase().replace('-', "_");
+    let mut options: Vec<_> = pokemon_db
+        .iter()
+        .filter_map(|(name, data)| {
+            let evolution = data.evolution.as_ref()?;
+            if evolution.from != key {
+                return None;
+            }
+            let allowed = match (&evolution.form, &entry.form) {
+                (Some(required), form) => form.as_deref() == Some(required.as_str()),
+                (None, None) => true,
+                (None, Some(form)) => data.forms.contains_key(form),
+            };
+            allowed.then_some((name, evolution))
+        })
+        .collect();
+    options.sort_by(|a, b| a.0.cmp(b.0));
+    options
+}
+
+/// Evolve a Pokemon in the PC
+pub fn evolve_command(target: String, into: Option<String>) {
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+
+    let Some(mut storage) = PcStorage::load_checked() else {
+        return;
+    };
+
+    let Some(index) = storage.find_entry(&target) else {
+        match target.parse::<usize>() {
+            Ok(number) => eprintln!("{}", format!("There's no Pokemon #{} in your PC.", number).red()),
+            Err(_) => eprintln!("{}", format!("You don't have a {} in your PC.", target).red()),
+        }
+        return;
+    };
+    let entry = storage.pokemon[index].clone();
+    let species = entry.name.to_lowercase();
+    let full_name = form_name(&species, entry.form.as_deref());
+
+    let options = evolution_options(&entry, &pokemon_db);
+    if options.is_empty() {
+        println!("{}", format!("{} doesn't evolve.", full_name).yellow());
+        return;
+    }
+
+    let chosen = match &into {
+        Some(into) => {
+            let into = into.to_lowercase().replace('-', "_");
+            match options.iter().find(|(name, _)| **name == into) {
+                Some(option) => *option,
+                None => {
+                    eprintln!("{}", format!("{} can't evolve into {}.", full_name, into.replace('_', "-")).red());
+                    return;
+                }
+            }
+        }
+        None if options.len() == 1 => options[0],
+        None => {
+            println!("{}", format!("{} can evolve into:", full_name).cyan().bold());
+            for (name, evolution) in &options {
+                println!("  {:<14} {}", name.replace('_', "-").green(), describe_method(evolution).dimmed());
+            }
+            println!();
+            println!("Choose one with --into, e.g. catch-pokemon evolve {} --into {}", species, options[0].0.replace('_', "-"));
+            return;
+        }
+    };
+    let (new_key, evolution) = chosen;
+    let new_name = new_key.replace('_', "-");
+
+    let Some(cost) = Cost::of(evolution) else {
+        eprintln!("{}", format!("Evolution data for {} is incomplete.", new_name).red());
+        return;
+    };
+
+    let family = family(&species, &pokemon_db);
+    let mut bag = None;
+    let mut ledger = None;
+    match cost {
+        Cost::Item(item) => {
+            let mut loaded = Bag::load();
+            if !loaded.take(item) {
+                println!(
+                    "{}",
+                    format!("{} evolves with {}. You don't have a {}.", full_name, describe_method(evolution), item.display_name())
+                        .red()
+                );
+                return;
+            }
+            bag = Some(loaded);
+        }
+        Cost::Candy(needed) => {
+            let mut loaded = CandyLedger::load();
+            if !loaded.spend(&family, needed) {
+                println!(
+                    "{}",
+                    format!(
+                        "Evolving {} into {} ({}) takes {}. You have {}.",
+                        full_name,
+                        new_name,
+                        describe_method(evolution),
+                        cost.describe(&family),
+                        loaded.balance(&family)
+                    )
+                    .red()
+                );
+                println!("{}", "Transfer duplicates for candy with 'catch-pokemon release <name>'.".dimmed());
+                return;
+            }
+            ledger = Some(loaded);
+        }
+    }
+
+    let new_form = entry.form.clone().filter(|f| pokemon_db.get(new_key).is_some_and(|d| d.forms.contains_key(f)));
+    let evolving = &mut storage.pokemon[index];
+    evolving.evolutions.push(EvolutionRecord { from: species.clone(), at: Local::now() });
+    evolving.name = new_name.clone();
+    evolving.form = new_form.clone();
+    storage.resign_chain();
+
+    if let Err(e) = storage.save() {
+        eprintln!("{}", format!("SAVE FAILED: {}. The evolution did not happen.", e).red().bold());
+        return;
+    }
+    if let Some(bag) = bag {
+        if let Err(e) = bag.save() {
+            eprintln!("{}", format!("Could not save your bag: {}", e).red());
+        }
+    }
+    if let Some(ledger) = ledger {
+        if let Err(e) = ledger.save() {
+            eprintln!("{}", format!("Could not save your candy: {}", e).red());
+        }
+    }
+
+    // Keep the battle team pointing at Pokemon that are still in the PC
+    let mut team = BattleTeam::load();
+    let still_held = storage.pokemon.iter().filter(|p| p.name.to_lowercase() == species && p.shiny == entry.shiny).count();
+    let on_team = team.pokemon.iter().filter(|p| p.name.to_lowercase() == species && p.shiny == entry.shiny).count();
+    if on_team > still_held {
+        // The team holds one of each species, so an evolution into one
+        // already there leaves the team instead
+        let index = team.pokemon.iter().position(|p| p.name.to_lowercase() == species && p.shiny == entry.shiny);
+        let new_on_team = team.pokemon.iter().any(|p| p.name.to_lowercase().replace('-', "_") == *new_key);
+        match index {
+            Some(index) if new_on_team => {
+                team.pokemon.remove(index);
+            }
+            Some(index) => team.pokemon[index].name = new_name.clone(),
+            None => {}
+        }
+        if let Err(e) = team.save() {
+            eprintln!("{}", format!("Could not update your battle team: {}", e).red());
+        }
+        let mut training = TeamTraining::load();
+        training.rename(&species, &new_name);
+        if let Err(e) = training.save() {
+            eprintln!("{}", format!("Could not update your team's training: {}", e).red());
+        }
+        refresh_prompt();
+    }
+
+    let new_full_name = form_name(&new_name, new_form.as_deref());
+    println!();
+    println!("{}", format!("What? {} is evolving!", full_name).cyan().bold());
+    println!();
+    if !graphics::print_image(&new_full_name, entry.shiny, 12) {
+        print_sprite(&new_full_name, entry.shiny);
+    }
+    println!();
+    println!(
+        "{}",
+        format!("Congratulations! Your {} evolved into {}!", full_name, new_full_name).green().bold()
+    );
+    println!("{}", format!("Used {}.", cost.describe(&family)).dimmed());
+
+    let mut pokedex = Pokedex::load();
+    pokedex.mark_caught(&new_name, new_form.as_deref());
+    let _ = pokedex.save();
+    check_unlocks(&pokedex);
+    check_achievements();
+}
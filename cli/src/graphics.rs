@@ -0,0 +1,333 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use std::io::{stdout, IsTerminal, Write};
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::sprites::{load_sprite, Rgb, Sprite};
+
+// Image id used for kitty placements so a redraw replaces the previous sprite
+const KITTY_IMAGE_ID: u32 = 4242;
+
+/// Terminal image protocol used for full-resolution sprites
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    Iterm,
+    /// No image support — use half-block ANSI art
+    Ansi,
+}
+
+/// Pick an image protocol from the config override or the environment.
+/// Detected once per process, since the TUIs ask on every redraw.
+pub fn detect_protocol() -> Protocol {
+    static PROTOCOL: OnceLock<Protocol> = OnceLock::new();
+    *PROTOCOL.get_or_init(detect_uncached)
+}
+
+/// An explicit override always wins. Otherwise piped output gets ANSI art
+/// unless CATCH_POKEMON_TTY says the pipe ends at a terminal (the shell
+/// function tees catch output).
+fn detect_uncached() -> Protocol {
+    match Config::load().sprites.protocol.as_str() {
+        "kitty" => return Protocol::Kitty,
+        "sixel" => return Protocol::Sixel,
+        "iterm" => return Protocol::Iterm,
+        "ansi" => return Protocol::Ansi,
+        _ => {}
+    }
+
+    if !stdout().is_terminal() && std::env::var_os("CATCH_POKEMON_TTY").is_none() {
+        return Protocol::Ansi;
+    }
+
+    let env = |key: &str| std::env::var(key).unwrap_or_default();
+    let term = env("TERM");
+    let term_program = env("TERM_PROGRAM");
+
+    // Multiplexers swallow graphics escapes unless passthrough is configured
+    if std::env::var_os("TMUX").is_some() || term.starts_with("screen") {
+        return Protocol::Ansi;
+    }
+
+    if std::env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || term_program == "ghostty" {
+        return Protocol::Kitty;
+    }
+    if term_program == "iTerm.app" || term_program == "WezTerm" || env("LC_TERMINAL") == "iTerm2" {
+        return Protocol::Iterm;
+    }
+    if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") || term_program == "mintty" {
+        return Protocol::Sixel;
+    }
+
+    Protocol::Ansi
+}
+
+/// Size of one terminal cell in pixels, guessing 10x20 when the terminal
+/// doesn't report its pixel size
+fn cell_size() -> (usize, usize) {
+    match crossterm::terminal::window_size() {
+        Ok(ws) if ws.width > 0 && ws.height > 0 && ws.columns > 0 && ws.rows > 0 => (
+            (ws.width / ws.columns).max(1) as usize,
+            (ws.height / ws.rows).max(1) as usize,
+        ),
+        _ => (10, 20),
+    }
+}
+
+/// A sprite encoded for an image protocol, ready to be drawn
+pub struct SpriteImage {
+    protocol: Protocol,
+    payload: String,
+    /// Height on screen in terminal rows
+    pub rows: usize,
+}
+
+impl SpriteImage {
+    /// Encode a sprite scaled up to fit in `max_cols` x `max_rows` cells.
+    /// Returns None when the terminal has no image support or the sprite
+    /// isn't embedded, so callers fall back to ANSI art.
+    pub fn new(name: &str, shiny: bool, max_cols: usize, max_rows: usize) -> Option<Self> {
+        let protocol = detect_protocol();
+        if protocol == Protocol::Ansi || max_cols == 0 || max_rows == 0 {
+            return None;
+        }
+        let sprite = load_sprite(name, shiny)?;
+        if sprite.width == 0 || sprite.height == 0 {
+            return None;
+        }
+
+        // Integer nearest-neighbour scaling keeps pixel art crisp
+        let (cw, ch) = cell_size();
+        let scale = (max_cols * cw / sprite.width)
+            .min(max_rows * ch / sprite.height)
+            .max(1);
+        let scaled = upscale(&sprite, scale);
+        let cols = scaled.width.div_ceil(cw);
+        let rows = scaled.height.div_ceil(ch);
+
+        let payload = match protocol {
+            Protocol::Kitty => encode_kitty(&scaled, cols, rows),
+            Protocol::Sixel => encode_sixel(&scaled),
+            Protocol::Iterm => encode_iterm(&scaled, cols, rows),
+            Protocol::Ansi => return None,
+        };
+
+        Some(SpriteImage { protocol, payload, rows })
+    }
+
+    /// Draw the image with its top-left corner at the given cell (0-based)
+    pub fn draw_at(&self, col: usize, row: usize) {
+        let mut out = stdout();
+        if self.protocol == Protocol::Kitty {
+            let _ = write!(out, "{}", kitty_delete());
+        }
+        let _ = write!(out, "\x1B[{};{}H{}", row + 1, col + 1, self.payload);
+        let _ = out.flush();
+    }
+
+    /// Print the image inline at the cursor and move below it
+    pub fn print(&self) {
+        let mut out = stdout();
+        let _ = write!(out, "{}", self.payload);
+        match self.protocol {
+            // The kitty payload holds the cursor at the top-left corner
+            Protocol::Kitty => {
+                let _ = write!(out, "{}", "\n".repeat(self.rows));
+            }
+            Protocol::Iterm => {
+                let _ = writeln!(out);
+            }
+            _ => {}
+        }
+        let _ = out.flush();
+    }
+}
+
+/// Remove any kitty images we placed. Sixel and iTerm2 images live in the
+/// cells themselves and disappear when the text is redrawn.
+pub fn clear_images() {
+    if detect_protocol() == Protocol::Kitty {
+        print!("{}", kitty_delete());
+        let _ = stdout().flush();
+    }
+}
+
+/// Print a full-resolution sprite inline if the terminal supports it.
+/// Returns false when the caller should fall back to ANSI art.
+pub fn print_image(name: &str, shiny: bool, max_rows: usize) -> bool {
+    let width = crossterm::terminal::size()
+        .ok()
+        .filter(|(w, _)| *w > 0)
+        .map(|(w, _)| w as usize)
+        .unwrap_or(80);
+    match SpriteImage::new(name, shiny, width.saturating_sub(1), max_rows) {
+        Some(image) => {
+            image.print();
+            true
+        }
+        None => false,
+    }
+}
+
+fn kitty_delete() -> String {
+    format!("\x1B_Ga=d,d=I,i={},q=2\x1B\\", KITTY_IMAGE_ID)
+}
+
+fn upscale(sprite: &Sprite, scale: usize) -> Sprite {
+    let width = sprite.width * scale;
+    let height = sprite.height * scale;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(sprite.pixels[(y / scale) * sprite.width + x / scale]);
+        }
+    }
+    Sprite { width, height, pixels }
+}
+
+fn rgba_bytes(sprite: &Sprite) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(sprite.pixels.len() * 4);
+    for px in &sprite.pixels {
+        match px {
+            Some([r, g, b]) => bytes.extend_from_slice(&[*r, *g, *b, 255]),
+            None => bytes.extend_from_slice(&[0, 0, 0, 0]),
+        }
+    }
+    bytes
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Kitty graphics protocol: zlib-compressed RGBA, sent in 4096-byte chunks
+fn encode_kitty(sprite: &Sprite, cols: usize, rows: usize) -> String {
+    let encoded = BASE64.encode(zlib(&rgba_bytes(sprite)));
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1B_Ga=T,f=32,o=z,s={},v={},c={},r={},i={},q=2,C=1,m={};{}\x1B\\",
+                sprite.width, sprite.height, cols, rows, KITTY_IMAGE_ID, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1B_Gm={};{}\x1B\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// iTerm2 inline image protocol (also understood by WezTerm): a base64 PNG
+fn encode_iterm(sprite: &Sprite, cols: usize, rows: usize) -> String {
+    let png = encode_png(sprite);
+    format!(
+        "\x1B]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        cols,
+        rows,
+        BASE64.encode(&png)
+    )
+}
+
+fn encode_png(sprite: &Sprite) -> Vec<u8> {
+    fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        let mut crc = Crc::new();
+        crc.update(kind);
+        crc.update(data);
+        out.extend_from_slice(&crc.sum().to_be_bytes());
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(sprite.width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(sprite.height as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]); // 8-bit RGBA, no interlace
+
+    // Every scanline starts with filter type 0 (none)
+    let rgba = rgba_bytes(sprite);
+    let mut raw = Vec::with_capacity(rgba.len() + sprite.height);
+    for row in rgba.chunks(sprite.width * 4) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &ihdr);
+    chunk(&mut png, b"IDAT", &zlib(&raw));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// DEC sixel graphics. Unset pixels stay transparent (P2 = 1).
+fn encode_sixel(sprite: &Sprite) -> String {
+    let mut palette: Vec<Rgb> = Vec::new();
+    let indices: Vec<Option<usize>> = sprite
+        .pixels
+        .iter()
+        .map(|px| {
+            px.map(|rgb| match palette.iter().position(|c| *c == rgb) {
+                Some(i) => i,
+                None => {
+                    palette.push(rgb);
+                    palette.len() - 1
+                }
+            })
+        })
+        .collect();
+
+    let mut out = format!("\x1BP0;1;0q\"1;1;{};{}", sprite.width, sprite.height);
+    for (i, [r, g, b]) in palette.iter().enumerate() {
+        let pct = |v: u8| v as u32 * 100 / 255;
+        out.push_str(&format!("#{};2;{};{};{}", i, pct(*r), pct(*g), pct(*b)));
+    }
+
+    for band in (0..sprite.height).step_by(6) {
+        for color in 0..palette.len() {
+            let mut sixels: Vec<u8> = (0..sprite.width)
+                .map(|x| {
+                    let mut bits = 0u8;
+                    for dy in 0..6 {
+                        let y = band + dy;
+                        if y < sprite.height && indices[y * sprite.width + x] == Some(color) {
+                            bits |= 1 << dy;
+                        }
+                    }
+                    bits
+                })
+                .collect();
+            while sixels.last() == Some(&0) {
+                sixels.pop();
+            }
+            if sixels.is_empty() {
+                continue;
+            }
+
+            out.push_str(&format!("#{}", color));
+            let mut x = 0;
+            while x < sixels.len() {
+                let run = sixels[x..].iter().take_while(|&&b| b == sixels[x]).count();
+                let ch = (63 + sixels[x]) as char;
+                if run > 3 {
+                    out.push_str(&format!("!{}{}", run, ch));
+                } else {
+                    out.extend(std::iter::repeat_n(ch, run));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1B\\");
+    out
+}
@@ -0,0 +1,114 @@
+use colored::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::events::best_multiplier;
+use crate::models::HABITATS;
+
+/// Per-repo settings file, looked up from the cwd towards the root
+pub const REPO_CONFIG_FILE: &str = ".catch-pokemon.toml";
+
+/// Largest type boost a habitat can give. Repo configs come with any cloned
+/// repo, so they can't be allowed to flood the pool with one type.
+pub const MAX_HABITAT_BOOST: f32 = 3.0;
+
+/// Where the player is working, which makes some types more common
+#[derive(Deserialize, Debug, Clone)]
+pub struct Habitat {
+    pub name: String,
+    /// Files that identify the habitat; "*.ext" matches by extension
+    #[serde(default)]
+    pub markers: Vec<String>,
+    /// Directories (and everything under them) that are this habitat
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Encounter weight multiplier per type, 0 to MAX_HABITAT_BOOST
+    #[serde(default)]
+    pub types: HashMap<String, f32>,
+}
+
+impl Habitat {
+    /// Weight multiplier for a Pokemon with these types
+    pub fn multiplier(&self, types: &[String]) -> f32 {
+        best_multiplier(&self.types, types)
+    }
+
+    /// Bring every boost into range; anything unreadable counts as no boost
+    fn clamped(mut self) -> Self {
+        for boost in self.types.values_mut() {
+            *boost = if boost.is_nan() { 1.0 } else { boost.clamp(0.0, MAX_HABITAT_BOOST) };
+        }
+        self
+    }
+
+    fn matches_dir(&self, dir: &Path) -> bool {
+        self.markers.iter().any(|marker| match marker.strip_prefix("*.") {
+            Some(ext) => fs::read_dir(dir)
+                .map(|entries| {
+                    entries.flatten().any(|e| e.path().extension().is_some_and(|x| x == ext))
+                })
+                .unwrap_or(false),
+            None => dir.join(marker).exists(),
+        })
+    }
+}
+
+/// `.catch-pokemon.toml`, e.g.
+///
+/// ```toml
+/// [habitat]
+/// name = "Volcano lab"
+/// types = { fire = 3.0 }
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct RepoConfig {
+    habitat: Option<Habitat>,
+}
+
+fn load_habitats() -> Vec<Habitat> {
+    serde_json::from_str(HABITATS).unwrap_or_default()
+}
+
+fn read_repo_config(path: &Path) -> Option<Habitat> {
+    let contents = fs::read_to_string(path).ok()?;
+    match toml::from_str::<RepoConfig>(&contents) {
+        Ok(config) => config.habitat.map(Habitat::clamped),
+        Err(e) => {
+            eprintln!("{}", format!("Ignoring invalid {}: {}", path.display(), e).yellow());
+            None
+        }
+    }
+}
+
+/// Habitat for a directory. Walks up towards the home directory (or root)
+/// and the nearest `.catch-pokemon.toml`, project marker or habitat path
+/// such as /tmp wins.
+pub fn detect_habitat(dir: &Path) -> Option<Habitat> {
+    let habitats = load_habitats();
+    let home = dirs::home_dir();
+
+    for ancestor in dir.ancestors() {
+        if let Some(h) = read_repo_config(&ancestor.join(REPO_CONFIG_FILE)) {
+            return Some(h);
+        }
+        if let Some(h) = habitats.iter().find(|h| h.paths.iter().any(|p| Path::new(p) == ancestor)) {
+            return Some(h.clone());
+        }
+        // Don't treat the home directory itself as a project
+        if home.as_deref() == Some(ancestor) {
+            break;
+        }
+        if let Some(h) = habitats.iter().find(|h| h.matches_dir(ancestor)) {
+            return Some(h.clone());
+        }
+    }
+    None
+}
+
+/// Habitat of the current working directory
+pub fn current_habitat() -> Option<Habitat> {
+    std::env::current_dir().ok().and_then(|dir| detect_habitat(&dir))
+}
@@ -0,0 +1,186 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::catch::{encounter_pool, EncounterSlot, SHINY_ODDS};
+use crate::models::{Pokedex, PokemonData, POKEMON_DATA};
+use crate::storage::{get_hunt_path, load_signed, save_signed};
+
+/// Share of all encounters the hunted species takes while hunting
+pub const HUNT_SHARE: f32 = 0.25;
+
+/// Extra shiny rolls from the shiny charm
+const CHARM_ROLLS: u32 = 2;
+
+/// Extra shiny rolls for a chain of this length or longer, best first
+const CHAIN_ROLLS: [(u32, u32); 3] = [(30, 4), (20, 2), (10, 1)];
+
+/// Shiny hunting progress: the hunted species and the current encounter
+/// chain
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct HuntState {
+    /// Species being hunted, e.g. "mr-mime"
+    pub target: Option<String>,
+    /// Species of the current chain
+    pub chain_species: Option<String>,
+    /// Consecutive encounters of `chain_species`
+    pub chain: u32,
+}
+
+impl HuntState {
+    pub fn load() -> Self {
+        load_signed(&get_hunt_path(), "Hunt state").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_hunt_path(), self)
+    }
+
+    /// Count an encounter toward the chain. A different species breaks it,
+    /// except while hunting, when only the hunted species counts.
+    pub fn record_encounter(&mut self, species: &str) {
+        if self.chain_species.as_deref() == Some(species) {
+            self.chain += 1;
+        } else if self.target.is_none() || self.target.as_deref() == Some(species) {
+            self.chain_species = Some(species.to_string());
+            self.chain = 1;
+        }
+    }
+
+    /// Current chain length for this species
+    pub fn chain_for(&self, species: &str) -> u32 {
+        if self.chain_species.as_deref() == Some(species) {
+            self.chain
+        } else {
+            0
+        }
+    }
+}
+
+/// Extra shiny rolls earned by a chain of this length
+pub fn chain_rolls(chain: u32) -> u32 {
+    CHAIN_ROLLS.iter().find(|(len, _)| chain >= *len).map(|(_, rolls)| *rolls).unwrap_or(0)
+}
+
+/// The shiny charm is earned by catching every encounterable species
+pub fn has_shiny_charm(pokedex: &Pokedex, pool: &[EncounterSlot]) -> bool {
+    !pool.is_empty()
+        && pool
+            .iter()
+            .filter(|slot| slot.form.is_none())
+            .all(|slot| pokedex.entries.get(&slot.name.replace('_', "-")).is_some_and(|e| e.caught))
+}
+
+/// Shiny odds for one encounter as "one in N", and what improved them
+pub struct ShinyOdds {
+    pub one_in: u32,
+    pub bonuses: Vec<String>,
+}
+
+impl ShinyOdds {
+    /// Each bonus adds rolls against the base odds, as in the main games
+    pub fn new(base: u32, chain: u32, charm: bool) -> Self {
+        let mut rolls = 1;
+        let mut bonuses = Vec::new();
+        if base < SHINY_ODDS {
+            bonuses.push("mass outbreak".to_string());
+        }
+        let from_chain = chain_rolls(chain);
+        if from_chain > 0 {
+            rolls += from_chain;
+            bonuses.push(format!("chain {}", chain));
+        }
+        if charm {
+            rolls += CHARM_ROLLS;
+            bonuses.push("shiny charm".to_string());
+        }
+        ShinyOdds { one_in: (base / rolls).max(1), bonuses }
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace("'", "").replace(".", "").replace([' ', '_'], "-")
+}
+
+/// Start, stop or show a shiny hunt
+pub fn hunt_command(species: Option<String>, stop: bool) {
+    let mut state = HuntState::load();
+
+    if stop {
+        match state.target.take() {
+            Some(target) => {
+                state.chain_species = None;
+                state.chain = 0;
+                match state.save() {
+                    Ok(()) => println!("Stopped hunting {}.", target.green()),
+                    Err(e) => eprintln!("{}", format!("Could not save hunt: {}", e).red()),
+                }
+            }
+            None => println!("{}", "You're not hunting anything.".dimmed()),
+        }
+        return;
+    }
+
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let pool = encounter_pool(&pokemon_db);
+
+    if let Some(species) = species {
+        let name = normalize(&species);
+        let Some(slot) = pool.iter().find(|s| s.form.is_none() && s.name.replace('_', "-") == name) else {
+            eprintln!("{}", format!("{} can't be encountered, so it can't be hunted.", species).red());
+            return;
+        };
+        if matches!(slot.data.category.as_str(), "legendary" | "mythical") {
+            eprintln!("{}", "Legendary and mythical Pokemon can't be hunted.".red());
+            return;
+        }
+        if state.target.as_deref() != Some(name.as_str()) {
+            // Switching targets starts a new chain
+            state.chain = if state.chain_species.as_deref() == Some(name.as_str()) { state.chain } else { 0 };
+            state.chain_species = Some(name.clone());
+            state.target = Some(name.clone());
+            if let Err(e) = state.save() {
+                eprintln!("{}", format!("Could not save hunt: {}", e).red());
+                return;
+            }
+        }
+        println!(
+            "{}",
+            format!("Now hunting {}! It will make up {:.0}% of encounters.", name, HUNT_SHARE * 100.0)
+                .cyan()
+                .bold()
+        );
+    }
+
+    println!();
+    println!("{}", "  Shiny Hunt".cyan().bold());
+    println!("{}", "  ══════════".cyan());
+    println!();
+    match &state.target {
+        Some(target) => println!("  Hunting:     {}", target.green().bold()),
+        None => println!("  Hunting:     {}", "nothing (catch-pokemon hunt <species>)".dimmed()),
+    }
+    match &state.chain_species {
+        Some(species) => println!("  Chain:       {} x{}", species, state.chain.to_string().yellow().bold()),
+        None => println!("  Chain:       {}", "none".dimmed()),
+    }
+    let next = CHAIN_ROLLS.iter().rev().find(|(len, _)| state.chain < *len);
+    if let Some((len, _)) = next {
+        println!("  Next bonus:  {}", format!("at chain {}", len).dimmed());
+    }
+
+    let charm = has_shiny_charm(&Pokedex::load(), &pool);
+    println!(
+        "  Shiny charm: {}",
+        if charm { "yes".yellow().bold().to_string() } else { "catch every species to earn it".dimmed().to_string() }
+    );
+
+    let odds = ShinyOdds::new(SHINY_ODDS, state.chain, charm);
+    println!("  Shiny odds:  {}", format!("1/{}", odds.one_in).bright_yellow().bold());
+}
@@ -0,0 +1,404 @@
+use chrono::{DateTime, Duration, Local};
+use colored::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::Pokedex;
+use crate::scheduler::ScheduleState;
+use crate::storage::{get_bag_path, load_encrypted, save_encrypted};
+
+/// Weight multiplier incense gives Pokemon of its type
+const INCENSE_BOOST: f32 = 3.0;
+/// Encounters an incense or repel lasts
+const FIELD_ITEM_ENCOUNTERS: u32 = 10;
+/// Incense and repels also wear off after this long
+const FIELD_ITEM_MINUTES: i64 = 60;
+const LURE_MINUTES: i64 = 30;
+/// Percent chance a catch turns up an item
+const FIND_CHANCE: f32 = 30.0;
+
+/// Catch rate multiplier of a Razz Berry
+pub const RAZZ_CATCH_BONUS: f32 = 1.5;
+/// Flee chance multiplier of a Nanab Berry
+pub const NANAB_FLEE_FACTOR: f32 = 0.5;
+
+const TYPES: [&str; 18] = [
+    "normal", "fire", "water", "electric", "grass", "ice", "fighting", "poison", "ground", "flying", "psychic",
+    "bug", "rock", "ghost", "dragon", "dark", "steel", "fairy",
+];
+
+/// Consumable items kept in the bag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Item {
+    /// Attracts Pokemon of one type for 10 encounters
+    Incense,
+    /// Keeps common Pokemon away for 10 encounters
+    Repel,
+    /// Doubles how often encounters happen for 30 minutes
+    Lure,
+    /// Makes the next throw easier
+    RazzBerry,
+    /// Calms the wild Pokemon so it's less likely to flee
+    NanabBerry,
+    /// Doubles the items found with a catch
+    PinapBerry,
+    FireStone,
+    WaterStone,
+    ThunderStone,
+    LeafStone,
+    MoonStone,
+    SunStone,
+    ShinyStone,
+    DuskStone,
+    DawnStone,
+    IceStone,
+    /// Stands in for a trade when evolving
+    LinkingCord,
+}
+
+impl Item {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Item::Incense => "Incense",
+            Item::Repel => "Repel",
+            Item::Lure => "Lure",
+            Item::RazzBerry => "Razz Berry",
+            Item::NanabBerry => "Nanab Berry",
+            Item::PinapBerry => "Pinap Berry",
+            Item::FireStone => "Fire Stone",
+            Item::WaterStone => "Water Stone",
+            Item::ThunderStone => "Thunder Stone",
+            Item::LeafStone => "Leaf Stone",
+            Item::MoonStone => "Moon Stone",
+            Item::SunStone => "Sun Stone",
+            Item::ShinyStone => "Shiny Stone",
+            Item::DuskStone => "Dusk Stone",
+            Item::DawnStone => "Dawn Stone",
+            Item::IceStone => "Ice Stone",
+            Item::LinkingCord => "Linking Cord",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Item::Incense => "Attracts one type for 10 encounters",
+            Item::Repel => "Keeps common Pokemon away for 10 encounters",
+            Item::Lure => "Encounters happen twice as often for 30 minutes",
+            Item::RazzBerry => "Catch rate x1.5 for one throw",
+            Item::NanabBerry => "Halves the flee chance for one throw",
+            Item::PinapBerry => "A catch on this throw finds two items",
+            Item::LinkingCord => "Evolves Pokemon that evolve by trade",
+            _ => "Evolves certain Pokemon",
+        }
+    }
+
+    fn is_berry(&self) -> bool {
+        matches!(self, Item::RazzBerry | Item::NanabBerry | Item::PinapBerry)
+    }
+
+    /// Stones and the Linking Cord, used by 'catch-pokemon evolve'
+    pub fn is_evolution_item(&self) -> bool {
+        !self.is_berry() && !matches!(self, Item::Incense | Item::Repel | Item::Lure)
+    }
+
+    /// Relative chance of finding this item with a catch
+    fn find_weight(&self) -> u32 {
+        match self {
+            Item::RazzBerry => 40,
+            Item::NanabBerry => 20,
+            Item::PinapBerry => 20,
+            Item::Incense => 8,
+            Item::Repel => 7,
+            Item::Lure => 5,
+            Item::LinkingCord => 2,
+            // Each stone
+            _ => 1,
+        }
+    }
+}
+
+/// A berry fed to the wild Pokemon with a throw
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Berry {
+    /// Catch rate x1.5
+    Razz,
+    /// Halves the flee chance
+    Nanab,
+    /// Two items if the throw catches
+    Pinap,
+}
+
+impl Berry {
+    pub fn item(&self) -> Item {
+        match self {
+            Berry::Razz => Item::RazzBerry,
+            Berry::Nanab => Item::NanabBerry,
+            Berry::Pinap => Item::PinapBerry,
+        }
+    }
+}
+
+/// A field item in use. Wears off after its encounters run out or at
+/// `expires_at`, whichever comes first.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActiveEffect {
+    pub item: Item,
+    /// Type attracted by incense
+    #[serde(default)]
+    pub pokemon_type: Option<String>,
+    #[serde(default)]
+    pub encounters_left: Option<u32>,
+    pub expires_at: DateTime<Local>,
+}
+
+impl ActiveEffect {
+    fn is_expired(&self, now: DateTime<Local>) -> bool {
+        now >= self.expires_at || self.encounters_left == Some(0)
+    }
+
+    /// e.g. "Incense (fire) - 6 encounters left"
+    pub fn describe(&self, now: DateTime<Local>) -> String {
+        let name = match &self.pokemon_type {
+            Some(t) => format!("{} ({})", self.item.display_name(), t),
+            None => self.item.display_name().to_string(),
+        };
+        match self.encounters_left {
+            Some(0) => format!("{} - last encounter", name),
+            Some(n) => format!("{} - {} encounter{} left", name, n, if n == 1 { "" } else { "s" }),
+            None => format!("{} - {} min left", name, (self.expires_at - now).num_minutes().max(1)),
+        }
+    }
+}
+
+/// Items and active effects, encrypted like the PC
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Bag {
+    #[serde(default)]
+    pub items: BTreeMap<Item, u32>,
+    #[serde(default)]
+    pub effects: Vec<ActiveEffect>,
+}
+
+impl Bag {
+    /// A new trainer's bag comes with a few items to try
+    fn starter_kit() -> Self {
+        let mut bag = Bag::default();
+        bag.add(Item::RazzBerry, 3);
+        bag.add(Item::Incense, 1);
+        bag.add(Item::Repel, 1);
+        bag.add(Item::Lure, 1);
+        bag
+    }
+
+    pub fn load() -> Self {
+        let path = get_bag_path();
+        if !path.exists() {
+            return Bag::grant_starter_kit();
+        }
+        let mut bag: Bag = load_encrypted(&path, "bag").unwrap_or_default();
+        bag.effects.retain(|e| !e.is_expired(Local::now()));
+        bag
+    }
+
+    /// The starter items, once per trainer; an empty bag after that
+    fn grant_starter_kit() -> Self {
+        let mut pokedex = Pokedex::load();
+        if pokedex.starter_kit_granted {
+            return Bag::default();
+        }
+        pokedex.starter_kit_granted = true;
+        if pokedex.save().is_err() {
+            return Bag::default();
+        }
+        let bag = Bag::starter_kit();
+        if let Err(e) = bag.save() {
+            eprintln!("{}", format!("Could not save your bag: {}", e).red());
+        }
+        bag
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_encrypted(&get_bag_path(), self)
+    }
+
+    pub fn add(&mut self, item: Item, n: u32) {
+        *self.items.entry(item).or_default() += n;
+    }
+
+    /// Remove one of an item, returning false if there is none
+    pub fn take(&mut self, item: Item) -> bool {
+        match self.items.get_mut(&item) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                if *n == 0 {
+                    self.items.remove(&item);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn active(&self, item: Item) -> Option<&ActiveEffect> {
+        self.effects.iter().find(|e| e.item == item)
+    }
+
+    /// Encounter weight multiplier from incense and repels
+    pub fn weight_multiplier(&self, types: &[String], category: &str) -> f32 {
+        if self.active(Item::Repel).is_some() && category == "common" {
+            return 0.0;
+        }
+        match self.active(Item::Incense).and_then(|e| e.pokemon_type.as_ref()) {
+            Some(t) if types.contains(t) => INCENSE_BOOST,
+            _ => 1.0,
+        }
+    }
+
+    /// Count an encounter against every effect that lasts for N encounters.
+    /// Used-up effects are dropped on the next load.
+    pub fn tick_encounter(&mut self) {
+        for effect in &mut self.effects {
+            if let Some(n) = effect.encounters_left.as_mut() {
+                *n = n.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Items turned up by a catch. A Pinap Berry guarantees two.
+pub fn find_items(pinap: bool, rng: &mut impl Rng) -> Vec<Item> {
+    let count = if pinap {
+        2
+    } else if rng.gen_range(0.0..100.0) < FIND_CHANCE {
+        1
+    } else {
+        0
+    };
+    let all = <Item as clap::ValueEnum>::value_variants();
+    let total: u32 = all.iter().map(|i| i.find_weight()).sum();
+    (0..count)
+        .map(|_| {
+            let mut roll = rng.gen_range(0..total);
+            *all.iter()
+                .find(|i| {
+                    if roll < i.find_weight() {
+                        true
+                    } else {
+                        roll -= i.find_weight();
+                        false
+                    }
+                })
+                .unwrap_or(&Item::RazzBerry)
+        })
+        .collect()
+}
+
+pub fn bag_command() {
+    let bag = Bag::load();
+    let now = Local::now();
+
+    println!("{}", "  Bag".cyan().bold());
+    println!("{}", "  ═══".cyan());
+    println!();
+    if bag.items.is_empty() {
+        println!("  {}", "Empty. Catching Pokemon sometimes turns up items.".dimmed());
+    }
+    for (item, count) in &bag.items {
+        println!(
+            "  {:<13} x{:<3} {}",
+            item.display_name().green().bold(),
+            count,
+            item.description().dimmed()
+        );
+    }
+
+    if !bag.effects.is_empty() {
+        println!();
+        println!("{}", "  Active".cyan().bold());
+        for effect in &bag.effects {
+            println!("  {}", effect.describe(now).yellow());
+        }
+    }
+
+    println!();
+    println!("{}", "  Use a field item with 'catch-pokemon use <item>'. Feed berries with 'catch <berry>'.".dimmed());
+}
+
+/// Use a field item (incense, repel or lure)
+pub fn use_command(item: Item, pokemon_type: Option<String>) {
+    if item.is_berry() {
+        println!("{}", "Berries are fed to a wild Pokemon during a throw: catch razz, catch nanab or catch pinap.".yellow());
+        return;
+    }
+    if item.is_evolution_item() {
+        println!("{}", format!("The {} is used with 'catch-pokemon evolve'.", item.display_name()).yellow());
+        return;
+    }
+
+    let pokemon_type = match (item, pokemon_type) {
+        (Item::Incense, Some(t)) => {
+            let t = t.to_lowercase();
+            if !TYPES.contains(&t.as_str()) {
+                eprintln!("{}", format!("Unknown type '{}'.", t).red());
+                return;
+            }
+            Some(t)
+        }
+        (Item::Incense, None) => {
+            eprintln!("{}", "Choose a type for the incense, e.g. catch-pokemon use incense --type fire".red());
+            return;
+        }
+        (_, Some(_)) => {
+            eprintln!("{}", "Only incense takes a type.".red());
+            return;
+        }
+        (_, None) => None,
+    };
+
+    let mut bag = Bag::load();
+    let now = Local::now();
+    if let Some(effect) = bag.active(item) {
+        println!("{}", format!("Already active: {}", effect.describe(now)).yellow());
+        return;
+    }
+    if !bag.take(item) {
+        println!("{}", format!("You don't have a {}.", item.display_name()).red());
+        return;
+    }
+
+    let effect = match item {
+        Item::Lure => ActiveEffect {
+            item,
+            pokemon_type: None,
+            encounters_left: None,
+            expires_at: now + Duration::minutes(LURE_MINUTES),
+        },
+        _ => ActiveEffect {
+            item,
+            pokemon_type,
+            encounters_left: Some(FIELD_ITEM_ENCOUNTERS),
+            expires_at: now + Duration::minutes(FIELD_ITEM_MINUTES),
+        },
+    };
+    let description = effect.describe(now);
+    let expires_at = effect.expires_at;
+    bag.effects.push(effect);
+    if let Err(e) = bag.save() {
+        eprintln!("{}", format!("Could not save your bag: {}", e).red());
+        return;
+    }
+
+    // The scheduler runs on every prompt and can't decrypt the bag, so it
+    // gets its own copy of the lure's expiry
+    if item == Item::Lure {
+        let mut schedule = ScheduleState::load();
+        schedule.lure_until = Some(expires_at);
+        if let Err(e) = schedule.save() {
+            eprintln!("{}", format!("Could not update the encounter schedule: {}", e).red());
+        }
+    }
+
+    println!("{}", format!("You used {}! {}", item.display_name(), description).green().bold());
+}
@@ -1,14 +1,37 @@
+mod achievements;
+mod annotations;
 mod api;
 mod battle;
+mod buddy;
+mod candy;
 mod catch;
+mod config;
 mod crypto;
 mod display;
+mod eggs;
+mod encounter_log;
+mod events;
+mod evolution;
+mod graphics;
+mod habitat;
+mod hunt;
+mod items;
 mod models;
 mod pc_tui;
+mod pity;
 mod pokedex_tui;
+mod research;
+mod rng;
+mod roaming;
+mod scheduler;
 mod setup;
+mod simulate;
+mod sprites;
+mod stats;
 mod storage;
 mod trade;
+mod training;
+mod wild_battle;
 
 use clap::{Parser, Subcommand};
 use colored::*;
@@ -23,7 +46,7 @@ use std::io::{stdout, Write};
 Available commands:\n  \
 catch     Try to catch a Pokemon with different Pokeball types\n  \
 pc        View your Pokemon collection with detailed statistics\n  \
-release   Release Pokemon back to the wild\n  \
+release   Transfer Pokemon to the Professor for candy\n  \
 status    Check if you've caught a Pokemon before\n  \
 clear     Clear your entire Pokemon collection\n\n\
 Examples:\n  \
@@ -35,16 +58,29 @@ catch-pokemon release rattata --number 5"
 struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    /// Seed every game roll for reproducible runs (development builds only).
+    /// Seeded runs use a separate test profile and can't touch the real PC.
+    #[cfg(debug_assertions)]
+    #[arg(long, global = true, hide = true)]
+    seed: Option<u64>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Try to catch a Pokemon with animated Pokeball throwing
     #[command(long_about = "Attempt to catch a Pokemon using a Poke Ball.\n\n\
-Each Pokemon has different catch rates based on rarity:\n\
-- Legendary/Mythical: 3% base rate (very hard)\n\
-- Pseudo-legendary/Starters: 45% base rate (hard)\n\
-- Common Pokemon: 120-255% base rate (easy)\n\n\
+Catching uses the Gen III+ formula. Each species has a catch rate from 3\n\
+(legendaries) to 255 (the most common), which becomes a modified rate:\n\
+  a = (3·HPmax − 2·HPcur) · catch_rate · ball / (3·HPmax) · status · bonus\n\
+The ball shakes once for each passed shake check, and the Pokemon is caught\n\
+when four checks pass. A critical capture, likelier the more species you've\n\
+caught, needs just one.\n\n\
+- HP: weakening a Pokemon in the wild battle raises a up to three times\n\
+- Status: sleep and freeze x2.5, paralysis, poison and burn x1.5\n\
+- Ball and bonus: a Poke Ball is x1, and a Razz Berry adds x1.5\n\n\
+At full HP with a Poke Ball, a Pikachu (190) is caught about 35% of the time\n\
+and a legendary (3) about 1.6%.\n\n\
 Examples:\n\
   catch-pokemon catch pikachu\n\
   catch-pokemon catch bulbasaur --hide-pokemon\n\
@@ -66,6 +102,10 @@ Examples:\n\
         #[arg(long, default_value = "false", hide = true)]
         shiny: bool,
 
+        /// Regional, Mega or alternate form (set by encounter system)
+        #[arg(long, hide = true)]
+        form: Option<String>,
+
         /// Session token from encounter (required to prevent manual catching)
         #[arg(long, hide = true)]
         token: Option<String>,
@@ -73,6 +113,50 @@ Examples:\n\
         /// Attempt number for rolling flee rate (set by shell function)
         #[arg(long, default_value = "1", hide = true)]
         attempt: u32,
+
+        /// Habitat of the encounter (set by shell function)
+        #[arg(long, hide = true)]
+        habitat: Option<String>,
+
+        /// Feed a berry from your bag with this throw
+        #[arg(long, value_enum)]
+        berry: Option<items::Berry>,
+    },
+
+    /// Battle the wild Pokemon before throwing to weaken it
+    #[command(long_about = "Send out a Pokemon from your battle team against the current wild Pokemon.\n\n\
+Attacks lower its HP and sleep or paralysis give it a status, both of which\n\
+raise the catch chance. Attack damage depends on power rank and type\n\
+effectiveness, and knocking the wild Pokemon out ends the encounter.\n\
+Every turn raises the chance it flees, just like a throw.\n\n\
+Use the 'fight' shell function during an encounter:\n\
+  fight                 Attack with your team lead\n\
+  fight sleep           Try to put it to sleep\n\
+  fight paralyze pikachu   Use pikachu from your team")]
+    Fight {
+        /// Name of the wild Pokemon
+        #[arg(hide = true)]
+        pokemon: String,
+
+        /// Move to use
+        #[arg(long = "move", value_enum, default_value = "attack")]
+        action: wild_battle::FightMove,
+
+        /// Team Pokemon to fight with (defaults to your team lead)
+        #[arg(long)]
+        with: Option<String>,
+
+        /// Regional, Mega or alternate form (set by encounter system)
+        #[arg(long, hide = true)]
+        form: Option<String>,
+
+        /// Session token from encounter
+        #[arg(long, hide = true)]
+        token: Option<String>,
+
+        /// Attempt number for rolling flee rate (set by shell function)
+        #[arg(long, default_value = "1", hide = true)]
+        attempt: u32,
     },
 
     /// Display your Pokemon collection with detailed statistics
@@ -82,6 +166,7 @@ Shows detailed information including:\n\
 - Breakdown of catches by ball type for each Pokemon\n\
 - Summary statistics of total catches by ball type\n\
 - Recent catch history with timestamps\n\n\
+Searching matches nicknames and tags as well as species names.\n\n\
 Examples:\n\
   catch-pokemon pc\n\
   catch-pokemon pc --search\n\
@@ -92,21 +177,25 @@ Examples:\n\
         search: bool,
     },
 
-    /// Release Pokemon from your PC back to the wild
-    #[command(long_about = "Release Pokemon from your PC storage back to the wild.\n\n\
-You can release single Pokemon or multiple at once. This action cannot be undone!\n\
-If you specify more Pokemon than you have, it will release all available.\n\n\
+    /// Transfer Pokemon from your PC to the Professor for candy
+    #[command(long_about = "Transfer Pokemon from your PC storage to the Professor.\n\n\
+Each Pokemon transferred earns one candy for its evolution family (Charmander,\n\
+Charmeleon and Charizard all give Charmander candy). Candy is spent on evolving\n\
+and on rerolling stats. Shinies are transferred last, and locked Pokemon\n\
+('catch-pokemon annotate <number> --lock') are never transferred.\n\n\
+You can transfer single Pokemon or multiple at once. This action cannot be undone!\n\
+If you specify more Pokemon than you have, it will transfer all available.\n\n\
 Examples:\n\
   catch-pokemon release pidgey\n\
   catch-pokemon release rattata --number 10\n\
   catch-pokemon release pikachu -n 3")]
     Release {
-        /// Name of the Pokemon to release (case insensitive)
+        /// Name of the Pokemon to transfer (case insensitive)
         pokemon: String,
 
-        /// Number of this Pokemon to release (releases all if you don't have enough)
+        /// Number of this Pokemon to transfer (transfers all if you don't have enough)
         #[arg(short = 'n', long, default_value = "1",
-              help = "How many of this Pokemon to release (default: 1)")]
+              help = "How many of this Pokemon to transfer (default: 1)")]
         number: usize,
     },
 
@@ -135,7 +224,8 @@ Scripting example:\n\
     /// Clear your entire Pokemon collection (DESTRUCTIVE)
     #[command(long_about = "Permanently delete all Pokemon from your PC storage.\n\n\
 ⚠️  WARNING: This action cannot be undone!\n\
-All caught Pokemon, catch history, and statistics will be lost.\n\
+All caught Pokemon, catch history, and statistics will be lost, along\n\
+with your bag, candy, eggs, training, research, achievements and encounter log.\n\
 You will be prompted to confirm before deletion.\n\n\
 Example:\n\
   catch-pokemon clear")]
@@ -159,7 +249,8 @@ Examples:\n\
     /// Set up shell functions (catch, pc, pokemon_encounter, etc.)
     #[command(long_about = "Install shell functions for the Pokemon catching game.\n\n\
 This sets up convenient shell commands:\n\
-- catch: Attempt to catch the current wild Pokemon\n\
+- catch: Attempt to catch the current wild Pokemon (optionally with a berry)\n\
+- fight: Weaken the wild Pokemon with your battle team before throwing\n\
 - pc: View your Pokemon collection\n\
 - pokemon_encounter: Generate a new wild Pokemon encounter\n\
 - pokemon_new: Force a new encounter\n\
@@ -215,6 +306,8 @@ Example:\n\
 Your battle team holds up to 20 Pokemon selected from your PC.\n\
 This is the roster you bring to battles — opponents see your battle team,\n\
 and you pick 6 from it each round.\n\n\
+Team Pokemon level up from battle rounds they win and from your first\n\
+terminal each day. Your buddy (catch-pokemon buddy) grows friendlier as you play.\n\n\
 The battle team is stored in an encrypted file alongside your PC.\n\n\
 Examples:\n\
   catch-pokemon team                    # View your battle team\n\
@@ -275,34 +368,480 @@ Examples:\n\
   catch-pokemon encounter --show-pokemon")]
     Encounter {
         /// Display the Pokemon sprite alongside the name
-        #[arg(long, help = "Show the Pokemon sprite using pokemon-colorscripts")]
+        #[arg(long, help = "Show the Pokemon sprite")]
         show_pokemon: bool,
     },
+
+    /// Print a Pokemon sprite (used by the shell functions)
+    #[command(hide = true)]
+    Sprite {
+        /// Name of the Pokemon
+        pokemon: String,
+
+        /// Show the shiny variant
+        #[arg(long)]
+        shiny: bool,
+    },
+
+    /// Simulate encounters offline and report balancing statistics
+    #[command(long_about = "Run many offline encounter and catch sessions using the real weights,\n\
+shiny odds and flee escalation, then report:\n\
+- Encounter probability per category (exact and simulated)\n\
+- Catch and flee rates, and Poke Balls thrown per catch\n\
+- Expected encounters to catch every species at least once\n\n\
+Each session throws Poke Balls until the Pokemon is caught or flees.\n\
+Use --hp and --status to see how a weakened Pokemon changes the odds.\n\
+Nothing is saved to your PC.\n\n\
+Examples:\n\
+  catch-pokemon simulate\n\
+  catch-pokemon simulate --sessions 5000000\n\
+  catch-pokemon simulate --hp 0.25 --status sleep\n\
+  catch-pokemon simulate --json > balance.json")]
+    Simulate {
+        /// Number of encounter sessions to simulate
+        #[arg(short = 'n', long, default_value = "1000000")]
+        sessions: u64,
+
+        /// Remaining HP of the wild Pokemon as a fraction (0.0 - 1.0)
+        #[arg(long, default_value = "1.0")]
+        hp: f32,
+
+        /// Status of the wild Pokemon: none, poison, burn, paralysis, sleep, freeze
+        #[arg(long, default_value = "none")]
+        status: String,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show today's mass outbreak and upcoming ones
+    #[command(long_about = "Every day one species breaks out for a few hours. During the outbreak it\n\
+appears far more often and has much better shiny odds. The species and time\n\
+window are derived from the date, so everyone gets the same outbreak.\n\n\
+Also lists the time-of-day and seasonal modifiers that are active right now.\n\n\
+Examples:\n\
+  catch-pokemon events\n\
+  catch-pokemon events --days 14")]
+    Events {
+        /// Number of days to show, starting today
+        #[arg(short, long, default_value = "7")]
+        days: u32,
+    },
+
+    /// Hunt one species for a shiny
+    #[command(long_about = "While hunting, the chosen species makes up a quarter of all encounters and every\n\
+other species appears less often. Each encounter of the hunted species extends\n\
+your chain; chains of 10, 20 and 30 add extra shiny rolls. Catching every species\n\
+earns the shiny charm, which adds two more.\n\n\
+Without arguments, shows the current hunt, chain and shiny odds.\n\n\
+Examples:\n\
+  catch-pokemon hunt dratini\n\
+  catch-pokemon hunt\n\
+  catch-pokemon hunt --stop")]
+    Hunt {
+        /// Species to hunt
+        species: Option<String>,
+        /// Stop hunting and reset the chain
+        #[arg(long, conflicts_with = "species")]
+        stop: bool,
+    },
+
+    /// Show the items in your bag
+    #[command(long_about = "Show your items and any that are active.\n\n\
+Catching a Pokemon sometimes turns up an item. Field items are used with\n\
+'catch-pokemon use'; berries are fed with a throw ('catch razz' in the shell).\n\n\
+Example:\n\
+  catch-pokemon bag")]
+    Bag,
+
+    /// Use an incense, repel or lure
+    #[command(long_about = "Use a field item from your bag.\n\n\
+- Incense: Pokemon of one type appear 3x as often for 10 encounters\n\
+- Repel: common Pokemon stay away for 10 encounters\n\
+- Lure: encounters happen twice as often for 30 minutes\n\n\
+Incense and repels also wear off after an hour.\n\n\
+Examples:\n\
+  catch-pokemon use incense --type ghost\n\
+  catch-pokemon use repel\n\
+  catch-pokemon use lure")]
+    Use {
+        /// Item to use
+        #[arg(value_enum)]
+        item: items::Item,
+
+        /// Type attracted by an incense
+        #[arg(long = "type", value_name = "TYPE")]
+        pokemon_type: Option<String>,
+    },
+
+    /// Evolve a Pokemon in your PC
+    #[command(long_about = "Evolve a Pokemon in your PC, keeping its original catch date.\n\n\
+Pick it by name (shinies are picked first) or by its PC number, where 1 is your\n\
+first catch. Evolving uses something up:\n\
+- Stone evolutions use that stone from your bag\n\
+- Trade evolutions use a Linking Cord\n\
+- Level evolutions use 1-3 candy of the Pokemon's family, more for higher levels\n\
+- Friendship and special evolutions use 2 candy\n\n\
+Candy comes from transferring Pokemon with 'catch-pokemon release'.\n\
+Stones and Linking Cords are sometimes found when catching Pokemon.\n\n\
+Examples:\n\
+  catch-pokemon evolve charmander\n\
+  catch-pokemon evolve eevee --into umbreon\n\
+  catch-pokemon evolve 12")]
+    Evolve {
+        /// Species name or PC number
+        #[arg(value_name = "ID|NAME")]
+        pokemon: String,
+
+        /// Species to evolve into, for Pokemon with more than one evolution
+        #[arg(long, value_name = "SPECIES")]
+        into: Option<String>,
+    },
+
+    /// Show your eggs and hatch the ones that are ready
+    #[command(long_about = "Show the egg incubator and hatch any eggs that are ready.\n\n\
+Eggs come from the daycare and from every 25th catch (milestone eggs hold a baby\n\
+Pokemon like Pichu or Riolu). The incubator holds 3 eggs. Every encounter and\n\
+every new terminal is a step; eggs hatch after 10-30 steps depending on the\n\
+species. Hatched Pokemon go to your PC with 'Egg' as their ball.\n\n\
+Examples:\n\
+  catch-pokemon eggs\n\
+  catch-pokemon eggs --skip-animation")]
+    Eggs {
+        /// Skip the hatching animation
+        #[arg(long)]
+        skip_animation: bool,
+    },
+
+    /// Leave two Pokemon of the same family at the daycare
+    #[command(long_about = "Leave two Pokemon of the same evolution family at the daycare.\n\n\
+They leave your PC while they're there and find an egg every 20 steps, as long as\n\
+the incubator has room. The egg hatches into the family's first species, so two\n\
+Pikachu find a Pichu egg. Legendary and mythical Pokemon don't lay eggs.\n\n\
+Pick each Pokemon by name or PC number. Without arguments, shows who is there.\n\n\
+Examples:\n\
+  catch-pokemon daycare pikachu raichu\n\
+  catch-pokemon daycare dratini dratini\n\
+  catch-pokemon daycare --withdraw")]
+    Daycare {
+        /// Two Pokemon (names or PC numbers)
+        #[arg(value_name = "ID|NAME", num_args = 0..=2)]
+        pokemon: Vec<String>,
+
+        /// Take both Pokemon back to your PC
+        #[arg(long, conflicts_with = "pokemon")]
+        withdraw: bool,
+    },
+
+    /// Show your candy
+    #[command(long_about = "Show how much candy you have for each evolution family.\n\n\
+Candy comes from transferring Pokemon with 'catch-pokemon release'. Spend it on\n\
+evolving ('catch-pokemon evolve') or on rerolling one Pokemon's stats\n\
+('catch-pokemon reroll').\n\n\
+Example:\n\
+  catch-pokemon candy")]
+    Candy,
+
+    /// Browse achievements and your progress toward them
+    #[command(long_about = "Browse achievements with progress bars.\n\n\
+Achievements are checked after every catch, transfer, evolution, hatch and\n\
+ranked battle, and announced as soon as they unlock. They're stored signed,\n\
+so they can't be edited by hand. The rules live in data/achievements.json.\n\n\
+Examples:\n\
+  catch-pokemon achievements          # Browse (Tab filters unlocked / in progress)\n\
+  catch-pokemon achievements --list   # Print them, e.g. to share with your team")]
+    Achievements {
+        /// Print the list instead of opening the browser
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Show every encounter, including the ones that got away
+    #[command(long_about = "Show your encounters, newest first, with each ball thrown and how it ended.\n\n\
+Every encounter and every throw is appended to a signed log, encounter_log.jsonl\n\
+in your storage directory. Each line is chained to the one before it, and the\n\
+newest line's hash is signed into encounter_log_head.json, so lines can't be\n\
+edited, removed, reordered or cut off the end. Encounters end as:\n\
+  caught    A ball worked\n\
+  fled      It ran away after a ball or a fight turn\n\
+  fainted   It was knocked out with `fight`\n\
+  escaped   Balls were thrown, but the encounter ran out\n\
+  skipped   The encounter ran out before any ball was thrown\n\
+  active    Still open (encounters last 30 minutes)\n\n\
+Examples:\n\
+  catch-pokemon log                          # The last 20 encounters\n\
+  catch-pokemon log --species dragonite --result fled\n\
+  catch-pokemon log --shiny --since 2026-01-01 --limit 100")]
+    Log {
+        /// Only this species, e.g. mr-mime
+        #[arg(long)]
+        species: Option<String>,
+
+        /// Only encounters that ended this way
+        #[arg(long, value_enum)]
+        result: Option<encounter_log::EncounterResult>,
+
+        /// Only shiny encounters
+        #[arg(long)]
+        shiny: bool,
+
+        /// Only encounters on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<chrono::NaiveDate>,
+
+        /// How many encounters to list
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show today's and this week's research tasks
+    #[command(long_about = "Show your research tasks and their progress.\n\n\
+Three new tasks come every day and two every week, such as 'Catch 3 water types'\n\
+or 'Encounter 5 bug types'. Everyone gets the same tasks on the same day.\n\
+Catches are counted from your signed PC and encounters from your encounter log\n\
+('catch-pokemon log'). Releasing a Pokemon before a task is done takes it back\n\
+off the count. Finished tasks pay out items to your bag\n\
+straight away: berries and lures for daily tasks, evolution items for weekly ones.\n\n\
+Example:\n\
+  catch-pokemon research")]
+    Research,
+
+    /// Show your buddy, or pick one from your battle team
+    #[command(long_about = "Your buddy is a battle team Pokemon that comes along as you play.\n\
+It gains friendship on your first terminal each day and with every encounter.\n\n\
+'catch-pokemon prompt' shows your buddy in your shell prompt or tmux status bar.\n\n\
+Examples:\n\
+  catch-pokemon buddy                 # Show your buddy and prompt setup\n\
+  catch-pokemon buddy set pikachu     # Pick a buddy from your battle team\n\
+  catch-pokemon buddy clear")]
+    Buddy {
+        #[command(subcommand)]
+        action: Option<BuddyAction>,
+    },
+
+    /// Print your buddy for a shell prompt or status bar (fast)
+    #[command(long_about = "Print a tiny coloured buddy glyph and its mood, plus a yellow ! while a\n\
+wild Pokemon is waiting. Prints nothing without a buddy or a wild Pokemon.\n\n\
+Reads a small cache refreshed on catches and encounters, so it's fast enough\n\
+for every prompt.\n\n\
+Mood follows friendship: · calm, ☺ content, ♪ happy, ♥ ecstatic.\n\
+A shiny buddy shows ✦ instead of ●, coloured by its first type.\n\n\
+Examples:\n\
+  PS1='$(catch-pokemon prompt --format bash) '\"$PS1\"\n\
+  PROMPT='$(catch-pokemon prompt --format zsh) '$PROMPT   # setopt PROMPT_SUBST\n\
+  set -g status-right '#(catch-pokemon prompt --format tmux)'\n\n\
+starship.toml:\n\
+  [custom.buddy]\n\
+  command = \"catch-pokemon prompt\"\n\
+  when = true")]
+    Prompt {
+        /// Colour escapes for where the output goes
+        #[arg(long, value_enum, default_value = "ansi")]
+        format: buddy::PromptFormat,
+
+        /// Include the buddy's name
+        #[arg(long)]
+        name: bool,
+    },
+
+    /// Spend candy to reroll one Pokemon's stats
+    #[command(long_about = "Reroll the attack, defense and stamina of one Pokemon in your PC.\n\n\
+Costs 5 candy of its family. The new stats replace the old ones, even if they're\n\
+worse. Pick the Pokemon by name (shinies are picked first) or by its PC number,\n\
+where 1 is your first catch.\n\n\
+Examples:\n\
+  catch-pokemon reroll dratini\n\
+  catch-pokemon reroll 12")]
+    Reroll {
+        /// Species name or PC number
+        #[arg(value_name = "ID|NAME")]
+        pokemon: String,
+    },
+
+    /// Give one Pokemon a nickname, tags or notes, or lock it
+    #[command(long_about = "Attach a nickname, tags, notes and a lock to one Pokemon in your PC.\n\n\
+Pick the Pokemon by name (shinies are picked first), by its PC number, where 1\n\
+is your first catch, or by its nickname. Without options it shows what's set.\n\
+Locked Pokemon can't be transferred. Nicknames show on your battle team, and\n\
+'catch-pokemon pc' searches nicknames and tags. An empty --nickname or --note\n\
+removes it.\n\n\
+Examples:\n\
+  catch-pokemon annotate 12\n\
+  catch-pokemon annotate pikachu --nickname Sparky --lock\n\
+  catch-pokemon annotate sparky --tag starter --tag favourite\n\
+  catch-pokemon annotate 12 --note \"Hatched from my first egg\"")]
+    Annotate {
+        /// Species name, PC number or nickname
+        #[arg(value_name = "ID|NAME")]
+        pokemon: String,
+
+        /// Set a nickname ("" removes it)
+        #[arg(long)]
+        nickname: Option<String>,
+
+        /// Add a tag (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Remove a tag (repeatable)
+        #[arg(long = "untag", value_name = "TAG")]
+        untags: Vec<String>,
+
+        /// Set the notes ("" removes them)
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Protect this Pokemon from being transferred
+        #[arg(long)]
+        lock: bool,
+
+        /// Allow this Pokemon to be transferred again
+        #[arg(long)]
+        unlock: bool,
+
+        /// Remove everything before applying the other options
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Browse your stats: catch rates, streaks, activity and the pity timer
+    #[command(long_about = "Browse a dashboard of your collection and encounters: catch and shiny rates,\n\
+your rarest catch, a calendar of catches per day, daily streaks, Pokedex\n\
+completion over time, category and type breakdowns and per-ball success rates.\n\
+It also shows how long it has been since you saw a legendary, mythical or\n\
+pseudo-legendary Pokemon. Piped output is plain text, and --json exports\n\
+everything, e.g. for a team dashboard.\n\n\
+Pity is opt-in. Once enabled, every encounter past the threshold without a rare\n\
+Pokemon raises their encounter weight, until one appears and the counter resets.\n\
+Settings are saved to config.toml in your storage directory.\n\n\
+Examples:\n\
+  catch-pokemon stats                      # Browse (←→ switch pages)\n\
+  catch-pokemon stats --json > stats.json  # Export\n\
+  catch-pokemon stats --pity on\n\
+  catch-pokemon stats --pity-after 100 --pity-ramp 0.2")]
+    Stats {
+        /// Turn the pity timer 'on' or 'off'
+        #[arg(long, value_name = "on|off")]
+        pity: Option<String>,
+
+        /// Encounters without a rare Pokemon before the pity ramp starts
+        #[arg(long, value_name = "N")]
+        pity_after: Option<u32>,
+
+        /// Rare weight multiplier added per encounter past the threshold
+        #[arg(long, value_name = "MULTIPLIER")]
+        pity_ramp: Option<f32>,
+
+        /// Print the stats as JSON, e.g. to export to a dashboard
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Control how often wild Pokemon appear
+    #[command(long_about = "View or change the encounter schedule shared by all your shells.\n\n\
+By default every new terminal triggers an encounter. You can limit encounters\n\
+to one per N minutes, roll a chance on each shell start, silence encounters\n\
+during quiet hours or days, and opt in to an encounter after N commands.\n\n\
+Settings are saved to config.toml in your storage directory.\n\n\
+Examples:\n\
+  catch-pokemon schedule                          # Show current schedule\n\
+  catch-pokemon schedule --every 30               # At most one encounter per 30 min\n\
+  catch-pokemon schedule --chance 25              # 25% chance per new shell\n\
+  catch-pokemon schedule --quiet-hours 22:00-08:00\n\
+  catch-pokemon schedule --quiet-days sat,sun\n\
+  catch-pokemon schedule --after-commands 50      # Encounter every 50 commands")]
+    Schedule {
+        /// Minimum minutes between encounters (0 = no limit)
+        #[arg(long, value_name = "MINUTES")]
+        every: Option<u32>,
+
+        /// Percent chance that a new shell triggers an encounter
+        #[arg(long, value_name = "PERCENT")]
+        chance: Option<f32>,
+
+        /// Quiet hours in local time, e.g. 22:00-08:00 (or 'off')
+        #[arg(long, value_name = "RANGE")]
+        quiet_hours: Option<String>,
+
+        /// Comma-separated quiet days, e.g. sat,sun (or 'none')
+        #[arg(long, value_name = "DAYS")]
+        quiet_days: Option<String>,
+
+        /// Encounter after this many shell commands (0 = off)
+        #[arg(long, value_name = "N")]
+        after_commands: Option<u32>,
+
+        /// Ask whether an encounter should fire now (used by shell hooks)
+        #[arg(long, value_enum, hide = true)]
+        check: Option<scheduler::Trigger>,
+
+        /// Print the scheduler decision when used with --check
+        #[arg(long, hide = true)]
+        verbose: bool,
+
+        /// Exit 0 if the command trigger is on (used by shell hooks)
+        #[arg(long, hide = true)]
+        commands_enabled: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BuddyAction {
+    /// Make a battle team Pokemon your buddy
+    Set {
+        /// Pokemon on your battle team
+        pokemon: String,
+    },
+    /// Stop having a buddy
+    Clear,
 }
 
 fn release_pokemon(pokemon_name: String, number: usize) {
-    let mut storage = models::PcStorage::load();
+    // Transfers mint candy, so never build on a tampered PC
+    let Some(mut storage) = models::PcStorage::load_checked() else {
+        return;
+    };
 
     if storage.pokemon.is_empty() {
-        println!("{}", "Your PC is empty. No Pokemon to release!".yellow());
+        println!("{}", "Your PC is empty. No Pokemon to transfer!".yellow());
         return;
     }
 
-    let available_count = storage.count_pokemon(&pokemon_name);
-    if available_count == 0 {
+    let total_count = storage.count_pokemon(&pokemon_name);
+    if total_count == 0 {
         println!("{}", format!("You don't have any {} in your PC.", pokemon_name).red());
         return;
     }
 
+    let mut annotations = annotations::Annotations::load();
+    let locked_count = storage.pokemon.iter()
+        .filter(|p| p.name.eq_ignore_ascii_case(&pokemon_name) && annotations.is_locked(p))
+        .count();
+    let available_count = total_count - locked_count;
+    if available_count == 0 {
+        println!("{}", format!("Your {} {} locked. Unlock one with 'catch-pokemon annotate <number> --unlock'.",
+                 pokemon_name, if total_count > 1 { "are all" } else { "is" }).red());
+        return;
+    }
+    if locked_count > 0 {
+        println!("{}", format!("{} locked {} will be kept.", locked_count, pokemon_name).cyan());
+    }
+
     let to_release = number.min(available_count);
     if number > available_count {
-        println!("{}", format!("You only have {} {} in your PC, releasing all of them.",
-                 available_count, pokemon_name).yellow());
+        println!("{}", format!("You only have {} {}{} in your PC, transferring all of them.",
+                 available_count, if locked_count > 0 { "unlocked " } else { "" }, pokemon_name).yellow());
     }
 
+    let pokemon_db: std::collections::HashMap<String, models::PokemonData> =
+        serde_json::from_str(models::POKEMON_DATA).unwrap_or_default();
+    let family = candy::family(&pokemon_name, &pokemon_db);
+
     println!("{}",
-             format!("Are you sure you want to release {} {}{}? This cannot be undone!",
-                     to_release, pokemon_name, if to_release > 1 { "s" } else { "" }).red().bold());
+             format!("Are you sure you want to transfer {} {}{} to the Professor for {}? This cannot be undone!",
+                     to_release, pokemon_name, if to_release > 1 { "s" } else { "" },
+                     candy::candy_name(&family)).red().bold());
     print!("Type 'yes' to confirm: ");
     stdout().flush().unwrap();
 
@@ -310,23 +849,38 @@ fn release_pokemon(pokemon_name: String, number: usize) {
     std::io::stdin().read_line(&mut input).unwrap();
 
     if input.trim().to_lowercase() == "yes" {
-        let released = storage.release_pokemon(&pokemon_name, to_release);
+        let released = storage.release_pokemon(&pokemon_name, to_release, |p| annotations.is_locked(p));
 
         if let Err(e) = storage.save() {
             eprintln!("Warning: Could not save to PC: {}", e);
         } else {
+            annotations.forget(&released);
+            if let Err(e) = annotations.save() {
+                eprintln!("{}", format!("Could not save your annotations: {}", e).red());
+            }
+            let released = released.len();
+            let mut ledger = candy::CandyLedger::load();
+            let earned = ledger.transfer(&family, released);
+            if let Err(e) = ledger.save() {
+                eprintln!("{}", format!("Could not save your candy: {}", e).red());
+            }
+
             println!();
             println!("{}",
-                     format!("Released {} {}{}! They've returned to the wild.",
+                     format!("Transferred {} {}{} to the Professor!",
                              released, pokemon_name, if released > 1 { "s" } else { "" }).green().bold());
+            println!("{}",
+                     format!("You received {} {} ({} total).",
+                             earned, candy::candy_name(&family), ledger.balance(&family)).yellow());
 
             if storage.count_pokemon(&pokemon_name) > 0 {
                 println!("You still have {} {} remaining in your PC.",
                         storage.count_pokemon(&pokemon_name), pokemon_name);
             }
+            achievements::check_achievements();
         }
     } else {
-        println!("Release cancelled.");
+        println!("Transfer cancelled.");
     }
 }
 
@@ -364,9 +918,23 @@ fn check_pokemon(pokemon_name: String, boolean_mode: bool) {
 fn main() {
     let args = Args::parse();
 
+    #[cfg(debug_assertions)]
+    if let Some(seed) = args.seed {
+        rng::init(Some(seed));
+        eprintln!(
+            "{}",
+            format!("Seeded run (seed {}): using test profile {}", seed, storage::get_data_dir().display()).yellow()
+        );
+    }
+
     match args.command {
-        Commands::Catch { pokemon, skip_animation, hide_pokemon, shiny, token, attempt } => {
-            catch::catch_pokemon(pokemon, skip_animation, hide_pokemon, shiny, token, attempt);
+        Commands::Catch { pokemon, skip_animation, hide_pokemon, shiny, form, token, attempt, habitat, berry } => {
+            let encounter = catch::Encounter { pokemon, form, shiny, token, attempt, habitat };
+            catch::catch_pokemon(encounter, berry, skip_animation, hide_pokemon, &mut rng::game_rng());
+        },
+        Commands::Fight { pokemon, action, with, form, token, attempt } => {
+            let encounter = catch::Encounter { pokemon, form, shiny: false, token, attempt, habitat: None };
+            wild_battle::fight_command(encounter, with, action, &mut rng::game_rng());
         },
         Commands::Pc { search } => {
             pc_tui::show_pc(search);
@@ -405,7 +973,75 @@ fn main() {
             setup::manage_team(add, remove, clear);
         },
         Commands::Encounter { show_pokemon } => {
-            catch::encounter_pokemon(show_pokemon);
+            catch::encounter_pokemon(show_pokemon, &mut rng::game_rng());
+        },
+        Commands::Sprite { pokemon, shiny } => {
+            sprites::print_sprite(&pokemon, shiny);
+        },
+        Commands::Simulate { sessions, hp, status, json } => {
+            simulate::simulate_command(sessions, hp, &status, json);
+        },
+        Commands::Events { days } => {
+            events::events_command(days);
+        },
+        Commands::Hunt { species, stop } => {
+            hunt::hunt_command(species, stop);
+        },
+        Commands::Bag => {
+            items::bag_command();
+        },
+        Commands::Use { item, pokemon_type } => {
+            items::use_command(item, pokemon_type);
+        },
+        Commands::Evolve { pokemon, into } => {
+            evolution::evolve_command(pokemon, into);
+        },
+        Commands::Eggs { skip_animation } => {
+            eggs::eggs_command(skip_animation, &mut rng::game_rng());
+        },
+        Commands::Daycare { pokemon, withdraw } => {
+            eggs::daycare_command(pokemon, withdraw);
+        },
+        Commands::Candy => {
+            candy::candy_command();
+        },
+        Commands::Achievements { list } => {
+            achievements::achievements_command(list);
+        },
+        Commands::Log { species, result, shiny, since, limit } => {
+            encounter_log::log_command(encounter_log::LogFilter { species, result, shiny, since, limit });
+        },
+        Commands::Research => {
+            research::research_command();
+        },
+        Commands::Buddy { action } => match action {
+            Some(BuddyAction::Set { pokemon }) => buddy::set_buddy(Some(pokemon)),
+            Some(BuddyAction::Clear) => buddy::set_buddy(None),
+            None => buddy::buddy_command(),
+        },
+        Commands::Prompt { format, name } => {
+            buddy::prompt_command(format, name);
+        },
+        Commands::Reroll { pokemon } => {
+            candy::reroll_command(pokemon, &mut rng::game_rng());
+        },
+        Commands::Annotate { pokemon, nickname, tags, untags, note, lock, unlock, clear } => {
+            annotations::annotate_command(
+                pokemon,
+                annotations::AnnotationChanges { nickname, tags, untags, note, lock, unlock, clear },
+            );
+        },
+        Commands::Stats { pity, pity_after, pity_ramp, json } => {
+            stats::stats_command(pity, pity_after, pity_ramp, json);
+        },
+        Commands::Schedule { every, chance, quiet_hours, quiet_days, after_commands, check, verbose, commands_enabled } => {
+            if let Some(trigger) = check {
+                scheduler::check_trigger(trigger, verbose);
+            }
+            if commands_enabled {
+                scheduler::check_commands_enabled();
+            }
+            scheduler::schedule_command(every, chance, quiet_hours, quiet_days, after_commands);
         },
         Commands::Update { version } => {
             setup::update_binary(version);
@@ -1,13 +1,15 @@
 use chrono::{DateTime, Local};
 use colored::*;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
 use std::fs;
 
 use crate::crypto::{
     compute_entry_hash, decrypt_battle_team, decrypt_pokedex, decrypt_storage,
     derive_signing_key, encrypt_battle_team, encrypt_pokedex, encrypt_storage,
-    sign_entry, HmacSha256,
+    sign_entry, verify_chain, HmacSha256,
 };
 use crate::storage::{get_pokedex_path, get_storage_path, get_team_path};
 use hmac::Mac as HmacMac;
@@ -18,16 +20,46 @@ pub const POKEBALL_LEFT: &str = include_str!("../static/art/pokeball-left.txt");
 pub const POKEBALL_RIGHT: &str = include_str!("../static/art/pokeball-right.txt");
 pub const POKEBALL_CAUGHT: &str = include_str!("../static/art/pokeball-caught.txt");
 pub const POKEBALL_NOT_CAUGHT: &str = include_str!("../static/art/pokeball-not-caught.txt");
+pub const EGG_STILL: &str = include_str!("../static/art/egg-still.txt");
+pub const EGG_LEFT: &str = include_str!("../static/art/egg-left.txt");
+pub const EGG_RIGHT: &str = include_str!("../static/art/egg-right.txt");
+pub const EGG_CRACKED: &str = include_str!("../static/art/egg-cracked.txt");
 
 // Embed the Pokemon data directly in the binary
 pub const POKEMON_DATA: &str = include_str!("../data/pokemon.json");
 
-// Embed valid pokemon-colorscripts names (for encounter filtering)
-pub const VALID_POKEMON: &str = include_str!("../data/valid_pokemon.txt");
+// Time-of-day, weekday and seasonal encounter modifiers
+pub const ENCOUNTER_MODIFIERS: &str = include_str!("../data/encounter_modifiers.json");
+
+// Project habitats detected from the working directory
+pub const HABITATS: &str = include_str!("../data/habitats.json");
+
+// Roaming legendaries and the species that unlock them
+pub const ROAMERS: &str = include_str!("../data/roamers.json");
+
+// Achievement rules
+pub const ACHIEVEMENTS: &str = include_str!("../data/achievements.json");
+
+// Fallback encounter list for builds without embedded sprites
+const VALID_POKEMON_FALLBACK: &str = include_str!("../data/valid_pokemon.txt");
 
 // Embed the shell functions directly in the binary
 pub const SHELL_FUNCTIONS: &str = include_str!("../shell/functions.sh");
 
+/// Pokemon that can be encountered, in pokemon.json key format (underscores):
+/// every species with an embedded sprite.
+pub fn valid_pokemon() -> HashSet<String> {
+    let embedded = crate::sprites::embedded_names();
+    if embedded.is_empty() {
+        return VALID_POKEMON_FALLBACK
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .collect();
+    }
+    embedded.iter().map(|n| n.replace('-', "_")).collect()
+}
+
 pub fn default_flee_rate() -> u8 {
     10
 }
@@ -35,24 +67,77 @@ pub fn default_flee_rate() -> u8 {
 #[derive(Debug, Clone, Copy)]
 pub enum PokeballType {
     Pokeball,
+    /// Not a ball: the origin recorded for Pokemon hatched from eggs
+    Egg,
 }
 
 impl PokeballType {
     pub fn catch_modifier(&self) -> f32 {
         match self {
             PokeballType::Pokeball => 1.0,
+            PokeballType::Egg => 1.0,
         }
     }
 
     pub fn display_name(&self) -> &str {
         match self {
             PokeballType::Pokeball => "Poké Ball",
+            PokeballType::Egg => "Egg",
         }
     }
 
     pub fn ball_symbol(&self) -> String {
         match self {
             PokeballType::Pokeball => "◓".red().to_string(),
+            PokeballType::Egg => "◯".white().to_string(),
+        }
+    }
+}
+
+/// Status condition on a wild Pokemon. Sleeping and frozen Pokemon are the
+/// easiest to catch.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusCondition {
+    #[default]
+    None,
+    Poison,
+    Burn,
+    Paralysis,
+    Sleep,
+    Freeze,
+}
+
+impl StatusCondition {
+    /// Status bonus in the catch formula (Gen V+ values)
+    pub fn catch_bonus(&self) -> f32 {
+        match self {
+            StatusCondition::None => 1.0,
+            StatusCondition::Poison | StatusCondition::Burn | StatusCondition::Paralysis => 1.5,
+            StatusCondition::Sleep | StatusCondition::Freeze => 2.5,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "none" => Some(StatusCondition::None),
+            "poison" => Some(StatusCondition::Poison),
+            "burn" => Some(StatusCondition::Burn),
+            "paralysis" => Some(StatusCondition::Paralysis),
+            "sleep" => Some(StatusCondition::Sleep),
+            "freeze" => Some(StatusCondition::Freeze),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            StatusCondition::None => "healthy",
+            StatusCondition::Poison => "poisoned",
+            StatusCondition::Burn => "burned",
+            StatusCondition::Paralysis => "paralyzed",
+            StatusCondition::Sleep => "asleep",
+            StatusCondition::Freeze => "frozen",
         }
     }
 }
@@ -67,6 +152,70 @@ pub struct PokemonData {
     pub types: Vec<String>,
     #[serde(default)]
     pub power_rank: u8,
+    /// Regional, Mega and alternate forms keyed by form name (e.g. "alola", "mega-x")
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub forms: BTreeMap<String, FormData>,
+    /// How this species evolves from its pre-evolution, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evolution: Option<EvolutionData>,
+}
+
+/// Per-form overrides. Category and flee rate are shared with the base species.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FormData {
+    pub types: Vec<String>,
+    pub catch_rate: u8,
+    #[serde(default)]
+    pub power_rank: u8,
+}
+
+/// How a species is reached by evolution
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EvolutionMethod {
+    /// Requirement is the level, e.g. "16"
+    Level,
+    /// Requirement is the stone item, e.g. "fire-stone"
+    Stone,
+    Trade,
+    Friendship,
+    /// Anything else; the requirement describes it
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvolutionData {
+    /// Pre-evolution in pokemon.json key format, e.g. "charmander"
+    pub from: String,
+    /// Form the pre-evolution must be in, e.g. "galar" for Perrserker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<String>,
+    pub method: EvolutionMethod,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requirement: Option<String>,
+}
+
+impl PokemonData {
+    /// Types, catch rate and power rank for a form, or the base species when
+    /// `form` is None or unknown
+    pub fn form(&self, form: Option<&str>) -> FormData {
+        match form.and_then(|f| self.forms.get(f)) {
+            Some(data) => data.clone(),
+            None => FormData {
+                types: self.types.clone(),
+                catch_rate: self.catch_rate,
+                power_rank: self.power_rank,
+            },
+        }
+    }
+}
+
+/// Display/sprite name for a species in a form, e.g. "vulpix-alola"
+pub fn form_name(name: &str, form: Option<&str>) -> String {
+    match form {
+        Some(form) => format!("{}-{}", name, form),
+        None => name.to_string(),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -76,12 +225,64 @@ pub struct CaughtPokemon {
     pub ball_used: String,
     #[serde(default)]
     pub shiny: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<String>,
+    /// Habitat the Pokemon was caught in, e.g. "Rust project"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub habitat: Option<String>,
+    /// Individual stats, rolled at catch. None for Pokemon caught before
+    /// stats existed until they are rerolled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ivs: Option<Ivs>,
+    /// Species this Pokemon evolved from, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evolutions: Vec<EvolutionRecord>,
     #[serde(default)]
     pub prev_hash: Option<String>,
     #[serde(default)]
     pub signature: Option<String>,
 }
 
+/// Individual values: attack, defense and stamina from 0 to 15
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Ivs {
+    pub attack: u8,
+    pub defense: u8,
+    pub stamina: u8,
+}
+
+impl Ivs {
+    pub const MAX: u8 = 15;
+
+    pub fn roll(rng: &mut impl Rng) -> Self {
+        Ivs {
+            attack: rng.gen_range(0..=Self::MAX),
+            defense: rng.gen_range(0..=Self::MAX),
+            stamina: rng.gen_range(0..=Self::MAX),
+        }
+    }
+
+    /// Share of the best possible stats, 0-100
+    pub fn percent(&self) -> u32 {
+        let total = self.attack as u32 + self.defense as u32 + self.stamina as u32;
+        total * 100 / (Self::MAX as u32 * 3)
+    }
+}
+
+impl fmt::Display for Ivs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}/{} ({}%)", self.attack, self.defense, self.stamina, self.percent())
+    }
+}
+
+/// One evolution of a caught Pokemon
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvolutionRecord {
+    /// Name before evolving, e.g. "charmander"
+    pub from: String,
+    pub at: DateTime<Local>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PcStorage {
     pub pokemon: Vec<CaughtPokemon>,
@@ -97,6 +298,30 @@ impl PcStorage {
         }
     }
 
+    /// The PC, or why it fails its integrity check. Rewards are never
+    /// counted from a tampered PC.
+    pub fn load_verified() -> Result<Self, String> {
+        let storage = PcStorage::load();
+        if storage.chain_hash.is_some() {
+            verify_chain(&storage)?;
+        }
+        Ok(storage)
+    }
+
+    /// The PC for a command that builds on it, or None after reporting a
+    /// failed integrity check. Commands that re-sign the chain must use this,
+    /// or they would make a tampered PC look legitimate.
+    pub fn load_checked() -> Option<Self> {
+        match PcStorage::load_verified() {
+            Ok(storage) => Some(storage),
+            Err(msg) => {
+                eprintln!("{}", format!("PC integrity check FAILED: {}", msg).red().bold());
+                eprintln!("Run 'catch-pokemon verify' for details.");
+                None
+            }
+        }
+    }
+
     pub fn load() -> Self {
         let path = get_storage_path();
         if !path.exists() {
@@ -181,7 +406,15 @@ impl PcStorage {
         Ok(())
     }
 
-    pub fn add_pokemon(&mut self, name: String, ball: PokeballType, shiny: bool) {
+    pub fn add_pokemon(
+        &mut self,
+        name: String,
+        ball: PokeballType,
+        shiny: bool,
+        form: Option<String>,
+        habitat: Option<String>,
+        ivs: Ivs,
+    ) {
         let key = derive_signing_key();
         let prev_hash = self
             .chain_hash
@@ -193,6 +426,10 @@ impl PcStorage {
             caught_at: Local::now(),
             ball_used: ball.display_name().to_string(),
             shiny,
+            form,
+            habitat,
+            ivs: Some(ivs),
+            evolutions: Vec::new(),
             prev_hash: Some(prev_hash.clone()),
             signature: None,
         };
@@ -202,19 +439,29 @@ impl PcStorage {
         self.pokemon.push(entry);
     }
 
-    pub fn release_pokemon(&mut self, name: &str, count: usize) -> usize {
-        let mut released = 0;
-
-        self.pokemon.retain(|p| {
-            if p.name.to_lowercase() == name.to_lowercase() && released < count {
-                released += 1;
-                false
-            } else {
-                true
-            }
+    /// Remove up to `count` Pokemon of a species, non-shinies first, skipping
+    /// any that are `locked`. Returns the removed entries.
+    pub fn release_pokemon(
+        &mut self,
+        name: &str,
+        count: usize,
+        locked: impl Fn(&CaughtPokemon) -> bool,
+    ) -> Vec<CaughtPokemon> {
+        let mut matching: Vec<usize> = (0..self.pokemon.len())
+            .rev()
+            .filter(|&i| self.pokemon[i].name.to_lowercase() == name.to_lowercase() && !locked(&self.pokemon[i]))
+            .collect();
+        matching.sort_by_key(|&i| self.pokemon[i].shiny);
+        matching.truncate(count);
+        let released: Vec<CaughtPokemon> = matching.iter().map(|&i| self.pokemon[i].clone()).collect();
+
+        let mut index = 0;
+        self.pokemon.retain(|_| {
+            index += 1;
+            !matching.contains(&(index - 1))
         });
 
-        if released > 0 {
+        if !released.is_empty() {
             self.resign_chain();
         }
 
@@ -238,6 +485,17 @@ impl PcStorage {
         };
     }
 
+    /// Index of the entry for a 1-based PC number in catch order, or for a
+    /// species name (shinies first, then the earliest catch)
+    pub fn find_entry(&self, target: &str) -> Option<usize> {
+        if let Ok(number) = target.parse::<usize>() {
+            return (number >= 1 && number <= self.pokemon.len()).then(|| number - 1);
+        }
+        let name = target.to_lowercase().replace('_', "-");
+        let matching = || self.pokemon.iter().enumerate().filter(|(_, p)| p.name.to_lowercase() == name);
+        matching().find(|(_, p)| p.shiny).or_else(|| matching().next()).map(|(i, _)| i)
+    }
+
     pub fn has_pokemon(&self, name: &str) -> bool {
         self.pokemon
             .iter()
@@ -259,17 +517,26 @@ pub struct PokedexEntry {
     pub caught: bool,
     pub seen_at: Option<DateTime<Local>>,
     pub caught_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub forms_seen: BTreeSet<String>,
+    #[serde(default)]
+    pub forms_caught: BTreeSet<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Pokedex {
     pub entries: HashMap<String, PokedexEntry>,
+    /// The bag's starter items were handed out. Kept here rather than in the
+    /// bag, so deleting bag.json doesn't hand them out again.
+    #[serde(default)]
+    pub starter_kit_granted: bool,
 }
 
 impl Pokedex {
     pub fn new() -> Self {
         Pokedex {
             entries: HashMap::new(),
+            starter_kit_granted: false,
         }
     }
 
@@ -305,10 +572,9 @@ impl Pokedex {
         Ok(())
     }
 
-    pub fn mark_seen(&mut self, name: &str) {
+    fn entry_mut(&mut self, name: &str) -> &mut PokedexEntry {
         let normalized = name.to_lowercase();
-        let entry = self
-            .entries
+        self.entries
             .entry(normalized.clone())
             .or_insert_with(|| PokedexEntry {
                 name: normalized,
@@ -316,25 +582,28 @@ impl Pokedex {
                 caught: false,
                 seen_at: None,
                 caught_at: None,
-            });
+                forms_seen: BTreeSet::new(),
+                forms_caught: BTreeSet::new(),
+            })
+    }
+
+    pub fn mark_seen(&mut self, name: &str, form: Option<&str>) {
+        let entry = self.entry_mut(name);
+        if let Some(form) = form {
+            entry.forms_seen.insert(form.to_string());
+        }
         if !entry.seen {
             entry.seen = true;
             entry.seen_at = Some(Local::now());
         }
     }
 
-    pub fn mark_caught(&mut self, name: &str) {
-        let normalized = name.to_lowercase();
-        let entry = self
-            .entries
-            .entry(normalized.clone())
-            .or_insert_with(|| PokedexEntry {
-                name: normalized,
-                seen: false,
-                caught: false,
-                seen_at: None,
-                caught_at: None,
-            });
+    pub fn mark_caught(&mut self, name: &str, form: Option<&str>) {
+        let entry = self.entry_mut(name);
+        if let Some(form) = form {
+            entry.forms_seen.insert(form.to_string());
+            entry.forms_caught.insert(form.to_string());
+        }
         if !entry.seen {
             entry.seen = true;
             entry.seen_at = Some(Local::now());
@@ -413,4 +682,12 @@ pub struct PcEntry {
     pub first_caught: String,
     pub last_caught: String,
     pub on_team: bool,
+    /// Catches per habitat
+    pub habitats: BTreeMap<String, usize>,
+    /// Best individual stats among this species
+    pub best_ivs: Option<Ivs>,
+    pub nicknames: Vec<String>,
+    pub tags: BTreeSet<String>,
+    /// How many are locked against transfer
+    pub locked: usize,
 }
@@ -1,18 +1,23 @@
 use colored::*;
 use std::collections::HashMap;
 use std::io::{stdout, Write};
-use std::process::Command;
 
 use crossterm::{
     cursor, terminal, ExecutableCommand,
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
 };
 
+use crate::achievements::unlock_quietly;
+use crate::annotations::{Annotation, Annotations};
+use crate::candy::{candy_name, family, CandyLedger};
+use crate::catch::{catch_chance_for_rate, encounter_pool, true_odds};
 use crate::crypto::verify_chain;
 use crate::display::{color_category, color_type, interactive_pokemon_search};
 use crate::models::{
-    BattleTeam, BattleTeamEntry, PcEntry, PcStorage, PokemonData, POKEMON_DATA, VALID_POKEMON,
+    BattleTeam, BattleTeamEntry, PcEntry, PcStorage, PokeballType, PokemonData, POKEMON_DATA,
 };
+use crate::graphics::{clear_images, SpriteImage};
+use crate::sprites::sprite_lines;
 
 pub fn show_pc(search: bool) {
     let storage = PcStorage::load();
@@ -32,8 +37,10 @@ pub fn show_pc(search: bool) {
         }
     }
 
+    let mut annotations = Annotations::load();
+
     if search {
-        if let Err(e) = interactive_pokemon_search(&storage) {
+        if let Err(e) = interactive_pokemon_search(&storage, &annotations) {
             eprintln!("Error in interactive search: {}", e);
         }
         return;
@@ -67,13 +74,29 @@ pub fn show_pc(search: bool) {
                 first_caught: p.caught_at.format("%Y-%m-%d %H:%M").to_string(),
                 last_caught: p.caught_at.format("%Y-%m-%d %H:%M").to_string(),
                 on_team: team_names.contains(&normalized),
+                habitats: Default::default(),
+                best_ivs: None,
+                nicknames: Vec::new(),
+                tags: Default::default(),
+                locked: 0,
             }
         });
         entry.count += 1;
+        if let Some(ref habitat) = p.habitat {
+            *entry.habitats.entry(habitat.clone()).or_insert(0) += 1;
+        }
         if p.shiny { entry.shiny_count += 1; }
+        if let Some(ivs) = p.ivs {
+            if entry.best_ivs.is_none_or(|best| ivs.percent() > best.percent()) {
+                entry.best_ivs = Some(ivs);
+            }
+        }
         let ts = p.caught_at.format("%Y-%m-%d %H:%M").to_string();
         if ts < entry.first_caught { entry.first_caught = ts.clone(); }
         if ts > entry.last_caught { entry.last_caught = ts; }
+        if let Some(annotation) = annotations.get(p) {
+            add_annotation(entry, annotation);
+        }
     }
 
     let mut entries: Vec<PcEntry> = entries_map.into_values().collect();
@@ -89,12 +112,22 @@ pub fn show_pc(search: bool) {
     }
 
     // Launch TUI
-    if let Err(e) = pc_tui(&mut entries, &storage) {
+    if let Err(e) = pc_tui(&mut entries, &storage, &mut annotations) {
         eprintln!("TUI error: {}", e);
     }
 }
 
-fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dyn std::error::Error>> {
+fn add_annotation(entry: &mut PcEntry, annotation: &Annotation) {
+    entry.nicknames.extend(annotation.nickname.clone());
+    entry.tags.extend(annotation.tags.iter().cloned());
+    if annotation.locked { entry.locked += 1; }
+}
+
+fn pc_tui(
+    entries: &mut Vec<PcEntry>,
+    _storage: &PcStorage,
+    annotations: &mut Annotations,
+) -> Result<(), Box<dyn std::error::Error>> {
     use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 
     // Enter alternate screen (like vim does — clean slate, restores on exit)
@@ -106,6 +139,7 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
     let mut scroll_offset: usize = 0;
     let mut cached_sprite_name = String::new();
     let mut cached_sprite: Vec<String> = Vec::new();
+    let mut cached_image: Option<SpriteImage> = None;
     let mut status_msg: Option<String> = None;
     let mut confirming_release = false;
 
@@ -124,14 +158,18 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
 
     let mut searching = false;
     let mut search_term = String::new();
+    let mut ledger = CandyLedger::load();
 
     // Precompute true odds for sorting
     let pokemon_db_sort: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
-    let valid_names_sort: std::collections::HashSet<&str> = VALID_POKEMON
-        .lines().filter(|l| !l.is_empty()).collect();
-    let total_weight: u32 = pokemon_db_sort.iter()
-        .filter(|(n, _)| valid_names_sort.contains(n.as_str()))
-        .map(|(_, d)| d.catch_rate as u32).sum();
+    // Encounter weights include every form
+    let encounter_slots = encounter_pool(&pokemon_db_sort);
+    let total_weight: u32 = encounter_slots.iter().map(|s| s.weight).sum();
+    let category_weight = |category: &str| -> u32 {
+        encounter_slots.iter()
+            .filter(|s| s.data.category == category)
+            .map(|s| s.weight).sum()
+    };
 
     loop {
         let (tw, th) = terminal::size().unwrap_or((80, 24));
@@ -158,7 +196,10 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
             }
             // Search filter
             if !search_term.is_empty() {
-                if !e.name.to_lowercase().contains(&search_term.to_lowercase()) { return false; }
+                let term = search_term.to_lowercase();
+                if !e.name.to_lowercase().contains(&term)
+                    && !e.nicknames.iter().any(|n| n.to_lowercase().contains(&term))
+                    && !e.tags.iter().any(|t| t.contains(&term)) { return false; }
             }
             true
         }).collect();
@@ -174,18 +215,8 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
                     // Sort by true catch odds (rarest first)
                     let na = ea.name.replace("-", "_");
                     let nb = eb.name.replace("-", "_");
-                    let odds_a = pokemon_db_sort.get(&na).map(|d| {
-                        let cat_w: u32 = pokemon_db_sort.iter()
-                            .filter(|(n, dd)| valid_names_sort.contains(n.as_str()) && dd.category == d.category)
-                            .map(|(_, dd)| dd.catch_rate as u32).sum();
-                        (cat_w as f64 / total_weight as f64) * (d.catch_rate as f64 / 255.0)
-                    }).unwrap_or(0.0);
-                    let odds_b = pokemon_db_sort.get(&nb).map(|d| {
-                        let cat_w: u32 = pokemon_db_sort.iter()
-                            .filter(|(n, dd)| valid_names_sort.contains(n.as_str()) && dd.category == d.category)
-                            .map(|(_, dd)| dd.catch_rate as u32).sum();
-                        (cat_w as f64 / total_weight as f64) * (d.catch_rate as f64 / 255.0)
-                    }).unwrap_or(0.0);
+                    let odds_a = pokemon_db_sort.get(&na).map(|d| true_odds(d, &encounter_slots)).unwrap_or(0.0);
+                    let odds_b = pokemon_db_sort.get(&nb).map(|d| true_odds(d, &encounter_slots)).unwrap_or(0.0);
                     odds_a.partial_cmp(&odds_b).unwrap_or(std::cmp::Ordering::Equal)
                 }
                 "power" => eb.power_rank.cmp(&ea.power_rank),
@@ -226,18 +257,15 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
         let sprite_key = format!("{}:{}", sel.name, sel.shiny_count > 0);
         if cached_sprite_name != sprite_key {
             cached_sprite_name = sprite_key;
-            let display_name = sel.name.replace("_", "-");
-            let mut args = vec!["-n", &display_name, "--no-title"];
-            if sel.shiny_count > 0 {
-                args.push("-s");
+            let right_panel_width = tw.saturating_sub(left_width + 3);
+            // Full-resolution image when the terminal supports one, ANSI art otherwise
+            cached_image = SpriteImage::new(&sel.name, sel.shiny_count > 0, right_panel_width, list_height / 2);
+            if cached_image.is_none() {
+                cached_sprite = sprite_lines(&sel.name, sel.shiny_count > 0, right_panel_width);
+                if cached_sprite.is_empty() {
+                    cached_sprite = vec!["(no sprite)".to_string()];
+                }
             }
-            cached_sprite = Command::new("pokemon-colorscripts")
-                .args(&args)
-                .output()
-                .ok()
-                .filter(|r| r.status.success())
-                .map(|r| String::from_utf8_lossy(&r.stdout).lines().map(|l| l.to_string()).collect())
-                .unwrap_or_else(|| vec!["(no sprite)".to_string()]);
         }
 
         // Build right panel content
@@ -250,28 +278,26 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
         right.push(format!("Type:     {}", types_display.join(" / ")));
         right.push(format!("Power:    {}", format!("{}", sel.power_rank).bright_yellow().bold()));
         right.push(format!("Category: {}", cat_display));
+        let sel_family = family(&sel.name, &pokemon_db_sort);
+        right.push(format!("Candy:    {} {}", ledger.balance(&sel_family).to_string().yellow().bold(), candy_name(&sel_family).dimmed()));
+        if let Some(ivs) = sel.best_ivs {
+            right.push(format!("Best IVs: {}", ivs.to_string().bright_cyan()));
+        }
 
         // Look up rates
         let normalized = sel.name.replace("-", "_");
         let pokemon_db_local: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
         if let Some(data) = pokemon_db_local.get(&normalized) {
-            let valid_names_set: std::collections::HashSet<&str> = VALID_POKEMON
-                .lines().filter(|l| !l.is_empty()).collect();
-            let total_weight: u32 = pokemon_db_local.iter()
-                .filter(|(n, _)| valid_names_set.contains(n.as_str()))
-                .map(|(_, d)| d.catch_rate as u32).sum();
-
             // Category encounter rate (all Pokemon in this category combined)
-            let category_weight: u32 = pokemon_db_local.iter()
-                .filter(|(n, d)| valid_names_set.contains(n.as_str()) && d.category == data.category)
-                .map(|(_, d)| d.catch_rate as u32).sum();
+            let category_weight = category_weight(&data.category);
             let category_encounter_pct = category_weight as f32 / total_weight as f32 * 100.0;
 
-            // Individual encounter rate
-            let encounter_pct = data.catch_rate as f32 / total_weight as f32 * 100.0;
+            // Individual encounter rate, any form
+            let species_weight: u32 = encounter_slots.iter().filter(|s| *s.name == normalized).map(|s| s.weight).sum();
+            let encounter_pct = species_weight as f32 / total_weight as f32 * 100.0;
 
             // Catch rate with Poke Ball
-            let catch_pct = data.catch_rate as f32 / 255.0 * 100.0;
+            let catch_pct = catch_chance_for_rate(data.catch_rate, PokeballType::Pokeball);
 
             // True catch rate uses category encounter rate
             let true_catch_pct = category_encounter_pct * catch_pct / 100.0;
@@ -306,9 +332,22 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
         if sel.on_team {
             right.push(format!("{}", "[On Battle Team]".cyan().bold()));
         }
+        if !sel.nicknames.is_empty() {
+            right.push(format!("Nickname: {}", sel.nicknames.join(", ").green()));
+        }
+        if !sel.tags.is_empty() {
+            let tags: Vec<String> = sel.tags.iter().map(|t| t.magenta().to_string()).collect();
+            right.push(format!("Tags:     {}", tags.join(", ")));
+        }
+        if sel.locked > 0 {
+            right.push(format!("Locked:   {}", format!("{} of {}", sel.locked, sel.count).cyan()));
+        }
         right.push(String::new());
         right.push(format!("{}", format!("First: {}", sel.first_caught).dimmed()));
         right.push(format!("{}", format!("Last:  {}", sel.last_caught).dimmed()));
+        for (habitat, count) in &sel.habitats {
+            right.push(format!("{}", format!("Caught in {} x{}", habitat, count).dimmed()));
+        }
         right.push(String::new());
 
         // Poke Ball grid: shinies as gold stars, regulars as red balls, 4 per row
@@ -331,8 +370,13 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
         }
         right.push(String::new());
 
-        // Tile sprites in a grid if terminal is wide enough
-        if !cached_sprite.is_empty() && sel.count > 0 {
+        // A single full-resolution image replaces the sprite grid
+        let mut image_row = 0;
+        if let Some(ref image) = cached_image {
+            image_row = right.len();
+            right.extend(std::iter::repeat_n(String::new(), image.rows));
+        } else if !cached_sprite.is_empty() && sel.count > 0 {
+            // Tile sprites in a grid if terminal is wide enough
             // Calculate sprite width (longest line, ignoring ANSI codes)
             let strip_ansi = |s: &str| -> usize {
                 let mut len = 0;
@@ -479,8 +523,16 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
             } else {
                 let team_count = entries.iter().filter(|e| e.on_team).count();
                 print!(" {}\x1B[K",
-                    format!("↑↓ Nav | /: Search | S: Sort | F: Type | C: Cat | T: Team ({}/20) | R: Release | Q: Quit", team_count).dimmed());
+                    format!("↑↓ Nav | /: Search | S: Sort | F: Type | C: Cat | T: Team ({}/20) | R: Transfer | Q: Quit", team_count).dimmed());
+            }
+        }
+
+        // Images go on top of the blank rows reserved in the right panel (body starts on row 2)
+        match cached_image {
+            Some(ref image) if image_row + image.rows <= list_height => {
+                image.draw_at(left_width + 2, 2 + image_row);
             }
+            _ => clear_images(),
         }
         stdout().flush()?;
 
@@ -560,12 +612,16 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
                     let ei = sorted[selected];
                     let name = entries[ei].name.clone();
                     let count = entries[ei].count;
+                    if entries[ei].locked == count {
+                        status_msg = Some(format!("{} is locked. Unlock it with 'catch-pokemon annotate'.", name));
+                        continue;
+                    }
 
                     // Show confirmation in footer
                     status_msg = Some(format!(
-                        "Release {}{}? Press Y to confirm, any other key to cancel",
+                        "Transfer {}{} for candy? Press Y to confirm, any other key to cancel",
                         name,
-                        if count > 1 { " (releases 1)" } else { "" }
+                        if count > 1 { " (transfers 1)" } else { "" }
                     ));
 
                     // Render the confirmation message immediately
@@ -578,12 +634,30 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
                     let name = entries[ei].name.clone();
                     confirming_release = false;
 
-                    let mut storage = PcStorage::load();
-                    let released = storage.release_pokemon(&name, 1);
-                    if released > 0 {
+                    // Transfers mint candy, so never build on a tampered PC
+                    let mut storage = match PcStorage::load_verified() {
+                        Ok(storage) => storage,
+                        Err(msg) => {
+                            status_msg = Some(format!("PC integrity check FAILED: {}", msg));
+                            continue;
+                        }
+                    };
+                    let released = storage.release_pokemon(&name, 1, |p| annotations.is_locked(p));
+                    if !released.is_empty() {
                         if let Err(e) = storage.save() {
                             status_msg = Some(format!("Error saving: {}", e));
                         } else {
+                            annotations.forget(&released);
+                            let _ = annotations.save();
+                            let entry = &mut entries[ei];
+                            entry.nicknames.clear();
+                            entry.tags.clear();
+                            entry.locked = 0;
+                            for p in storage.pokemon.iter().filter(|p| p.name == name) {
+                                if let Some(annotation) = annotations.get(p) {
+                                    add_annotation(entry, annotation);
+                                }
+                            }
                             entries[ei].count -= 1;
                             if entries[ei].count == 0 {
                                 entries.remove(ei);
@@ -597,7 +671,16 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
                                 team.pokemon.retain(|p| p.name.to_lowercase().replace("-", "_") != normalized);
                                 let _ = team.save();
                             }
-                            status_msg = Some(format!("{} released back to the wild!", name));
+                            let family = family(&name, &pokemon_db_sort);
+                            let earned = ledger.transfer(&family, released.len());
+                            status_msg = Some(match ledger.save() {
+                                Ok(()) => format!("{} transferred to the Professor! +{} {}", name, earned, candy_name(&family)),
+                                Err(e) => format!("Could not save your candy: {}", e),
+                            });
+                            let unlocked = unlock_quietly();
+                            if let (Some(msg), false) = (status_msg.as_mut(), unlocked.is_empty()) {
+                                msg.push_str(&format!(" Achievement unlocked: {}!", unlocked.join(", ")));
+                            }
                             // Clear sprite cache so it reloads for new selection
                             cached_sprite_name = String::new();
                         }
@@ -605,7 +688,7 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
                 }
                 _ if confirming_release => {
                     confirming_release = false;
-                    status_msg = Some("Release cancelled.".to_string());
+                    status_msg = Some("Transfer cancelled.".to_string());
                 }
                 KeyCode::Home => { selected = 0; }
                 KeyCode::End => {
@@ -617,6 +700,7 @@ fn pc_tui(entries: &mut Vec<PcEntry>, _storage: &PcStorage) -> Result<(), Box<dy
     }
 
     // Restore terminal
+    clear_images();
     stdout().execute(cursor::Show)?;
     terminal::disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::config::PityConfig;
+use crate::storage::{get_pity_path, load_signed, save_signed};
+
+/// Most the pity timer can multiply rare weights by, however long the drought
+pub const MAX_PITY: f32 = 10.0;
+
+/// Categories the pity timer counts as a "rare" encounter
+pub fn is_pity_category(category: &str) -> bool {
+    matches!(category, "legendary" | "mythical" | "pseudo_legendary")
+}
+
+/// Encounters since the last legendary, mythical or pseudo-legendary.
+/// Tracked whether or not pity is enabled.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PityState {
+    pub since_rare: u32,
+    /// Longest stretch without a rare encounter
+    pub longest_drought: u32,
+    pub last_rare: Option<String>,
+    pub last_rare_at: Option<DateTime<Local>>,
+    pub total_encounters: u64,
+}
+
+impl PityState {
+    pub fn load() -> Self {
+        load_signed(&get_pity_path(), "Pity state").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_pity_path(), self)
+    }
+
+    /// Weight multiplier for rare categories. Starts at 1 and climbs by
+    /// `ramp` for every encounter past the threshold, up to MAX_PITY.
+    pub fn multiplier(&self, config: &PityConfig) -> f32 {
+        if !config.enabled || self.since_rare < config.threshold {
+            return 1.0;
+        }
+        (1.0 + config.ramp * (self.since_rare - config.threshold + 1) as f32).min(MAX_PITY)
+    }
+
+    pub fn record(&mut self, species: &str, category: &str) {
+        self.total_encounters += 1;
+        if is_pity_category(category) {
+            self.since_rare = 0;
+            self.last_rare = Some(species.to_string());
+            self.last_rare_at = Some(Local::now());
+        } else {
+            self.since_rare += 1;
+            self.longest_drought = self.longest_drought.max(self.since_rare);
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use colored::*;
 use std::collections::HashMap;
 use std::io::{stdout, Write};
-use std::process::Command;
 
 use crossterm::{
     cursor, terminal, ExecutableCommand,
@@ -9,16 +8,16 @@ use crossterm::{
 };
 
 use crate::display::{color_category, color_type};
-use crate::models::{PcStorage, Pokedex, PokemonData, POKEMON_DATA, VALID_POKEMON};
+use crate::graphics::{clear_images, SpriteImage};
+use crate::catch::{catch_chance_for_rate, encounter_pool, is_battle_only};
+use crate::models::{form_name, valid_pokemon, PcStorage, PokeballType, Pokedex, PokemonData, POKEMON_DATA};
+use crate::sprites::sprite_lines;
 
 pub fn show_pokedex() {
     use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 
     let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap_or_default();
-    let valid_names: std::collections::HashSet<&str> = VALID_POKEMON
-        .lines()
-        .filter(|l| !l.is_empty())
-        .collect();
+    let valid_names = valid_pokemon();
 
     let mut pokedex = Pokedex::load();
 
@@ -34,8 +33,14 @@ pub fn show_pokedex() {
                 caught: false,
                 seen_at: None,
                 caught_at: None,
+                forms_seen: Default::default(),
+                forms_caught: Default::default(),
             }
         });
+        if let Some(ref form) = p.form {
+            synced |= entry.forms_seen.insert(form.clone());
+            synced |= entry.forms_caught.insert(form.clone());
+        }
         if !entry.caught {
             entry.seen = true;
             entry.caught = true;
@@ -55,6 +60,9 @@ pub fn show_pokedex() {
     // Build list of all valid Pokemon
     struct DexRow {
         name: String,
+        /// pokemon.json key of the species (underscores)
+        species: String,
+        form: Option<String>,
         types: Vec<String>,
         power_rank: u8,
         category: String,
@@ -71,14 +79,40 @@ pub fn show_pokedex() {
         .filter(|p| p.shiny)
         .map(|p| p.name.to_lowercase())
         .collect();
+    let shiny_forms: std::collections::HashSet<String> = pc_storage.pokemon.iter()
+        .filter(|p| p.shiny && p.form.is_some())
+        .map(|p| form_name(&p.name.to_lowercase(), p.form.as_deref()))
+        .collect();
 
     let mut rows: Vec<DexRow> = Vec::new();
+    // Forms view: one row per regional or alternate form that can be caught
+    let mut form_rows: Vec<DexRow> = Vec::new();
     for name in &valid_names {
-        if let Some(data) = pokemon_db.get(*name) {
+        if let Some(data) = pokemon_db.get(name) {
             let display_name = name.replace("_", "-");
             let entry = pokedex.entries.get(&display_name);
+            for (form, form_data) in data.forms.iter().filter(|(form, _)| !is_battle_only(form)) {
+                let full_name = form_name(&display_name, Some(form));
+                let seen = entry.map(|e| e.forms_seen.contains(form)).unwrap_or(false);
+                let caught = entry.map(|e| e.forms_caught.contains(form)).unwrap_or(false);
+                form_rows.push(DexRow {
+                    name: full_name.clone(),
+                    species: name.clone(),
+                    form: Some(form.clone()),
+                    types: form_data.types.clone(),
+                    power_rank: form_data.power_rank,
+                    category: data.category.clone(),
+                    seen,
+                    caught,
+                    has_shiny: shiny_forms.contains(&full_name),
+                    seen_at: None,
+                    caught_at: None,
+                });
+            }
             rows.push(DexRow {
                 name: display_name.clone(),
+                species: name.clone(),
+                form: None,
                 types: data.types.clone(),
                 power_rank: data.power_rank,
                 category: data.category.clone(),
@@ -91,10 +125,13 @@ pub fn show_pokedex() {
         }
     }
     rows.sort_by(|a, b| a.name.cmp(&b.name));
+    form_rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut forms_view = false;
 
-    let total = rows.len();
-    let seen_count = rows.iter().filter(|r| r.seen).count();
-    let caught_count = rows.iter().filter(|r| r.caught).count();
+    // Encounter weights include every form
+    let encounter_slots = encounter_pool(&pokemon_db);
+    let total_weight: u32 = encounter_slots.iter().map(|s| s.weight).sum();
 
     // TUI
     stdout().execute(EnterAlternateScreen).unwrap();
@@ -107,14 +144,20 @@ pub fn show_pokedex() {
     let mut searching = false;
     let mut cached_sprite_name = String::new();
     let mut cached_sprite: Vec<String> = Vec::new();
+    let mut cached_image: Option<SpriteImage> = None;
 
     loop {
+        let active_rows = if forms_view { &form_rows } else { &rows };
+        let total = active_rows.len();
+        let seen_count = active_rows.iter().filter(|r| r.seen).count();
+        let caught_count = active_rows.iter().filter(|r| r.caught).count();
+
         // Filter by search
         let filtered: Vec<&DexRow> = if search_term.is_empty() {
-            rows.iter().collect()
+            active_rows.iter().collect()
         } else {
             let lower = search_term.to_lowercase();
-            rows.iter().filter(|r| {
+            active_rows.iter().filter(|r| {
                 r.name.contains(&lower)
             }).collect()
         };
@@ -145,22 +188,19 @@ pub fn show_pokedex() {
             let sprite_key = format!("{}:{}", s.name, s.has_shiny);
             if cached_sprite_name != sprite_key {
                 cached_sprite_name = sprite_key;
-                let mut args = vec!["-n", &s.name, "--no-title"];
-                if s.has_shiny {
-                    args.push("-s");
+                let (tw, _) = terminal::size().unwrap_or((80, 24));
+                let right_panel_width = (tw as usize).saturating_sub(30.min(tw as usize / 3) + 3);
+                // Full-resolution image when the terminal supports one, ANSI art otherwise
+                cached_image = SpriteImage::new(&s.name, s.has_shiny, right_panel_width, list_height / 2);
+                if cached_image.is_none() {
+                    cached_sprite = sprite_lines(&s.name, s.has_shiny, right_panel_width);
                 }
-                cached_sprite = Command::new("pokemon-colorscripts")
-                    .args(&args)
-                    .output()
-                    .ok()
-                    .filter(|r| r.status.success())
-                    .map(|r| String::from_utf8_lossy(&r.stdout).lines().map(|l| l.to_string()).collect())
-                    .unwrap_or_default();
             }
         }
 
         // Build right panel
         let mut right: Vec<String> = Vec::new();
+        let mut image_row = 0;
         if let Some(s) = sel {
             let types_display: Vec<String> = s.types.iter().map(|t| color_type(t)).collect();
             let cat_display = color_category(&s.category);
@@ -172,20 +212,21 @@ pub fn show_pokedex() {
             right.push(format!("Category: {}", cat_display));
 
             // Look up rates
-            let normalized_dex = s.name.replace("-", "_");
-            if let Some(data) = pokemon_db.get(&normalized_dex) {
-                let total_weight: u32 = pokemon_db.iter()
-                    .filter(|(n, _)| valid_names.contains(n.as_str()))
-                    .map(|(_, d)| d.catch_rate as u32).sum();
+            if let Some(data) = pokemon_db.get(&s.species) {
+                let catch_rate = data.form(s.form.as_deref()).catch_rate;
 
                 // Category encounter rate
-                let category_weight: u32 = pokemon_db.iter()
-                    .filter(|(n, d)| valid_names.contains(n.as_str()) && d.category == data.category)
-                    .map(|(_, d)| d.catch_rate as u32).sum();
+                let category_weight: u32 = encounter_slots.iter()
+                    .filter(|slot| slot.data.category == data.category)
+                    .map(|slot| slot.weight).sum();
                 let category_encounter_pct = category_weight as f32 / total_weight as f32 * 100.0;
 
-                let encounter_pct = data.catch_rate as f32 / total_weight as f32 * 100.0;
-                let catch_pct = data.catch_rate as f32 / 255.0 * 100.0;
+                // Megas aren't in the wild pool
+                let weight = encounter_slots.iter()
+                    .find(|slot| *slot.name == s.species && slot.form.map(|f| f.as_str()) == s.form.as_deref())
+                    .map_or(0, |slot| slot.weight);
+                let encounter_pct = weight as f32 / total_weight as f32 * 100.0;
+                let catch_pct = catch_chance_for_rate(catch_rate, PokeballType::Pokeball);
                 let true_catch_pct = category_encounter_pct * catch_pct / 100.0;
 
                 let catch_color = if catch_pct >= 75.0 { format!("{:.1}%", catch_pct).green() }
@@ -235,9 +276,32 @@ pub fn show_pokedex() {
                 right.push(format!("{}", "Not discovered".dimmed()));
             }
 
+            // Forms caught for this species
+            if s.form.is_none() {
+                if let Some(data) = pokemon_db.get(&s.species).filter(|d| d.forms.keys().any(|f| !is_battle_only(f))) {
+                    let entry = pokedex.entries.get(&s.name);
+                    let forms: Vec<String> = data.forms.keys().filter(|f| !is_battle_only(f)).map(|form| {
+                        if entry.map(|e| e.forms_caught.contains(form)).unwrap_or(false) {
+                            format!("{}", form.green())
+                        } else if entry.map(|e| e.forms_seen.contains(form)).unwrap_or(false) {
+                            format!("{}", form.yellow())
+                        } else {
+                            format!("{}", form.dimmed())
+                        }
+                    }).collect();
+                    right.push(format!("Forms:    {}", forms.join(", ")));
+                }
+            }
+
             right.push(String::new());
-            for line in &cached_sprite {
-                right.push(line.clone());
+            if let Some(ref image) = cached_image {
+                // Leave blank rows for the image, drawn after the text
+                image_row = right.len();
+                right.extend(std::iter::repeat_n(String::new(), image.rows));
+            } else {
+                for line in &cached_sprite {
+                    right.push(line.clone());
+                }
             }
         }
 
@@ -246,7 +310,7 @@ pub fn show_pokedex() {
 
         // Header
         print!(" {} | {}/{} seen | {}/{} caught\x1B[K\r\n",
-            "Pokedex".cyan().bold(),
+            if forms_view { "Pokedex (Forms)" } else { "Pokedex" }.cyan().bold(),
             format!("{}", seen_count).yellow(),
             total,
             format!("{}", caught_count).green(),
@@ -316,7 +380,15 @@ pub fn show_pokedex() {
         } else {
             print!(" {} | {}\x1B[K",
                 format!("{}/{}", if filtered.is_empty() { 0 } else { selected + 1 }, filtered.len()).dimmed(),
-                "↑↓ Navigate | /: Search | Q: Quit".dimmed());
+                "↑↓ Navigate | /: Search | F: Forms | Q: Quit".dimmed());
+        }
+
+        // Images go on top of the blank rows reserved in the right panel (body starts on row 3)
+        match cached_image {
+            Some(ref image) if sel.is_some() && image_row + image.rows <= list_height => {
+                image.draw_at(left_width + 2, 3 + image_row);
+            }
+            _ => clear_images(),
         }
         stdout().flush().unwrap();
 
@@ -360,6 +432,11 @@ pub fn show_pokedex() {
                 match code {
                     KeyCode::Char('q') | KeyCode::Esc => break,
                     KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Char('f') => {
+                        forms_view = !forms_view;
+                        selected = 0;
+                        scroll_offset = 0;
+                    }
                     KeyCode::Char('/') => {
                         searching = true;
                         search_term.clear();
@@ -382,6 +459,7 @@ pub fn show_pokedex() {
         }
     }
 
+    clear_images();
     stdout().execute(cursor::Show).unwrap();
     terminal::disable_raw_mode().unwrap();
     stdout().execute(LeaveAlternateScreen).unwrap();
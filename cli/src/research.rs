@@ -0,0 +1,395 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::catch::encounter_pool;
+use crate::display::progress_bar;
+use crate::encounter_log::{self, encounters, EncounterRecord};
+use crate::items::{Bag, Item};
+use crate::models::{PcStorage, PokemonData, POKEMON_DATA};
+use crate::storage::{get_research_path, load_signed, save_signed};
+
+// Bumping the version reshuffles every future task
+const RESEARCH_DOMAIN: &str = "catch-pokemon:research:v1";
+const DAILY_TASKS: u8 = 3;
+const WEEKLY_TASKS: u8 = 2;
+/// Width of the progress bars, in cells
+const BAR_WIDTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    /// Runs for one day
+    Daily(NaiveDate),
+    /// Runs Monday to Sunday; holds the Monday
+    Weekly(NaiveDate),
+}
+
+impl Period {
+    fn key(&self) -> String {
+        match self {
+            Period::Daily(date) => date.format("%Y-%m-%d").to_string(),
+            Period::Weekly(monday) => format!("{}-W{:02}", monday.iso_week().year(), monday.iso_week().week()),
+        }
+    }
+
+    /// Days covered as [start, end)
+    fn window(&self) -> (NaiveDate, NaiveDate) {
+        match *self {
+            Period::Daily(date) => (date, date + Days::new(1)),
+            Period::Weekly(monday) => (monday, monday + Days::new(7)),
+        }
+    }
+
+    fn contains(&self, at: DateTime<Local>) -> bool {
+        let (start, end) = self.window();
+        let date = at.date_naive();
+        date >= start && date < end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Objective {
+    /// Catch Pokemon, optionally of one type
+    Catch { type_name: Option<String> },
+    /// Encounter Pokemon of one type
+    Encounter { type_name: String },
+    /// Catch different species
+    Species,
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    /// Period key and slot, e.g. "2026-10-18:0" or "2026-W42:1"
+    pub id: String,
+    pub period: Period,
+    pub objective: Objective,
+    pub goal: u64,
+    pub reward: Item,
+    pub reward_count: u32,
+}
+
+impl Task {
+    pub fn describe(&self) -> String {
+        let plural = if self.goal == 1 { "" } else { "s" };
+        match &self.objective {
+            Objective::Catch { type_name: Some(t) } => format!("Catch {} {} type{}", self.goal, t, plural),
+            Objective::Catch { type_name: None } => format!("Catch {} Pokemon", self.goal),
+            Objective::Encounter { type_name } => format!("Encounter {} {} type{}", self.goal, type_name, plural),
+            Objective::Species => format!("Catch {} different species", self.goal),
+        }
+    }
+
+    pub fn reward_name(&self) -> String {
+        if self.reward_count == 1 {
+            self.reward.display_name().to_string()
+        } else {
+            format!("{}x {}", self.reward_count, self.reward.display_name())
+        }
+    }
+}
+
+/// Types worth a task: every type in the encounter pool, sorted so the
+/// same date always picks the same one
+fn task_types(pokemon_db: &HashMap<String, PokemonData>) -> Vec<String> {
+    let mut types: Vec<String> = encounter_pool(pokemon_db)
+        .iter()
+        .filter(|s| s.form.is_none())
+        .flat_map(|s| s.types.iter().cloned())
+        .collect();
+    types.sort();
+    types.dedup();
+    types
+}
+
+fn reward_for(period: Period, roll: u8) -> (Item, u32) {
+    let all = <Item as clap::ValueEnum>::value_variants();
+    let pool: Vec<Item> = all
+        .iter()
+        .copied()
+        .filter(|i| i.is_evolution_item() == matches!(period, Period::Weekly(_)))
+        .collect();
+    let item = pool[roll as usize % pool.len()];
+    let count = match item {
+        Item::RazzBerry | Item::NanabBerry | Item::PinapBerry => 3,
+        _ => 1,
+    };
+    (item, count)
+}
+
+/// The tasks for a period, the same for every player
+pub fn tasks_for(period: Period, pokemon_db: &HashMap<String, PokemonData>) -> Vec<Task> {
+    let types = task_types(pokemon_db);
+    let slots = match period {
+        Period::Daily(_) => DAILY_TASKS,
+        Period::Weekly(_) => WEEKLY_TASKS,
+    };
+    (0..slots)
+        .map(|slot| {
+            let id = format!("{}:{}", period.key(), slot);
+            let digest = Sha256::digest(format!("{}:{}", RESEARCH_DOMAIN, id));
+            let pick = u64::from_le_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+            let type_name = types[(pick % types.len() as u64) as usize].clone();
+            let spread = digest[8] as u64;
+            let (objective, goal) = match (period, slot) {
+                (Period::Daily(_), 0) => (Objective::Catch { type_name: Some(type_name) }, 2 + spread % 3),
+                (Period::Daily(_), 1) => (Objective::Encounter { type_name }, 3 + spread % 4),
+                (Period::Daily(_), _) => (Objective::Catch { type_name: None }, 5 + spread % 6),
+                (Period::Weekly(_), 0) => (Objective::Species, 10 + spread % 6),
+                (Period::Weekly(_), _) => (Objective::Catch { type_name: Some(type_name) }, 8 + spread % 5),
+            };
+            let (reward, reward_count) = reward_for(period, digest[9]);
+            Task { id, period, objective, goal, reward, reward_count }
+        })
+        .collect()
+}
+
+/// Today's tasks followed by this week's
+pub fn current_tasks(pokemon_db: &HashMap<String, PokemonData>) -> Vec<Task> {
+    let today = Local::now().date_naive();
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let mut tasks = tasks_for(Period::Daily(today), pokemon_db);
+    tasks.extend(tasks_for(Period::Weekly(monday), pokemon_db));
+    tasks
+}
+
+/// Tasks already rewarded. Progress is counted from the PC and the
+/// encounter log themselves.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ResearchState {
+    #[serde(default)]
+    claimed: BTreeSet<String>,
+}
+
+impl ResearchState {
+    fn load() -> Self {
+        load_signed(&get_research_path(), "Research").unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_research_path(), self)
+    }
+
+    /// Forget tasks that have run out
+    fn prune(&mut self, tasks: &[Task]) {
+        self.claimed.retain(|id| tasks.iter().any(|t| &t.id == id));
+    }
+}
+
+fn has_type(pokemon_db: &HashMap<String, PokemonData>, species: &str, form: Option<&str>, type_name: &str) -> bool {
+    pokemon_db
+        .get(&species.to_lowercase().replace('-', "_"))
+        .is_some_and(|d| d.form(form).types.iter().any(|t| t == type_name))
+}
+
+/// Everything progress is counted from
+struct Records {
+    storage: PcStorage,
+    encounters: Vec<EncounterRecord>,
+    state: ResearchState,
+    pokemon_db: HashMap<String, PokemonData>,
+}
+
+impl Records {
+    /// None if the PC or the encounter log fails its integrity check
+    fn load() -> Option<Self> {
+        Some(Records {
+            storage: PcStorage::load_verified().ok()?,
+            encounters: encounter_log::load().ok().map(|entries| encounters(&entries))?,
+            state: ResearchState::load(),
+            pokemon_db: serde_json::from_str(POKEMON_DATA).ok()?,
+        })
+    }
+
+    fn progress(&self, task: &Task) -> u64 {
+        // Hatched Pokemon weren't caught in the wild
+        let catches = self
+            .storage
+            .pokemon
+            .iter()
+            .filter(|p| p.ball_used != "Egg" && task.period.contains(p.caught_at));
+        let encounters = self.encounters.iter().filter(|e| task.period.contains(e.at));
+        let count = match &task.objective {
+            Objective::Catch { type_name: None } => catches.count(),
+            Objective::Catch { type_name: Some(t) } => catches
+                .filter(|p| has_type(&self.pokemon_db, &p.name, p.form.as_deref(), t))
+                .count(),
+            Objective::Encounter { type_name } => encounters
+                .filter(|e| has_type(&self.pokemon_db, &e.species, e.form.as_deref(), type_name))
+                .count(),
+            Objective::Species => catches.map(|p| p.name.as_str()).collect::<BTreeSet<_>>().len(),
+        };
+        count as u64
+    }
+
+    /// Reward every task that is newly complete and return them
+    fn claim(&mut self, tasks: &[Task]) -> Vec<Task> {
+        let done: Vec<Task> = tasks
+            .iter()
+            .filter(|t| !self.state.claimed.contains(&t.id) && self.progress(t) >= t.goal)
+            .cloned()
+            .collect();
+        if done.is_empty() {
+            return done;
+        }
+        let mut bag = Bag::load();
+        for task in &done {
+            bag.add(task.reward, task.reward_count);
+            self.state.claimed.insert(task.id.clone());
+        }
+        if let Err(e) = bag.save() {
+            eprintln!("{}", format!("Could not save your bag: {}", e).red());
+            return Vec::new();
+        }
+        done
+    }
+}
+
+/// Reward newly finished tasks without printing. Returns the finished tasks.
+/// Verifying the PC and the encounter log is slow, so this runs after
+/// catches and in `catch-pokemon research`, never on every encounter.
+fn claim_research() -> Vec<Task> {
+    let Some(mut records) = Records::load() else {
+        return Vec::new();
+    };
+    let tasks = current_tasks(&records.pokemon_db);
+    records.state.prune(&tasks);
+    let done = records.claim(&tasks);
+    if !done.is_empty() {
+        if let Err(e) = records.state.save() {
+            eprintln!("{}", format!("Could not save research: {}", e).red());
+            return Vec::new();
+        }
+    }
+    done
+}
+
+/// Check research after a catch and announce finished tasks
+pub fn check_research() {
+    for task in &claim_research() {
+        println!(
+            "{} {} {}",
+            "Research complete!".cyan().bold(),
+            task.describe().green().bold(),
+            format!("(received {})", task.reward_name()).dimmed()
+        );
+    }
+}
+
+fn print_task(task: &Task, current: u64, claimed: bool) {
+    if claimed {
+        println!(
+            "  {} {:<32} {} {}",
+            "✓".green().bold(),
+            task.describe().green(),
+            progress_bar(task.goal, task.goal, BAR_WIDTH).green(),
+            format!("{} received", task.reward_name()).dimmed()
+        );
+    } else {
+        println!(
+            "  {} {:<32} {} {:>7}  {}",
+            "·".dimmed(),
+            task.describe(),
+            progress_bar(current, task.goal, BAR_WIDTH).yellow(),
+            format!("{}/{}", current.min(task.goal), task.goal).dimmed(),
+            format!("Reward: {}", task.reward_name()).cyan()
+        );
+    }
+}
+
+/// Show today's and this week's research tasks
+pub fn research_command() {
+    let Some(mut records) = Records::load() else {
+        eprintln!(
+            "{}",
+            "PC or encounter log integrity check FAILED. Run 'catch-pokemon verify' or 'catch-pokemon log' for details."
+                .red()
+                .bold()
+        );
+        return;
+    };
+    let tasks = current_tasks(&records.pokemon_db);
+    records.state.prune(&tasks);
+    // Tasks finished since the last catch are paid out here
+    if !records.claim(&tasks).is_empty() {
+        let _ = records.state.save();
+    }
+
+    println!("{}", "  Research".cyan().bold());
+    println!("{}", "  ════════".cyan());
+    println!();
+    let today = Local::now().date_naive();
+    let days_left = 7 - today.weekday().num_days_from_monday();
+    let week_reset = match days_left {
+        1 => "new tasks tomorrow".to_string(),
+        n => format!("new tasks in {} days", n),
+    };
+    for (title, reset, weekly) in [("Today", "new tasks at midnight".to_string(), false), ("This week", week_reset, true)] {
+        println!("  {} {}", title.bold(), format!("({})", reset).dimmed());
+        for task in tasks.iter().filter(|t| matches!(t.period, Period::Weekly(_)) == weekly) {
+            print_task(task, records.progress(task), records.state.claimed.contains(&task.id));
+        }
+        println!();
+    }
+    println!(
+        "{}",
+        "  Catches are counted from your PC and encounters from your encounter log. Rewards go to your bag.".dimmed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// What a player sees of a task, without the date-bearing id
+    fn content(task: &Task) -> (String, u64, Item, u32) {
+        (task.describe(), task.goal, task.reward, task.reward_count)
+    }
+
+    #[test]
+    fn tasks_are_the_same_for_everyone() {
+        let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap();
+        let daily = tasks_for(Period::Daily(day("2026-10-18")), &pokemon_db);
+        assert_eq!(daily.len(), DAILY_TASKS as usize);
+        assert_eq!(daily[0].id, "2026-10-18:0");
+        let again = tasks_for(Period::Daily(day("2026-10-18")), &pokemon_db);
+        assert_eq!(daily.iter().map(content).collect::<Vec<_>>(), again.iter().map(content).collect::<Vec<_>>());
+
+        let weekly = tasks_for(Period::Weekly(day("2026-10-12")), &pokemon_db);
+        assert_eq!(weekly.len(), WEEKLY_TASKS as usize);
+        assert_eq!(weekly[1].id, "2026-W42:1");
+    }
+
+    #[test]
+    fn tasks_change_from_day_to_day() {
+        let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap();
+        let start = day("2026-01-01");
+        let days: BTreeSet<Vec<(String, u64, Item, u32)>> = (0..30)
+            .map(|offset| tasks_for(Period::Daily(start + Days::new(offset)), &pokemon_db).iter().map(content).collect())
+            .collect();
+        // A month of days shouldn't repeat much, let alone collapse to one set
+        assert!(days.len() > 25, "only {} distinct days of tasks", days.len());
+    }
+
+    #[test]
+    fn goals_and_rewards_stay_in_range() {
+        let pokemon_db: HashMap<String, PokemonData> = serde_json::from_str(POKEMON_DATA).unwrap();
+        let start = day("2026-01-01");
+        for offset in 0..60 {
+            let date = start + Days::new(offset);
+            for task in tasks_for(Period::Daily(date), &pokemon_db) {
+                assert!((2..=10).contains(&task.goal), "{} has goal {}", task.id, task.goal);
+                assert!(!task.reward.is_evolution_item());
+            }
+            for task in tasks_for(Period::Weekly(date), &pokemon_db) {
+                assert!((8..=15).contains(&task.goal), "{} has goal {}", task.id, task.goal);
+                assert!(task.reward.is_evolution_item());
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::OnceLock;
+
+// Seed for this process, set once from --seed before any game logic runs
+static SEED: OnceLock<Option<u64>> = OnceLock::new();
+
+/// Fix the RNG seed for this run. A seeded run is a development run: it uses
+/// a separate storage profile and signing key (see storage::get_data_dir and
+/// crypto::derive_signing_key), so nothing it catches can reach the real PC.
+/// Release builds have no --seed, so they never set one.
+#[cfg(debug_assertions)]
+pub fn init(seed: Option<u64>) {
+    let _ = SEED.set(seed);
+}
+
+pub fn seed() -> Option<u64> {
+    SEED.get().copied().flatten()
+}
+
+pub fn is_seeded() -> bool {
+    seed().is_some()
+}
+
+/// RNG for game rolls (encounter, shiny, catch, flee, wiggles). Seeded runs
+/// are reproducible; normal runs draw from OS entropy.
+pub fn game_rng() -> StdRng {
+    match seed() {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
@@ -0,0 +1,132 @@
+use colored::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::catch::ThrowConditions;
+use crate::models::{Pokedex, StatusCondition, ROAMERS};
+use crate::storage::{get_roaming_path, load_signed, save_signed};
+
+/// Chance (percent) that an encounter is a roaming legendary while any roam
+pub const ROAMER_ENCOUNTER_CHANCE: f32 = 5.0;
+
+/// A legendary that starts roaming once its prerequisite species are caught
+#[derive(Deserialize, Debug)]
+struct RoamerDef {
+    species: String,
+    requires: Vec<String>,
+    roar: String,
+}
+
+/// A roaming legendary. HP and status carry over between appearances.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Roamer {
+    pub species: String,
+    pub hp: f32,
+    #[serde(default)]
+    pub status: StatusCondition,
+    #[serde(default)]
+    pub appearances: u32,
+    #[serde(default)]
+    pub caught: bool,
+    /// Encounter token of the current appearance; cleared once it flees
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl Roamer {
+    pub fn throw_conditions(&self) -> ThrowConditions {
+        ThrowConditions { hp_fraction: self.hp, status: self.status, ..Default::default() }
+    }
+
+    /// Whether this token belongs to an appearance the roamer hasn't fled yet.
+    /// Prints why not, since each appearance allows only one turn.
+    pub fn is_present(&self, token: Option<&str>) -> bool {
+        if token.is_some() && self.token.as_deref() == token {
+            return true;
+        }
+        println!("{}", format!("The wild {} has already fled. Keep an eye out for it.", self.species).red());
+        false
+    }
+}
+
+/// Roaming state shared by every shell
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RoamingState {
+    pub roamers: Vec<Roamer>,
+}
+
+impl RoamingState {
+    pub fn load() -> Self {
+        load_signed(&get_roaming_path(), "Roaming state").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_roaming_path(), self)
+    }
+
+    /// The roamer with this name, if it is still at large
+    pub fn active(&self, species: &str) -> Option<&Roamer> {
+        let species = species.to_lowercase();
+        self.roamers.iter().find(|r| !r.caught && r.species == species)
+    }
+
+    pub fn active_mut(&mut self, species: &str) -> Option<&mut Roamer> {
+        let species = species.to_lowercase();
+        self.roamers.iter_mut().find(|r| !r.caught && r.species == species)
+    }
+
+    pub fn is_roaming(&self, species: &str) -> bool {
+        self.active(species).is_some()
+    }
+
+    /// Occasionally swap an encounter for a roaming legendary
+    pub fn roll_encounter(&self, rng: &mut impl Rng) -> Option<&Roamer> {
+        let at_large: Vec<&Roamer> = self.roamers.iter().filter(|r| !r.caught).collect();
+        if at_large.is_empty() || rng.gen_range(0.0..100.0) >= ROAMER_ENCOUNTER_CHANCE {
+            return None;
+        }
+        Some(at_large[rng.gen_range(0..at_large.len())])
+    }
+}
+
+/// Start any roamers whose prerequisites are now caught. Called after a catch.
+pub fn check_unlocks(pokedex: &Pokedex) {
+    let defs: Vec<RoamerDef> = serde_json::from_str(ROAMERS).unwrap_or_default();
+    let mut state = RoamingState::load();
+    let mut unlocked = false;
+
+    for def in defs {
+        if state.roamers.iter().any(|r| r.species == def.species) {
+            continue;
+        }
+        let ready = def
+            .requires
+            .iter()
+            .all(|name| pokedex.entries.get(name).is_some_and(|e| e.caught));
+        if !ready {
+            continue;
+        }
+
+        println!();
+        println!("{}", def.roar.magenta().italic());
+        println!(
+            "{}",
+            format!("{} is now roaming! Keep an eye out in new terminals.", def.species).magenta().bold()
+        );
+        state.roamers.push(Roamer {
+            species: def.species,
+            hp: 1.0,
+            status: StatusCondition::None,
+            appearances: 0,
+            caught: false,
+            token: None,
+        });
+        unlocked = true;
+    }
+
+    if unlocked {
+        if let Err(e) = state.save() {
+            eprintln!("{}", format!("Could not save roaming state: {}", e).red());
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use colored::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::config::{Config, EncounterConfig};
+use crate::storage::get_schedule_path;
+
+/// What asked the scheduler for an encounter
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Trigger {
+    /// A new shell was opened
+    Shell,
+    /// The prompt hook ran after a command
+    Prompt,
+}
+
+pub enum Decision {
+    Encounter,
+    Skip(String),
+}
+
+/// Shared scheduler state. Stored as plain JSON (not encrypted) because the
+/// prompt hook reads it after every command and must not pay for key derivation.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ScheduleState {
+    #[serde(default)]
+    pub last_encounter_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub commands_since_encounter: u32,
+    /// A lure from the bag doubles encounter frequency until this time
+    #[serde(default)]
+    pub lure_until: Option<DateTime<Local>>,
+}
+
+impl ScheduleState {
+    pub fn lure_active(&self, now: DateTime<Local>) -> bool {
+        self.lure_until.is_some_and(|until| now < until)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(get_schedule_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = get_schedule_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so shells opening at the same time never read a partial file
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Parse "HH:MM-HH:MM" into a start and end time
+pub fn parse_quiet_hours(spec: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = spec.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    Some((start, end))
+}
+
+pub fn is_quiet(config: &EncounterConfig, now: DateTime<Local>) -> bool {
+    let weekday = now.weekday();
+    if config
+        .quiet_days
+        .iter()
+        .any(|d| d.parse::<Weekday>().map(|w| w == weekday).unwrap_or(false))
+    {
+        return true;
+    }
+
+    if let Some((start, end)) = config.quiet_hours.as_deref().and_then(parse_quiet_hours) {
+        let t = now.time();
+        return if start <= end {
+            t >= start && t < end
+        } else {
+            // Window wraps past midnight, e.g. 22:00-08:00
+            t >= start || t < end
+        };
+    }
+
+    false
+}
+
+/// Settings with a lure active: encounters twice as often
+pub fn lured(config: &EncounterConfig) -> EncounterConfig {
+    EncounterConfig {
+        interval_minutes: config.interval_minutes / 2,
+        shell_start_chance: (config.shell_start_chance * 2.0).min(100.0),
+        after_commands: config.after_commands.div_ceil(2),
+        ..config.clone()
+    }
+}
+
+/// Decide whether a trigger should produce an encounter. Updates the command
+/// counter for prompt triggers but leaves recording the encounter to the caller.
+pub fn decide(
+    config: &EncounterConfig,
+    state: &mut ScheduleState,
+    trigger: Trigger,
+    now: DateTime<Local>,
+    chance_roll: f32,
+) -> Decision {
+    if trigger == Trigger::Prompt {
+        if config.after_commands == 0 {
+            return Decision::Skip("command trigger disabled".to_string());
+        }
+        state.commands_since_encounter = state.commands_since_encounter.saturating_add(1);
+        if state.commands_since_encounter < config.after_commands {
+            return Decision::Skip(format!(
+                "{}/{} commands",
+                state.commands_since_encounter, config.after_commands
+            ));
+        }
+    }
+
+    if is_quiet(config, now) {
+        return Decision::Skip("quiet hours".to_string());
+    }
+
+    if let Some(last) = state.last_encounter_at {
+        let elapsed = now.signed_duration_since(last).num_minutes();
+        if elapsed < config.interval_minutes as i64 {
+            return Decision::Skip(format!(
+                "next encounter in {} min",
+                config.interval_minutes as i64 - elapsed
+            ));
+        }
+    }
+
+    if trigger == Trigger::Shell && chance_roll >= config.shell_start_chance {
+        return Decision::Skip("shell start roll failed".to_string());
+    }
+
+    Decision::Encounter
+}
+
+/// Used by the shell hooks: exit 0 when an encounter should start, 1 otherwise
+pub fn check_trigger(trigger: Trigger, verbose: bool) -> ! {
+    let config = Config::load();
+
+    // Fast path for the prompt hook when the command trigger is off
+    if trigger == Trigger::Prompt && config.encounters.after_commands == 0 {
+        std::process::exit(1);
+    }
+
+    // A new terminal is a step for eggs, whether or not it gets an encounter,
+    // and the first one of the day trains the battle team
+    if trigger == Trigger::Shell {
+        crate::eggs::step_session();
+        crate::training::daily_session();
+    }
+
+    let mut state = ScheduleState::load();
+    let now = Local::now();
+    let roll = rand::thread_rng().gen_range(0.0..100.0);
+    let encounters = if state.lure_active(now) { lured(&config.encounters) } else { config.encounters };
+
+    match decide(&encounters, &mut state, trigger, now, roll) {
+        Decision::Encounter => {
+            state.last_encounter_at = Some(now);
+            state.commands_since_encounter = 0;
+            let _ = state.save();
+            if verbose {
+                println!("encounter");
+            }
+            std::process::exit(0);
+        }
+        Decision::Skip(reason) => {
+            if trigger == Trigger::Prompt {
+                let _ = state.save();
+            }
+            if verbose {
+                println!("skip: {}", reason);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Used by the shell setup: exit 0 when the command trigger is on, so the
+/// prompt hook is only installed for those who opted in
+pub fn check_commands_enabled() -> ! {
+    std::process::exit(if Config::load().encounters.after_commands > 0 { 0 } else { 1 });
+}
+
+pub fn schedule_command(
+    every: Option<u32>,
+    chance: Option<f32>,
+    quiet_hours: Option<String>,
+    quiet_days: Option<String>,
+    after_commands: Option<u32>,
+) {
+    let mut config = Config::load();
+    let mut changed = false;
+
+    if let Some(minutes) = every {
+        config.encounters.interval_minutes = minutes;
+        changed = true;
+    }
+
+    if let Some(pct) = chance {
+        if !(0.0..=100.0).contains(&pct) {
+            eprintln!("{}", "Chance must be between 0 and 100.".red());
+            return;
+        }
+        config.encounters.shell_start_chance = pct;
+        changed = true;
+    }
+
+    if let Some(spec) = quiet_hours {
+        if spec == "off" {
+            config.encounters.quiet_hours = None;
+        } else if parse_quiet_hours(&spec).is_some() {
+            config.encounters.quiet_hours = Some(spec);
+        } else {
+            eprintln!("{}", "Quiet hours must look like 22:00-08:00 (or 'off').".red());
+            return;
+        }
+        changed = true;
+    }
+
+    if let Some(spec) = quiet_days {
+        let mut days = Vec::new();
+        if spec != "none" {
+            for d in spec.split(',').map(|d| d.trim().to_lowercase()) {
+                if d.parse::<Weekday>().is_err() {
+                    eprintln!("{}", format!("Unknown day '{}'. Use e.g. sat,sun (or 'none').", d).red());
+                    return;
+                }
+                days.push(d);
+            }
+        }
+        config.encounters.quiet_days = days;
+        changed = true;
+    }
+
+    let mut hook_added = false;
+    if let Some(n) = after_commands {
+        hook_added = config.encounters.after_commands == 0 && n > 0;
+        config.encounters.after_commands = n;
+        changed = true;
+    }
+
+    if changed {
+        if let Err(e) = config.save() {
+            eprintln!("{}", format!("Error saving config: {}", e).red());
+            return;
+        }
+        println!("{}", "Encounter schedule updated.".green());
+        if hook_added {
+            println!("{}", "Open a new shell for the command trigger to start counting.".dimmed());
+        }
+        println!();
+    }
+
+    let enc = &config.encounters;
+    let state = ScheduleState::load();
+    let now = Local::now();
+
+    println!("{}", "  Encounter Schedule".cyan().bold());
+    println!("{}", "  ══════════════════".cyan());
+    println!();
+    println!(
+        "  Frequency:     {}",
+        if enc.interval_minutes == 0 {
+            "every shell".to_string()
+        } else {
+            format!("at most one per {} min", enc.interval_minutes)
+        }
+        .yellow()
+    );
+    println!("  Shell start:   {}", format!("{:.0}% chance", enc.shell_start_chance).yellow());
+    println!(
+        "  Quiet hours:   {}",
+        enc.quiet_hours.clone().unwrap_or_else(|| "off".to_string()).yellow()
+    );
+    println!(
+        "  Quiet days:    {}",
+        if enc.quiet_days.is_empty() { "none".to_string() } else { enc.quiet_days.join(", ") }.yellow()
+    );
+    println!(
+        "  After commands:{}",
+        if enc.after_commands == 0 {
+            " off".to_string()
+        } else {
+            format!(" every {} commands ({} so far)", enc.after_commands, state.commands_since_encounter)
+        }
+        .yellow()
+    );
+    println!();
+
+    match state.last_encounter_at {
+        Some(last) => {
+            println!("  Last encounter: {}", last.format("%Y-%m-%d %H:%M").to_string().dimmed());
+            let elapsed = now.signed_duration_since(last).num_minutes();
+            let wait = enc.interval_minutes as i64 - elapsed;
+            if wait > 0 {
+                println!("  Next eligible:  {}", format!("in {} min", wait).dimmed());
+            }
+        }
+        None => println!("  Last encounter: {}", "never".dimmed()),
+    }
+    if is_quiet(enc, now) {
+        println!("  {}", "Quiet time is active — no encounters right now.".magenta());
+    }
+    if let Some(until) = state.lure_until.filter(|_| state.lure_active(now)) {
+        println!(
+            "  {}",
+            format!("A lure is active until {} — encounters happen twice as often.", until.format("%H:%M")).magenta()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// 2026-10-14 is a Wednesday
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let config = EncounterConfig { quiet_hours: Some("22:00-08:00".to_string()), ..Default::default() };
+        assert!(is_quiet(&config, at(14, 23, 30)));
+        assert!(is_quiet(&config, at(14, 7, 59)));
+        assert!(!is_quiet(&config, at(14, 8, 0)));
+        assert!(!is_quiet(&config, at(14, 21, 59)));
+
+        let daytime = EncounterConfig { quiet_hours: Some("09:00-17:00".to_string()), ..Default::default() };
+        assert!(is_quiet(&daytime, at(14, 12, 0)));
+        assert!(!is_quiet(&daytime, at(14, 17, 0)));
+    }
+
+    #[test]
+    fn quiet_days_match_the_weekday() {
+        let config = EncounterConfig { quiet_days: vec!["sat".to_string(), "sun".to_string()], ..Default::default() };
+        assert!(is_quiet(&config, at(17, 12, 0)));
+        assert!(is_quiet(&config, at(18, 12, 0)));
+        assert!(!is_quiet(&config, at(14, 12, 0)));
+    }
+
+    #[test]
+    fn lures_double_every_setting() {
+        let config = EncounterConfig { interval_minutes: 30, shell_start_chance: 60.0, after_commands: 5, ..Default::default() };
+        let lured = lured(&config);
+        assert_eq!(lured.interval_minutes, 15);
+        assert_eq!(lured.shell_start_chance, 100.0);
+        assert_eq!(lured.after_commands, 3);
+    }
+
+    #[test]
+    fn prompt_triggers_count_commands() {
+        let now = at(14, 12, 0);
+        let mut state = ScheduleState::default();
+        let off = EncounterConfig::default();
+        assert!(matches!(decide(&off, &mut state, Trigger::Prompt, now, 0.0), Decision::Skip(_)));
+        assert_eq!(state.commands_since_encounter, 0);
+
+        let config = EncounterConfig { after_commands: 3, ..Default::default() };
+        for _ in 0..2 {
+            assert!(matches!(decide(&config, &mut state, Trigger::Prompt, now, 0.0), Decision::Skip(_)));
+        }
+        assert!(matches!(decide(&config, &mut state, Trigger::Prompt, now, 0.0), Decision::Encounter));
+    }
+
+    #[test]
+    fn shell_triggers_respect_interval_and_chance() {
+        let now = at(14, 12, 0);
+        let config = EncounterConfig { interval_minutes: 30, shell_start_chance: 50.0, ..Default::default() };
+        let mut state = ScheduleState { last_encounter_at: Some(at(14, 11, 45)), ..Default::default() };
+        assert!(matches!(decide(&config, &mut state, Trigger::Shell, now, 0.0), Decision::Skip(_)));
+
+        state.last_encounter_at = Some(at(14, 11, 30));
+        assert!(matches!(decide(&config, &mut state, Trigger::Shell, now, 49.9), Decision::Encounter));
+        assert!(matches!(decide(&config, &mut state, Trigger::Shell, now, 50.0), Decision::Skip(_)));
+    }
+}
@@ -7,6 +7,9 @@ use std::process::Command;
 use crate::models::{
     BattleTeam, BattleTeamEntry, PcStorage, PokemonData, POKEMON_DATA, SHELL_FUNCTIONS,
 };
+use crate::annotations::Annotations;
+use crate::buddy::refresh_prompt;
+use crate::training::{best_ivs, effective_power, TeamTraining};
 
 pub fn setup_shell() {
     // Determine install directory
@@ -271,6 +274,12 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
             eprintln!("{}", format!("Error clearing team: {}", e).red());
         } else {
             println!("{}", "Battle team cleared.".green());
+            let mut training = TeamTraining::load();
+            if training.buddy.is_some() {
+                training.set_buddy(None);
+                let _ = training.save();
+                refresh_prompt();
+            }
         }
         return;
     }
@@ -353,6 +362,12 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
             if let Err(e) = team.save() {
                 eprintln!("{}", format!("Error saving team: {}", e).red());
             } else {
+                let mut training = TeamTraining::load();
+                if training.is_buddy(&name) {
+                    training.set_buddy(None);
+                    let _ = training.save();
+                    refresh_prompt();
+                }
                 println!(
                     "{}",
                     format!(
@@ -376,6 +391,10 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
         return;
     }
 
+    let storage = PcStorage::load();
+    let training = TeamTraining::load();
+    let annotations = Annotations::load();
+
     println!();
     println!("{}", "  Battle Team".cyan().bold());
     println!("{}", "  ═══════════".cyan());
@@ -423,11 +442,13 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
                 "common" => "Common".bright_black().to_string(),
                 _ => data.category.clone(),
             };
-            (type_strings.join(" / "), data.power_rank as u32, cat)
+            (type_strings.join(" / "), data.power_rank, cat)
         } else {
             ("???".to_string(), 0, "unknown".to_string())
         };
 
+        let progress = training.get(&entry.name);
+        let power = effective_power(power, progress.level(), best_ivs(&storage, &entry.name, entry.shiny));
         total_power += power;
 
         let shiny_str = if entry.shiny {
@@ -435,12 +456,27 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
         } else {
             String::new()
         };
+        let buddy_str = if training.is_buddy(&entry.name) {
+            " [Buddy]".magenta().bold().to_string()
+        } else {
+            String::new()
+        };
+        let name_str = match annotations.team_nickname(&storage, &entry.name, entry.shiny) {
+            Some(nickname) => format!("{} ({})", nickname.green().bold(), entry.name),
+            None => entry.name.green().bold().to_string(),
+        };
+        let next = match progress.xp_to_next() {
+            Some(xp) => format!("{} XP to next", xp),
+            None => "max level".to_string(),
+        };
 
         println!(
-            "  [{}] {}{}",
+            "  [{}] {}{}{} {}",
             format!("{:2}", i + 1).dimmed(),
-            entry.name.green().bold(),
-            shiny_str
+            name_str,
+            shiny_str,
+            buddy_str,
+            format!("Lv. {}", progress.level()).cyan().bold()
         );
         println!(
             "      {} | Power: {} | {}",
@@ -448,6 +484,11 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
             format!("{}", power).bright_yellow().bold(),
             cat_display
         );
+        println!(
+            "      {} {}",
+            progress.hearts().magenta(),
+            format!("({})", next).dimmed()
+        );
     }
 
     println!();
@@ -461,4 +502,8 @@ pub fn manage_team(add: Option<String>, remove: Option<String>, clear: bool) {
         format!("{}/20 slots", team.pokemon.len()).cyan(),
         format!("{}", total_power).bright_yellow().bold(),
     );
+    if training.buddy.is_none() {
+        println!();
+        println!("{}", "  Pick a buddy with: catch-pokemon buddy set <name>".dimmed());
+    }
 }
@@ -0,0 +1,321 @@
+use colored::*;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::catch::{capture_chance, encounter_pool, flee_chance, throw_ball, ThrowConditions, SHINY_ODDS};
+use crate::models::{PokemonData, StatusCondition, POKEMON_DATA};
+use crate::rng::game_rng;
+
+// Give up on a session after this many throws (flee is capped at 80%, so
+// this is never reached in practice)
+const MAX_THROWS: u32 = 1000;
+
+#[derive(Serialize, Default)]
+struct CategoryReport {
+    species: usize,
+    /// Exact encounter probability from the weights (percent)
+    encounter_pct: f64,
+    /// Share of simulated encounters in this category (percent)
+    simulated_encounter_pct: f64,
+    /// Share of encounters that ended in a catch (percent)
+    catch_pct: f64,
+    /// Share of encounters where the Pokemon fled (percent)
+    flee_pct: f64,
+    balls_per_catch: f64,
+    #[serde(skip)]
+    encounters: u64,
+    #[serde(skip)]
+    catches: u64,
+    #[serde(skip)]
+    flees: u64,
+    #[serde(skip)]
+    throws: u64,
+}
+
+#[derive(Serialize)]
+struct SimulationReport {
+    sessions: u64,
+    seed: Option<u64>,
+    /// HP fraction and status of every wild Pokemon thrown at
+    hp: f32,
+    status: String,
+    shiny_odds: String,
+    simulated_shiny_pct: f64,
+    balls_per_catch: f64,
+    catch_pct: f64,
+    flee_pct: f64,
+    /// Expected encounters before every species has been caught at least once
+    expected_encounters_to_complete_dex: f64,
+    /// Species with the lowest catches-per-encounter rate, hardest first
+    hardest_species: Vec<HardestSpecies>,
+    categories: BTreeMap<String, CategoryReport>,
+}
+
+#[derive(Serialize)]
+struct HardestSpecies {
+    name: String,
+    category: String,
+    expected_encounters: f64,
+}
+
+/// Probability that an encounter with this Pokemon ends in a catch when the
+/// player keeps throwing until it is caught or flees
+fn session_catch_probability(catch_pct: f32, base_flee: f32) -> f64 {
+    let c = catch_pct as f64 / 100.0;
+    let mut still_here = 1.0;
+    let mut caught = 0.0;
+    for attempt in 1..=MAX_THROWS {
+        caught += still_here * c;
+        let f = flee_chance(base_flee, attempt) as f64 / 100.0;
+        still_here *= (1.0 - c) * (1.0 - f);
+        if still_here < 1e-12 {
+            break;
+        }
+    }
+    caught
+}
+
+/// Expected encounters to catch every species, treating each species as an
+/// independent Poisson process with rate `rates[i]` per encounter:
+/// E[T] = ∫ 1 - Π(1 - e^(-r·t)) dt, integrated on a log scale.
+fn expected_completion(rates: &[f64]) -> f64 {
+    let rates: Vec<f64> = rates.iter().copied().filter(|r| *r > 0.0).collect();
+    if rates.is_empty() {
+        return f64::INFINITY;
+    }
+    let r_min = rates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let r_max = rates.iter().cloned().fold(0.0, f64::max);
+
+    // Below t_lo the integrand is ~1; above t_hi it is ~0
+    let t_lo = 1e-3 / r_max;
+    let t_hi = 60.0 / r_min;
+    let (u_lo, u_hi) = (t_lo.ln(), t_hi.ln());
+    let steps = 4000;
+    let du = (u_hi - u_lo) / steps as f64;
+
+    let integrand = |t: f64| -> f64 {
+        // ln Π(1 - e^(-r·t)), computed stably for small r·t
+        let log_all: f64 = rates.iter().map(|r| (-(-r * t).exp_m1()).ln()).sum();
+        -log_all.exp_m1()
+    };
+
+    let mut total = t_lo;
+    let mut prev = integrand(t_lo) * t_lo;
+    for i in 1..=steps {
+        let t = (u_lo + du * i as f64).exp();
+        let value = integrand(t) * t;
+        total += (prev + value) / 2.0 * du;
+        prev = value;
+    }
+    total
+}
+
+pub fn simulate_command(sessions: u64, hp: f32, status: &str, json: bool) {
+    if sessions == 0 {
+        eprintln!("{}", "Sessions must be at least 1.".red());
+        return;
+    }
+    if !(hp > 0.0 && hp <= 1.0) {
+        eprintln!("{}", "HP must be a fraction between 0 (exclusive) and 1.".red());
+        return;
+    }
+    let status = match StatusCondition::from_name(status) {
+        Some(status) => status,
+        None => {
+            eprintln!("{}", format!("Unknown status '{}'. Use none, poison, burn, paralysis, sleep or freeze.", status).red());
+            return;
+        }
+    };
+    // The simulated player has an empty Pokedex, so there are no critical captures
+    let conditions = ThrowConditions { hp_fraction: hp, status, ..Default::default() };
+
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let pool = encounter_pool(&pokemon_db);
+    let total_weight: u64 = pool.iter().map(|slot| slot.weight as u64).sum();
+    if total_weight == 0 {
+        eprintln!("{}", "No encounterable Pokemon.".red());
+        return;
+    }
+
+    let mut categories: BTreeMap<String, CategoryReport> = BTreeMap::new();
+    for slot in &pool {
+        let report = categories.entry(slot.data.category.clone()).or_default();
+        if slot.form.is_none() {
+            report.species += 1;
+        }
+        report.encounter_pct += slot.weight as f64 / total_weight as f64 * 100.0;
+    }
+
+    // Cumulative weights for a binary-search weighted pick
+    let mut cumulative = Vec::with_capacity(pool.len());
+    let mut running = 0u64;
+    for slot in &pool {
+        running += slot.weight as u64;
+        cumulative.push(running);
+    }
+
+    let mut rng = game_rng();
+    let mut shinies = 0u64;
+    for _ in 0..sessions {
+        let roll = rng.gen_range(0..total_weight);
+        let idx = cumulative.partition_point(|&c| c <= roll);
+        let slot = &pool[idx];
+        let data = slot.data;
+        if rng.gen_range(0..SHINY_ODDS) == 0 {
+            shinies += 1;
+        }
+
+        let report = categories.get_mut(&data.category).expect("category registered above");
+        report.encounters += 1;
+        for attempt in 1..=MAX_THROWS {
+            report.throws += 1;
+            if throw_ball(slot.catch_rate, &conditions, 0, &mut rng).caught {
+                report.catches += 1;
+                break;
+            }
+            if rng.gen_range(0.0..100.0) < flee_chance(data.flee_rate as f32, attempt) {
+                report.flees += 1;
+                break;
+            }
+        }
+    }
+
+    let (mut catches, mut flees, mut throws) = (0u64, 0u64, 0u64);
+    for report in categories.values_mut() {
+        let encounters = report.encounters.max(1) as f64;
+        report.simulated_encounter_pct = report.encounters as f64 / sessions as f64 * 100.0;
+        report.catch_pct = report.catches as f64 / encounters * 100.0;
+        report.flee_pct = report.flees as f64 / encounters * 100.0;
+        report.balls_per_catch = if report.catches > 0 { report.throws as f64 / report.catches as f64 } else { 0.0 };
+        catches += report.catches;
+        flees += report.flees;
+        throws += report.throws;
+    }
+
+    // Exact per-species catch rate per encounter drives the dex completion
+    // estimate. Catching any form of a species counts for the dex.
+    let mut species_rates: Vec<(&String, &PokemonData, f64)> = Vec::new();
+    for slot in &pool {
+        let p = slot.weight as f64 / total_weight as f64;
+        let q = session_catch_probability(capture_chance(slot.catch_rate, &conditions, 0), slot.data.flee_rate as f32);
+        match species_rates.last_mut() {
+            Some(last) if last.0 == slot.name => last.2 += p * q,
+            _ => species_rates.push((slot.name, slot.data, p * q)),
+        }
+    }
+    let rates: Vec<f64> = species_rates.iter().map(|(_, _, r)| *r).collect();
+    let expected_to_complete = expected_completion(&rates);
+
+    species_rates.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+    let hardest_species: Vec<HardestSpecies> = species_rates
+        .iter()
+        .take(5)
+        .map(|(name, data, rate)| HardestSpecies {
+            name: name.replace('_', "-"),
+            category: data.category.clone(),
+            expected_encounters: if *rate > 0.0 { 1.0 / rate } else { f64::INFINITY },
+        })
+        .collect();
+
+    let report = SimulationReport {
+        sessions,
+        seed: crate::rng::seed(),
+        hp,
+        status: status.display_name().to_string(),
+        shiny_odds: format!("1/{}", SHINY_ODDS),
+        simulated_shiny_pct: shinies as f64 / sessions as f64 * 100.0,
+        balls_per_catch: if catches > 0 { throws as f64 / catches as f64 } else { 0.0 },
+        catch_pct: catches as f64 / sessions as f64 * 100.0,
+        flee_pct: flees as f64 / sessions as f64 * 100.0,
+        expected_encounters_to_complete_dex: expected_to_complete,
+        hardest_species,
+        categories,
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(out) => println!("{}", out),
+            Err(e) => eprintln!("{}", format!("Error encoding report: {}", e).red()),
+        }
+        return;
+    }
+
+    print_report(&report);
+}
+
+fn print_report(report: &SimulationReport) {
+    println!("{}", "  Encounter Simulation".cyan().bold());
+    println!("{}", "  ════════════════════".cyan());
+    println!();
+    println!("  Sessions:        {}", report.sessions.to_string().yellow());
+    println!(
+        "  Wild Pokemon:    {}",
+        format!("{:.0}% HP, {}", report.hp * 100.0, report.status).yellow()
+    );
+    println!("  Catch rate:      {}", format!("{:.2}% of encounters", report.catch_pct).yellow());
+    println!("  Flee rate:       {}", format!("{:.2}% of encounters", report.flee_pct).yellow());
+    println!("  Balls per catch: {}", format!("{:.2}", report.balls_per_catch).yellow());
+    println!(
+        "  Shiny:           {}",
+        format!("{} odds, {:.4}% simulated", report.shiny_odds, report.simulated_shiny_pct).yellow()
+    );
+    println!(
+        "  Complete dex:    {}",
+        format!("~{} encounters", format_count(report.expected_encounters_to_complete_dex)).yellow().bold()
+    );
+    println!();
+
+    println!(
+        "  {:<18} {:>7} {:>11} {:>11} {:>9} {:>9} {:>10}",
+        "Category".bold(),
+        "Species".bold(),
+        "Encounter%".bold(),
+        "Simulated%".bold(),
+        "Caught%".bold(),
+        "Fled%".bold(),
+        "Balls/catch".bold()
+    );
+    println!("  {}", "─".repeat(82).dimmed());
+    for (name, c) in &report.categories {
+        println!(
+            "  {:<18} {:>7} {:>11} {:>11} {:>9} {:>9} {:>10}",
+            name,
+            c.species,
+            format!("{:.3}", c.encounter_pct),
+            format!("{:.3}", c.simulated_encounter_pct),
+            format!("{:.1}", c.catch_pct),
+            format!("{:.1}", c.flee_pct),
+            if c.catches > 0 { format!("{:.2}", c.balls_per_catch) } else { "-".to_string() }
+        );
+    }
+    println!();
+
+    println!("  {}", "Hardest to catch (expected encounters for one):".bold());
+    for s in &report.hardest_species {
+        println!(
+            "    {:<20} {:<18} {}",
+            s.name.green(),
+            s.category.dimmed(),
+            format_count(s.expected_encounters).yellow()
+        );
+    }
+}
+
+fn format_count(n: f64) -> String {
+    if !n.is_finite() {
+        "never".to_string()
+    } else if n >= 1_000_000.0 {
+        format!("{:.1}M", n / 1_000_000.0)
+    } else if n >= 1_000.0 {
+        format!("{:.1}k", n / 1_000.0)
+    } else {
+        format!("{:.0}", n)
+    }
+}
@@ -0,0 +1,289 @@
+use flate2::read::ZlibDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+
+// Sprite archive packed by build.rs (see pack_sprites there for the layout)
+const SPRITE_ARCHIVE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/sprites.bin"));
+
+pub type Rgb = [u8; 3];
+
+pub struct Sprite {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major pixels, None = transparent
+    pub pixels: Vec<Option<Rgb>>,
+}
+
+impl Sprite {
+    fn pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            None
+        }
+    }
+
+    /// Shrink by an integer factor, keeping the most common colour in each block.
+    /// A block only stays opaque if at least half of it was opaque.
+    pub fn downscale(&self, factor: usize) -> Sprite {
+        if factor <= 1 {
+            return Sprite { width: self.width, height: self.height, pixels: self.pixels.clone() };
+        }
+        let width = self.width.div_ceil(factor);
+        let height = self.height.div_ceil(factor);
+        let mut pixels = Vec::with_capacity(width * height);
+        for by in 0..height {
+            for bx in 0..width {
+                let mut counts: Vec<(Rgb, usize)> = Vec::new();
+                let mut opaque = 0;
+                for y in by * factor..(by * factor + factor) {
+                    for x in bx * factor..(bx * factor + factor) {
+                        if let Some(rgb) = self.pixel(x, y) {
+                            opaque += 1;
+                            match counts.iter_mut().find(|(c, _)| *c == rgb) {
+                                Some(entry) => entry.1 += 1,
+                                None => counts.push((rgb, 1)),
+                            }
+                        }
+                    }
+                }
+                pixels.push(if opaque * 2 >= factor * factor {
+                    counts.iter().max_by_key(|(_, n)| *n).map(|(c, _)| *c)
+                } else {
+                    None
+                });
+            }
+        }
+        Sprite { width, height, pixels }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Mono,
+}
+
+struct ArchiveEntry {
+    offset: usize,
+    width: usize,
+    height: usize,
+    palette_len: usize,
+}
+
+struct Archive {
+    data: Vec<u8>,
+    // (name, shiny) -> entry
+    entries: HashMap<(String, bool), ArchiveEntry>,
+}
+
+fn archive() -> &'static Archive {
+    static ARCHIVE: OnceLock<Archive> = OnceLock::new();
+    ARCHIVE.get_or_init(|| {
+        let mut data = Vec::new();
+        if ZlibDecoder::new(SPRITE_ARCHIVE).read_to_end(&mut data).is_err() {
+            data.clear();
+        }
+        let entries = index_archive(&data).unwrap_or_default();
+        Archive { data, entries }
+    })
+}
+
+fn index_archive(data: &[u8]) -> Option<HashMap<(String, bool), ArchiveEntry>> {
+    if data.len() < 7 || &data[..4] != b"CPSP" || data[4] != 1 {
+        return None;
+    }
+    let count = u16::from_le_bytes([data[5], data[6]]) as usize;
+    let mut entries = HashMap::with_capacity(count);
+    let mut pos = 7;
+    for _ in 0..count {
+        let name_len = *data.get(pos)? as usize;
+        let name = std::str::from_utf8(data.get(pos + 1..pos + 1 + name_len)?).ok()?.to_string();
+        pos += 1 + name_len;
+        let header = data.get(pos..pos + 4)?;
+        let (shiny, width, height, palette_len) =
+            (header[0] & 1 == 1, header[1] as usize, header[2] as usize, header[3] as usize);
+        pos += 4;
+        let entry = ArchiveEntry { offset: pos, width, height, palette_len };
+        pos += palette_len * 3 + width * height;
+        if pos > data.len() {
+            return None;
+        }
+        entries.insert((name, shiny), entry);
+    }
+    Some(entries)
+}
+
+fn sprite_key(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+/// Names of every Pokemon with an embedded (regular) sprite, in display format
+pub fn embedded_names() -> Vec<&'static str> {
+    archive()
+        .entries
+        .keys()
+        .filter(|(_, shiny)| !shiny)
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+/// Whether a regular sprite is embedded for this name (e.g. "vulpix-alola")
+pub fn has_sprite(name: &str) -> bool {
+    archive().entries.contains_key(&(sprite_key(name), false))
+}
+
+/// Load a sprite from the embedded archive. Shiny requests fall back to the
+/// regular sprite when no shiny variant was packed.
+pub fn load_sprite(name: &str, shiny: bool) -> Option<Sprite> {
+    let archive = archive();
+    let key = sprite_key(name);
+    let entry = archive
+        .entries
+        .get(&(key.clone(), shiny))
+        .or_else(|| archive.entries.get(&(key, false)))?;
+
+    let palette_bytes = &archive.data[entry.offset..entry.offset + entry.palette_len * 3];
+    let palette: Vec<Rgb> = palette_bytes.chunks(3).map(|c| [c[0], c[1], c[2]]).collect();
+    let start = entry.offset + entry.palette_len * 3;
+    let pixels = archive.data[start..start + entry.width * entry.height]
+        .iter()
+        .map(|&i| if i == 0 { None } else { palette.get(i as usize - 1).copied() })
+        .collect();
+
+    Some(Sprite { width: entry.width, height: entry.height, pixels })
+}
+
+/// Pick a colour mode from the config override or the environment
+pub fn detect_color_mode() -> ColorMode {
+    match Config::load().sprites.color.as_str() {
+        "truecolor" => return ColorMode::TrueColor,
+        "256" => return ColorMode::Ansi256,
+        "mono" => return ColorMode::Mono,
+        _ => {}
+    }
+
+    if std::env::var_os("NO_COLOR").is_some() || std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false) {
+        return ColorMode::Mono;
+    }
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+        return ColorMode::TrueColor;
+    }
+    ColorMode::Ansi256
+}
+
+/// Nearest colour in the xterm 6x6x6 cube or greyscale ramp
+pub fn rgb_to_ansi256(rgb: Rgb) -> u8 {
+    let to_level = |v: u8| -> u8 {
+        if v < 48 { 0 } else if v < 115 { 1 } else { (v - 35) / 40 }
+    };
+    let level_value = |l: u8| -> u8 { if l == 0 { 0 } else { 55 + l * 40 } };
+    let (r, g, b) = (to_level(rgb[0]), to_level(rgb[1]), to_level(rgb[2]));
+    let cube = [level_value(r), level_value(g), level_value(b)];
+
+    let avg = (rgb[0] as u32 + rgb[1] as u32 + rgb[2] as u32) / 3;
+    let grey_idx = if avg > 238 { 23 } else { (avg.saturating_sub(3) / 10) as u8 };
+    let grey_v = 8 + grey_idx * 10;
+
+    let dist = |c: [u8; 3]| -> u32 {
+        (0..3).map(|i| (c[i] as i32 - rgb[i] as i32).pow(2) as u32).sum()
+    };
+    if dist([grey_v, grey_v, grey_v]) < dist(cube) {
+        232 + grey_idx
+    } else {
+        16 + 36 * r + 6 * g + b
+    }
+}
+
+fn color_code(rgb: Rgb, mode: ColorMode, background: bool) -> String {
+    let layer = if background { 48 } else { 38 };
+    match mode {
+        ColorMode::TrueColor => format!("\x1B[{};2;{};{};{}m", layer, rgb[0], rgb[1], rgb[2]),
+        ColorMode::Ansi256 => format!("\x1B[{};5;{}m", layer, rgb_to_ansi256(rgb)),
+        ColorMode::Mono => String::new(),
+    }
+}
+
+/// Render a sprite as half-block text (two pixel rows per line), scaled down
+/// until it fits in `max_width` columns.
+pub fn render(sprite: &Sprite, mode: ColorMode, max_width: usize) -> Vec<String> {
+    let factor = if max_width == 0 { 1 } else { sprite.width.div_ceil(max_width).max(1) };
+    let scaled = sprite.downscale(factor);
+
+    let mut lines = Vec::new();
+    for y in (0..scaled.height).step_by(2) {
+        let mut line = String::new();
+        for x in 0..scaled.width {
+            let top = scaled.pixel(x, y);
+            let bottom = scaled.pixel(x, y + 1);
+            if mode == ColorMode::Mono {
+                line.push(match (top, bottom) {
+                    (Some(_), Some(_)) => '█',
+                    (Some(_), None) => '▀',
+                    (None, Some(_)) => '▄',
+                    (None, None) => ' ',
+                });
+                continue;
+            }
+            match (top, bottom) {
+                (None, None) => line.push_str("\x1B[0m "),
+                (Some(t), None) => {
+                    line.push_str("\x1B[0m");
+                    line.push_str(&color_code(t, mode, false));
+                    line.push('▀');
+                }
+                (None, Some(b)) => {
+                    line.push_str("\x1B[0m");
+                    line.push_str(&color_code(b, mode, false));
+                    line.push('▄');
+                }
+                (Some(t), Some(b)) => {
+                    line.push_str(&color_code(t, mode, false));
+                    line.push_str(&color_code(b, mode, true));
+                    line.push('▀');
+                }
+            }
+        }
+        if mode != ColorMode::Mono {
+            line.push_str("\x1B[0m");
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Sprite lines for a Pokemon, fitted to `max_width` columns. Uses the embedded
+/// sprite; pokemon-colorscripts is only consulted for names missing from the
+/// archive (e.g. source builds without sprite data).
+pub fn sprite_lines(name: &str, shiny: bool, max_width: usize) -> Vec<String> {
+    if let Some(sprite) = load_sprite(name, shiny) {
+        return render(&sprite, detect_color_mode(), max_width);
+    }
+
+    let display_name = sprite_key(name);
+    let mut args = vec!["-n", display_name.as_str(), "--no-title"];
+    if shiny {
+        args.push("-s");
+    }
+    Command::new("pokemon-colorscripts")
+        .args(&args)
+        .output()
+        .ok()
+        .filter(|r| r.status.success())
+        .map(|r| String::from_utf8_lossy(&r.stdout).lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Print a Pokemon sprite sized to the current terminal
+pub fn print_sprite(name: &str, shiny: bool) {
+    let width = crossterm::terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+    for line in sprite_lines(name, shiny, width.saturating_sub(1)) {
+        println!("{}", line);
+    }
+}
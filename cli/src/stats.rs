@@ -0,0 +1,708 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use colored::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{stdout, IsTerminal, Write};
+
+use crossterm::{
+    cursor, terminal, ExecutableCommand,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+};
+
+use crate::catch::{encounter_pool, true_odds};
+use crate::config::{Config, MAX_PITY_RAMP};
+use crate::display::{color_type, progress_bar};
+use crate::encounter_log::{self, EncounterResult, Outcome};
+use crate::models::{form_name, PcStorage, Pokedex, PokemonData, POKEMON_DATA};
+use crate::pity::PityState;
+
+/// Width of the distribution bars, in cells
+const BAR_WIDTH: usize = 24;
+/// Months shown in the Pokedex completion chart
+const COMPLETION_MONTHS: usize = 12;
+
+#[derive(Serialize, Debug, Default)]
+struct EncounterTotals {
+    total: u64,
+    caught: usize,
+    fled: usize,
+    fainted: usize,
+    escaped: usize,
+    skipped: usize,
+    /// Percent of finished encounters that ended in a catch. None before the
+    /// encounter log existed.
+    catch_rate: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct Streak {
+    days: u32,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+}
+
+#[derive(Serialize, Debug)]
+struct RareCatch {
+    name: String,
+    caught_at: DateTime<Local>,
+    /// The PC's "true odds": category encounter share times Poké Ball catch chance
+    true_odds_percent: f64,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct BallStats {
+    /// Balls thrown, from the encounter log
+    thrown: usize,
+    caught: usize,
+    success_rate: Option<f64>,
+    /// Pokemon in the PC caught with this ball
+    in_pc: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct PitySummary {
+    enabled: bool,
+    since_rare: u32,
+    longest_drought: u32,
+    last_rare: Option<String>,
+    last_rare_at: Option<DateTime<Local>>,
+    multiplier: f32,
+}
+
+/// Everything the dashboard shows, and what `--json` exports
+#[derive(Serialize, Debug)]
+struct Dashboard {
+    generated_at: DateTime<Local>,
+    catches: usize,
+    shinies: usize,
+    /// Shinies per catch
+    shiny_ratio: f64,
+    species_caught: usize,
+    species_seen: usize,
+    species_total: usize,
+    encounters: EncounterTotals,
+    longest_streak: Streak,
+    current_streak: u32,
+    rarest: Option<RareCatch>,
+    catches_per_day: BTreeMap<NaiveDate, usize>,
+    categories: BTreeMap<String, usize>,
+    types: BTreeMap<String, usize>,
+    /// Species caught by the end of each month ("YYYY-MM")
+    dex_completion: BTreeMap<String, usize>,
+    balls: BTreeMap<String, BallStats>,
+    pity: PitySummary,
+}
+
+fn longest_streak(days: &BTreeSet<NaiveDate>) -> Streak {
+    let mut best = Streak::default();
+    let mut run: Option<(NaiveDate, NaiveDate, u32)> = None;
+    for &day in days {
+        run = match run {
+            Some((start, end, n)) if end + Days::new(1) == day => Some((start, day, n + 1)),
+            _ => Some((day, day, 1)),
+        };
+        if let Some((start, end, n)) = run {
+            if n > best.days {
+                best = Streak { days: n, start: Some(start), end: Some(end) };
+            }
+        }
+    }
+    best
+}
+
+/// Days in a row with a catch, counting from today, or from yesterday if
+/// nothing has been caught yet today
+fn current_streak(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> u32 {
+    let mut day = if days.contains(&today) { today } else { today - Days::new(1) };
+    let mut n = 0;
+    while days.contains(&day) {
+        n += 1;
+        day = day - Days::new(1);
+    }
+    n
+}
+
+fn month_key(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+/// Running total of species caught, one entry per month from the first catch
+fn dex_completion(first_caught: &[NaiveDate], today: NaiveDate) -> BTreeMap<String, usize> {
+    let mut completion = BTreeMap::new();
+    let Some(&first) = first_caught.iter().min() else {
+        return completion;
+    };
+    let mut month = first.with_day(1).unwrap_or(first);
+    while month <= today {
+        let next = month.checked_add_months(chrono::Months::new(1)).unwrap_or(today);
+        completion.insert(month_key(month), first_caught.iter().filter(|d| **d < next).count());
+        if next <= month {
+            break;
+        }
+        month = next;
+    }
+    completion
+}
+
+fn build_dashboard(pokemon_db: &HashMap<String, PokemonData>, config: &Config) -> Dashboard {
+    let pool = encounter_pool(pokemon_db);
+    let species_total = pool.iter().filter(|s| s.form.is_none()).count();
+    let storage = PcStorage::load();
+    let pokedex = Pokedex::load();
+    let pity = PityState::load();
+    let today = Local::now().date_naive();
+    let key = |name: &str| name.to_lowercase().replace('-', "_");
+
+    // The encounter log is the full record; older saves only have the Pokedex
+    let encounters = match encounter_log::load() {
+        Ok(entries) => encounter_log::encounters(&entries),
+        Err(msg) => {
+            eprintln!("{}", format!("Encounter log integrity check FAILED: {}", msg).red());
+            Vec::new()
+        }
+    };
+    let mut seen_species: BTreeSet<&str> =
+        pokedex.entries.values().filter(|e| e.seen).map(|e| e.name.as_str()).collect();
+    seen_species.extend(encounters.iter().map(|r| r.species.as_str()));
+
+    let count = |result: EncounterResult| encounters.iter().filter(|r| r.result == result).count();
+    let finished = encounters.len() - count(EncounterResult::Active);
+    let encounter_totals = EncounterTotals {
+        total: if encounters.is_empty() { pity.total_encounters } else { encounters.len() as u64 },
+        caught: count(EncounterResult::Caught),
+        fled: count(EncounterResult::Fled),
+        fainted: count(EncounterResult::Fainted),
+        escaped: count(EncounterResult::Escaped),
+        skipped: count(EncounterResult::Skipped),
+        catch_rate: (finished > 0).then(|| count(EncounterResult::Caught) as f64 * 100.0 / finished as f64),
+    };
+
+    let mut catches_per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    let mut categories: BTreeMap<String, usize> = BTreeMap::new();
+    let mut types: BTreeMap<String, usize> = BTreeMap::new();
+    let mut balls: BTreeMap<String, BallStats> = BTreeMap::new();
+    let mut first_caught: HashMap<String, NaiveDate> = HashMap::new();
+    let mut rarest: Option<RareCatch> = None;
+    for p in &storage.pokemon {
+        let date = p.caught_at.date_naive();
+        *catches_per_day.entry(date).or_default() += 1;
+        balls.entry(p.ball_used.clone()).or_default().in_pc += 1;
+        let first = first_caught.entry(p.name.to_lowercase()).or_insert(date);
+        *first = (*first).min(date);
+
+        let Some(data) = pokemon_db.get(&key(&p.name)) else {
+            *categories.entry("unknown".to_string()).or_default() += 1;
+            continue;
+        };
+        *categories.entry(data.category.clone()).or_default() += 1;
+        for t in &data.form(p.form.as_deref()).types {
+            *types.entry(t.clone()).or_default() += 1;
+        }
+        let odds = true_odds(data, &pool) * 100.0;
+        if rarest.as_ref().is_none_or(|r| odds < r.true_odds_percent) {
+            rarest = Some(RareCatch {
+                name: form_name(&p.name, p.form.as_deref()),
+                caught_at: p.caught_at,
+                true_odds_percent: odds,
+            });
+        }
+    }
+
+    // Pokedex dates survive releases; the PC fills in older saves
+    let first_caught: Vec<NaiveDate> = pokedex
+        .entries
+        .values()
+        .filter(|e| e.caught)
+        .filter_map(|e| e.caught_at.map(|at| at.date_naive()).or_else(|| first_caught.get(&e.name).copied()))
+        .collect();
+
+    for record in &encounters {
+        for (ball, outcome) in &record.throws {
+            let stats = balls.entry(ball.clone()).or_default();
+            stats.thrown += 1;
+            if *outcome == Outcome::Caught {
+                stats.caught += 1;
+            }
+        }
+    }
+    for stats in balls.values_mut() {
+        stats.success_rate = (stats.thrown > 0).then(|| stats.caught as f64 * 100.0 / stats.thrown as f64);
+    }
+
+    let days: BTreeSet<NaiveDate> = catches_per_day.keys().copied().collect();
+    let catches = storage.pokemon.len();
+    let shinies = storage.pokemon.iter().filter(|p| p.shiny).count();
+    Dashboard {
+        generated_at: Local::now(),
+        catches,
+        shinies,
+        shiny_ratio: if catches > 0 { shinies as f64 / catches as f64 } else { 0.0 },
+        species_caught: pokedex.entries.values().filter(|e| e.caught).count(),
+        species_seen: seen_species.len(),
+        species_total,
+        encounters: encounter_totals,
+        longest_streak: longest_streak(&days),
+        current_streak: current_streak(&days, today),
+        rarest,
+        catches_per_day,
+        categories,
+        types,
+        dex_completion: dex_completion(&first_caught, today),
+        balls,
+        pity: PitySummary {
+            enabled: config.pity.enabled,
+            since_rare: pity.since_rare,
+            longest_drought: pity.longest_drought,
+            last_rare: pity.last_rare.clone(),
+            last_rare_at: pity.last_rare_at,
+            multiplier: pity.multiplier(&config.pity),
+        },
+    }
+}
+
+fn percent(value: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { value as f64 * 100.0 / total as f64 }
+}
+
+fn days(n: u32) -> String {
+    if n == 1 { "1 day".to_string() } else { format!("{} days", n) }
+}
+
+fn heading(title: &str) -> Vec<String> {
+    vec![title.cyan().bold().to_string(), "═".repeat(title.chars().count()).cyan().to_string(), String::new()]
+}
+
+fn overview_page(d: &Dashboard, config: &Config) -> Vec<String> {
+    let mut lines = heading("Trainer Stats");
+    lines.push(format!("Caught:        {}", format!("{} Pokemon, {} shiny", d.catches, d.shinies).yellow()));
+    if d.shinies > 0 {
+        lines.push(format!(
+            "Shiny ratio:   {} {}",
+            format!("1 in {:.0}", 1.0 / d.shiny_ratio).yellow(),
+            format!("({:.3}% of catches)", d.shiny_ratio * 100.0).dimmed()
+        ));
+    }
+    lines.push(format!(
+        "Pokedex:       {} {}",
+        format!("{} seen, {} caught of {}", d.species_seen, d.species_caught, d.species_total).yellow(),
+        format!("({:.1}%)", percent(d.species_caught, d.species_total)).dimmed()
+    ));
+    let e = &d.encounters;
+    match e.catch_rate {
+        Some(rate) => {
+            lines.push(format!(
+                "Encounters:    {} {}",
+                e.total.to_string().yellow(),
+                format!("({} caught, {} fled, {} fainted, {} escaped, {} skipped)", e.caught, e.fled, e.fainted, e.escaped, e.skipped).dimmed()
+            ));
+            lines.push(format!("Catch rate:    {}", format!("{:.1}% of encounters", rate).yellow()));
+        }
+        None => lines.push(format!("Encounters:    {}", e.total.to_string().yellow())),
+    }
+    match &d.rarest {
+        Some(r) => lines.push(format!(
+            "Rarest catch:  {} {} {}",
+            r.name.green().bold(),
+            format!("{:.4}% true odds", r.true_odds_percent).yellow(),
+            r.caught_at.format("(%Y-%m-%d)").to_string().dimmed()
+        )),
+        None => lines.push(format!("Rarest catch:  {}", "none yet".dimmed())),
+    }
+    let streak = &d.longest_streak;
+    let span = match (streak.start, streak.end) {
+        (Some(start), Some(end)) if start != end => format!(" ({} – {})", start.format("%Y-%m-%d"), end.format("%Y-%m-%d")),
+        (Some(day), _) => format!(" ({})", day.format("%Y-%m-%d")),
+        _ => String::new(),
+    };
+    lines.push(format!("Longest streak: {}{}", days(streak.days).yellow(), span.dimmed()));
+    lines.push(format!("Current streak: {}", days(d.current_streak).yellow()));
+
+    lines.push(String::new());
+    lines.extend(heading("Rarity Insurance"));
+    let p = &d.pity;
+    lines.push(format!("Since a rare:  {}", format!("{} encounters", p.since_rare).yellow().bold()));
+    match (&p.last_rare, p.last_rare_at) {
+        (Some(name), Some(at)) => lines.push(format!(
+            "Last rare:     {} {}",
+            name.green().bold(),
+            at.format("(%Y-%m-%d %H:%M)").to_string().dimmed()
+        )),
+        _ => lines.push(format!("Last rare:     {}", "none yet".dimmed())),
+    }
+    lines.push(format!("Longest gap:   {}", format!("{} encounters", p.longest_drought).yellow()));
+    let pity = &config.pity;
+    let status = if !pity.enabled {
+        format!("{}", "off (catch-pokemon stats --pity on)".dimmed())
+    } else if p.multiplier > 1.0 {
+        format!("{}", format!("ACTIVE, rare Pokemon {:.1}x as likely", p.multiplier).green().bold())
+    } else {
+        format!(
+            "{}",
+            format!("on, ramps after {} more encounters", pity.threshold.saturating_sub(p.since_rare)).green()
+        )
+    };
+    lines.push(format!("Pity:          {}", status));
+    if pity.enabled {
+        lines.push(format!(
+            "Settings:      {}",
+            format!("after {} encounters, +{}x per encounter", pity.threshold, pity.ramp).dimmed()
+        ));
+    }
+    lines
+}
+
+/// Catches per day as a calendar, one column per week, sized to `width`
+fn heatmap(d: &Dashboard, width: usize) -> Vec<String> {
+    let today = d.generated_at.date_naive();
+    let weeks = (width.saturating_sub(6) / 2).clamp(4, 53);
+    let this_monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let start = this_monday - Days::new(7 * (weeks as u64 - 1));
+    let max = d.catches_per_day.range(start..).map(|(_, n)| *n).max().unwrap_or(0);
+
+    // Month names above the week they start in, when there's room
+    let mut months = vec![' '; 4 + weeks * 2];
+    let mut free_from = 0;
+    for week in 0..weeks {
+        let monday = start + Days::new(7 * week as u64);
+        let pos = 4 + week * 2;
+        if (week == 0 || monday.day() <= 7) && pos >= free_from && pos + 3 <= months.len() {
+            for (i, c) in monday.format("%b").to_string().chars().enumerate() {
+                months[pos + i] = c;
+            }
+            free_from = pos + 4;
+        }
+    }
+    let months: String = months.into_iter().collect();
+    let mut lines = vec![months.dimmed().to_string()];
+
+    for weekday in 0..7u64 {
+        let label = match weekday {
+            0 => "Mon ",
+            2 => "Wed ",
+            4 => "Fri ",
+            _ => "    ",
+        };
+        let mut row = label.dimmed().to_string();
+        for week in 0..weeks {
+            let date = start + Days::new(7 * week as u64 + weekday);
+            if date > today {
+                row.push_str("  ");
+                continue;
+            }
+            let n = d.catches_per_day.get(&date).copied().unwrap_or(0);
+            let cell = match (n * 4).div_ceil(max.max(1)) {
+                0 => "·".dimmed(),
+                1 => "░".green(),
+                2 => "▒".green(),
+                3 => "▓".green(),
+                _ => "█".green().bold(),
+            };
+            row.push_str(&format!("{} ", cell));
+        }
+        lines.push(row);
+    }
+    let shown: usize = d.catches_per_day.range(start..).map(|(_, n)| n).sum();
+    lines.push(format!(
+        "    {} {} {} {} {} {} {}",
+        "Less".dimmed(),
+        "·".dimmed(),
+        "░".green(),
+        "▒".green(),
+        "▓".green(),
+        "█".green().bold(),
+        "More".dimmed()
+    ));
+    lines.push(String::new());
+    lines.push(format!("{} catches in the last {} weeks", shown.to_string().yellow(), weeks));
+    if let Some((day, n)) = d.catches_per_day.iter().max_by_key(|(day, n)| (**n, std::cmp::Reverse(**day))) {
+        lines.push(format!("Busiest day:   {} {}", day.format("%Y-%m-%d").to_string().yellow(), format!("({} catches)", n).dimmed()));
+    }
+    lines
+}
+
+fn activity_page(d: &Dashboard, width: usize) -> Vec<String> {
+    let mut lines = heading("Catches per Day");
+    lines.extend(heatmap(d, width));
+    lines.push(String::new());
+
+    lines.extend(heading("Pokedex Completion"));
+    if d.dex_completion.is_empty() {
+        lines.push("Nothing caught yet.".dimmed().to_string());
+    }
+    let skip = d.dex_completion.len().saturating_sub(COMPLETION_MONTHS);
+    for (month, caught) in d.dex_completion.iter().skip(skip) {
+        lines.push(format!(
+            "{}  {} {} {}",
+            month.dimmed(),
+            progress_bar(*caught as u64, d.species_total as u64, BAR_WIDTH).green(),
+            format!("{:>4}/{}", caught, d.species_total).yellow(),
+            format!("({:.1}%)", percent(*caught, d.species_total)).dimmed()
+        ));
+    }
+    lines
+}
+
+fn collection_page(d: &Dashboard) -> Vec<String> {
+    let mut lines = heading("Categories");
+    let mut categories: Vec<(&String, &usize)> = d.categories.iter().collect();
+    categories.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let max = categories.first().map(|(_, n)| **n).unwrap_or(0);
+    for (category, n) in &categories {
+        lines.push(format!(
+            "{:<18} {} {:>5} {}",
+            category.replace('_', " "),
+            progress_bar(**n as u64, max as u64, BAR_WIDTH).cyan(),
+            n,
+            format!("{:.1}%", percent(**n, d.catches)).dimmed()
+        ));
+    }
+    if categories.is_empty() {
+        lines.push("Nothing caught yet.".dimmed().to_string());
+    }
+    lines.push(String::new());
+
+    lines.extend(heading("Types"));
+    let mut types: Vec<(&String, &usize)> = d.types.iter().collect();
+    types.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let max = types.first().map(|(_, n)| **n).unwrap_or(0);
+    for (type_name, n) in &types {
+        // Pad outside the colour so the escape codes don't count as width
+        lines.push(format!(
+            "{}{} {} {:>5} {}",
+            color_type(type_name),
+            " ".repeat(18usize.saturating_sub(type_name.len())),
+            progress_bar(**n as u64, max as u64, BAR_WIDTH).cyan(),
+            n,
+            format!("{:.1}%", percent(**n, d.catches)).dimmed()
+        ));
+    }
+    lines.push(String::new());
+    lines.push("Dual-type Pokemon count toward both types.".dimmed().to_string());
+    lines
+}
+
+fn balls_page(d: &Dashboard) -> Vec<String> {
+    let mut lines = heading("Poké Balls");
+    lines.push(format!("{:<14} {:>7} {:>7} {:>8} {:>7}", "Ball", "Thrown", "Caught", "Success", "In PC").dimmed().to_string());
+    for (ball, stats) in &d.balls {
+        let success = match stats.success_rate {
+            Some(rate) => format!("{:>7.1}%", rate).yellow().to_string(),
+            None => format!("{:>8}", "–").dimmed().to_string(),
+        };
+        lines.push(format!("{:<14} {:>7} {:>7} {} {:>7}", ball, stats.thrown, stats.caught, success, stats.in_pc));
+    }
+    if d.balls.is_empty() {
+        lines.push("No balls thrown yet.".dimmed().to_string());
+    }
+    lines.push(String::new());
+    lines.push("Throws come from the encounter log ('catch-pokemon log'); hatched".dimmed().to_string());
+    lines.push("Pokemon show up under Egg.".dimmed().to_string());
+    lines
+}
+
+fn pages(d: &Dashboard, config: &Config, width: usize) -> Vec<(&'static str, Vec<String>)> {
+    vec![
+        ("Overview", overview_page(d, config)),
+        ("Activity", activity_page(d, width)),
+        ("Collection", collection_page(d)),
+        ("Balls", balls_page(d)),
+    ]
+}
+
+/// Show the stats dashboard, optionally changing the pity settings first.
+/// Prints plain text when piped or after changing settings, and JSON with
+/// `json` for exporting.
+pub fn stats_command(pity: Option<String>, pity_after: Option<u32>, pity_ramp: Option<f32>, json: bool) {
+    let mut config = Config::load();
+    let mut changed = false;
+
+    if let Some(toggle) = pity {
+        config.pity.enabled = match toggle.as_str() {
+            "on" => true,
+            "off" => false,
+            _ => {
+                eprintln!("{}", "Pity must be 'on' or 'off'.".red());
+                return;
+            }
+        };
+        changed = true;
+    }
+
+    if let Some(n) = pity_after {
+        if n == 0 {
+            eprintln!("{}", "The pity threshold must be at least 1 encounter.".red());
+            return;
+        }
+        config.pity.threshold = n;
+        changed = true;
+    }
+
+    if let Some(ramp) = pity_ramp {
+        if !(ramp > 0.0 && ramp <= MAX_PITY_RAMP) {
+            eprintln!("{}", format!("The pity ramp must be between 0 (exclusive) and {}.", MAX_PITY_RAMP).red());
+            return;
+        }
+        config.pity.ramp = ramp;
+        changed = true;
+    }
+
+    if changed {
+        if let Err(e) = config.save() {
+            eprintln!("{}", format!("Error saving config: {}", e).red());
+            return;
+        }
+        println!("{}", "Pity settings updated.".green());
+        println!();
+    }
+
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let dashboard = build_dashboard(&pokemon_db, &config);
+
+    if json {
+        match serde_json::to_string_pretty(&dashboard) {
+            Ok(out) => println!("{}", out),
+            Err(e) => eprintln!("{}", format!("Error encoding stats: {}", e).red()),
+        }
+        return;
+    }
+
+    if changed || !stdout().is_terminal() {
+        let width = terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+        for (_, lines) in pages(&dashboard, &config, width) {
+            for line in lines {
+                println!("  {}", line);
+            }
+            println!();
+        }
+        return;
+    }
+
+    if let Err(e) = run_tui(&dashboard, &config) {
+        eprintln!("TUI error: {}", e);
+    }
+}
+
+fn run_tui(dashboard: &Dashboard, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    stdout().execute(terminal::EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    stdout().execute(cursor::Hide)?;
+
+    let result = tui_loop(dashboard, config);
+
+    stdout().execute(cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    stdout().execute(terminal::LeaveAlternateScreen)?;
+    result
+}
+
+fn tui_loop(dashboard: &Dashboard, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mut page = 0usize;
+    let mut scroll = 0usize;
+
+    loop {
+        let (tw, th) = terminal::size()?;
+        let tw = tw as usize;
+        // Tab bar, separator, separator, footer
+        let body_height = (th as usize).saturating_sub(4);
+        let pages = pages(dashboard, config, tw.saturating_sub(2));
+        let lines = &pages[page].1;
+        scroll = scroll.min(lines.len().saturating_sub(body_height));
+
+        stdout().execute(cursor::MoveTo(0, 0))?;
+        let tabs: Vec<String> = pages
+            .iter()
+            .enumerate()
+            .map(|(i, (title, _))| {
+                let tab = format!("{} {}", i + 1, title);
+                if i == page { tab.cyan().bold().reversed().to_string() } else { tab.dimmed().to_string() }
+            })
+            .collect();
+        print!(" {}\x1B[K\r\n", tabs.join("  "));
+        print!(" {}\x1B[K\r\n", "─".repeat(tw.saturating_sub(2)).dimmed());
+        for i in 0..body_height {
+            match lines.get(scroll + i) {
+                Some(line) => print!("  {}\x1B[K\r\n", line),
+                None => print!("\x1B[K\r\n"),
+            }
+        }
+        print!(" {}\x1B[K\r\n", "─".repeat(tw.saturating_sub(2)).dimmed());
+        print!(" {}\x1B[K", "←→/Tab: Page | 1-4: Jump | ↑↓ Scroll | Q: Quit".dimmed());
+        stdout().flush()?;
+
+        if let Ok(Event::Key(KeyEvent { code, modifiers, .. })) = event::read() {
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                    page = (page + 1) % pages.len();
+                    scroll = 0;
+                }
+                KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                    page = (page + pages.len() - 1) % pages.len();
+                    scroll = 0;
+                }
+                KeyCode::Char(c @ '1'..='4') => {
+                    page = c as usize - '1' as usize;
+                    scroll = 0;
+                }
+                KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => scroll += 1,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(list: &[&str]) -> BTreeSet<NaiveDate> {
+        list.iter().map(|s| date(s)).collect()
+    }
+
+    #[test]
+    fn longest_streak_finds_the_longest_run() {
+        let days = dates(&["2026-01-01", "2026-01-02", "2026-01-05", "2026-01-06", "2026-01-07", "2026-01-09"]);
+        let streak = longest_streak(&days);
+        assert_eq!(streak.days, 3);
+        assert_eq!(streak.start, Some(date("2026-01-05")));
+        assert_eq!(streak.end, Some(date("2026-01-07")));
+        assert_eq!(longest_streak(&BTreeSet::new()).days, 0);
+    }
+
+    #[test]
+    fn longest_streak_crosses_month_and_year_ends() {
+        let days = dates(&["2025-12-30", "2025-12-31", "2026-01-01", "2026-02-28", "2026-03-01"]);
+        assert_eq!(longest_streak(&days).days, 3);
+    }
+
+    #[test]
+    fn current_streak_counts_back_from_today_or_yesterday() {
+        let days = dates(&["2026-10-15", "2026-10-16", "2026-10-17"]);
+        assert_eq!(current_streak(&days, date("2026-10-17")), 3);
+        // Nothing caught yet today keeps yesterday's streak alive
+        assert_eq!(current_streak(&days, date("2026-10-18")), 3);
+        assert_eq!(current_streak(&days, date("2026-10-19")), 0);
+    }
+
+    #[test]
+    fn dex_completion_is_a_running_total_per_month() {
+        let first = [date("2026-08-20"), date("2026-08-21"), date("2026-10-02")];
+        let completion = dex_completion(&first, date("2026-10-18"));
+        let expected: Vec<(&str, usize)> = vec![("2026-08", 2), ("2026-09", 2), ("2026-10", 3)];
+        assert_eq!(completion.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(), expected);
+        assert!(dex_completion(&[], date("2026-10-18")).is_empty());
+    }
+}
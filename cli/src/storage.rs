@@ -1,33 +1,206 @@
 use colored::*;
 use hmac::Mac as HmacMac;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{stdout, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::crypto::{derive_signing_key, verify_chain, HmacSha256};
+use crate::crypto::{decrypt_state, derive_signing_key, encrypt_state, sign_state, verify_chain, HmacSha256};
 use crate::models::PcStorage;
 
-pub fn get_storage_path() -> PathBuf {
+/// Directory holding all game state. Seeded development runs get their own
+/// profile so they never touch the real PC.
+pub fn get_data_dir() -> PathBuf {
     let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
     path.push("catch-pokemon");
+    if crate::rng::is_seeded() {
+        path.push("seeded");
+    }
+    path
+}
+
+pub fn get_storage_path() -> PathBuf {
+    let mut path = get_data_dir();
     path.push("pc_storage.json");
     path
 }
 
 pub fn get_pokedex_path() -> PathBuf {
-    let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
-    path.push("catch-pokemon");
+    let mut path = get_data_dir();
     path.push("pokedex.json");
     path
 }
 
 pub fn get_team_path() -> PathBuf {
-    let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
-    path.push("catch-pokemon");
+    let mut path = get_data_dir();
     path.push("battle_team.json");
     path
 }
 
+pub fn get_config_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("config.toml");
+    path
+}
+
+pub fn get_schedule_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("encounter_schedule.json");
+    path
+}
+
+pub fn get_wild_battle_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("wild_battle.json");
+    path
+}
+
+pub fn get_roaming_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("roaming.json");
+    path
+}
+
+pub fn get_hunt_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("hunt.json");
+    path
+}
+
+pub fn get_bag_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("bag.json");
+    path
+}
+
+pub fn get_pity_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("pity.json");
+    path
+}
+
+pub fn get_incubator_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("incubator.json");
+    path
+}
+
+pub fn get_candy_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("candy.json");
+    path
+}
+
+pub fn get_battle_history_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("battle_history.json");
+    path
+}
+
+pub fn get_achievements_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("achievements.json");
+    path
+}
+
+pub fn get_encounter_log_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("encounter_log.jsonl");
+    path
+}
+
+pub fn get_encounter_log_head_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("encounter_log_head.json");
+    path
+}
+
+pub fn get_research_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("research.json");
+    path
+}
+
+pub fn get_annotations_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("annotations.json");
+    path
+}
+
+pub fn get_prompt_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("prompt.json");
+    path
+}
+
+pub fn get_training_path() -> PathBuf {
+    let mut path = get_data_dir();
+    path.push("training.json");
+    path
+}
+
+#[derive(Serialize, Deserialize)]
+struct Signed<T> {
+    data: T,
+    signature: String,
+}
+
+/// Load a signed state file. Returns None if it doesn't exist or was edited
+/// by hand (with a warning naming `label`).
+pub fn load_signed<T: Serialize + DeserializeOwned>(path: &Path, label: &str) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    let verified = serde_json::from_str::<Signed<T>>(&contents).ok().filter(|signed| {
+        serde_json::to_string(&signed.data).is_ok_and(|json| sign_state(&json) == signed.signature)
+    });
+    if verified.is_none() {
+        eprintln!("{}", format!("{} failed its integrity check and was ignored.", label).yellow());
+    }
+    verified.map(|signed| signed.data)
+}
+
+/// Write a state file as `{"data": ..., "signature": ...}`. Game state that
+/// isn't encrypted like the PC is stored this way, so counts, levels and
+/// rewards can't be edited by hand: load_signed ignores a tampered file.
+pub fn save_signed<T: Serialize>(path: &Path, data: &T) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let signed = Signed { data, signature: sign_state(&serde_json::to_string(data)?) };
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&signed)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Load an encrypted state file. Returns None if it doesn't exist or can't be
+/// decrypted. An undecryptable file is backed up rather than wiped, with a
+/// warning naming `label`.
+pub fn load_encrypted<T: DeserializeOwned>(path: &Path, label: &str) -> Option<T> {
+    let data = fs::read(path).ok()?;
+    let state = decrypt_state(&data);
+    if state.is_none() {
+        let backup = path.with_extension("json.bak");
+        if !backup.exists() {
+            let _ = fs::copy(path, &backup);
+        }
+        eprintln!("{}", format!("Could not decrypt your {}. Backed up.", label).red());
+    }
+    state
+}
+
+/// Write a state file encrypted like the PC, for state that shouldn't even be
+/// readable by hand
+pub fn save_encrypted<T: Serialize>(path: &Path, data: &T) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, encrypt_state(data)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
 pub fn restore_pc(file: Option<String>) {
     let backup_path = match file {
         Some(f) => PathBuf::from(f),
@@ -164,9 +337,27 @@ pub fn clear_pc() {
             "pc_backup.json",
             "battle_team.json",
             "battle_team.json.bak",
+            "training.json",
+            "encounter_log.jsonl",
+            "encounter_log_head.json",
+            "annotations.json",
             "pokedex.json",
             "pokedex.json.bak",
             "pokedex_backup.json",
+            "bag.json",
+            "bag.json.bak",
+            "incubator.json",
+            "incubator.json.bak",
+            "candy.json",
+            "hunt.json",
+            "pity.json",
+            "roaming.json",
+            "wild_battle.json",
+            "battle_history.json",
+            "achievements.json",
+            "research.json",
+            "encounter_schedule.json",
+            "prompt.json",
         ];
 
         let mut cleared = false;
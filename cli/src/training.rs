@@ -0,0 +1,275 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use crate::buddy::refresh_prompt;
+use crate::models::{BattleTeam, Ivs, PcStorage};
+use crate::storage::{get_training_path, load_signed, save_signed};
+
+pub const MAX_LEVEL: u32 = 100;
+/// XP for every team member on the first terminal of the day
+const DAILY_XP: u64 = 100;
+/// XP for each of the six Pokemon that won a battle round
+pub const ROUND_XP: u64 = 200;
+/// Extra XP for every Pokemon that fought in a battle you won
+pub const WIN_XP: u64 = 500;
+
+pub const MAX_FRIENDSHIP: u8 = 255;
+/// Friendship a Pokemon starts with, as in the main games
+const BASE_FRIENDSHIP: u8 = 70;
+/// Friendship the buddy gains on the first terminal of the day
+const BUDDY_DAILY_FRIENDSHIP: u8 = 10;
+/// Friendship the buddy gains on every encounter you walk into together
+const BUDDY_WALK_FRIENDSHIP: u8 = 1;
+
+/// XP needed to reach a level. Level 10 takes 1,000 XP, level 100 takes 100,000.
+pub fn xp_for_level(level: u32) -> u64 {
+    10 * (level as u64).pow(2)
+}
+
+pub fn level_for_xp(xp: u64) -> u32 {
+    (((xp / 10) as f64).sqrt() as u32).clamp(1, MAX_LEVEL)
+}
+
+/// Battle power after training: 1% more per level, plus up to 15 for
+/// perfect individual stats
+pub fn effective_power(power_rank: u8, level: u32, ivs: Option<Ivs>) -> u32 {
+    let stats = ivs.map(|i| (i.attack + i.defense + i.stamina) as u32).unwrap_or(0);
+    power_rank as u32 * (100 + level) / 100 + stats / 3
+}
+
+/// Best individual stats among the PC's Pokemon of a species, matching the
+/// shiny one if the team member is shiny
+pub fn best_ivs(storage: &PcStorage, name: &str, shiny: bool) -> Option<Ivs> {
+    let key = member_key(name);
+    storage
+        .pokemon
+        .iter()
+        .filter(|p| member_key(&p.name) == key && (p.shiny || !shiny))
+        .filter_map(|p| p.ivs)
+        .max_by_key(|ivs| ivs.percent())
+}
+
+/// Team members are keyed by species in pokemon.json format, e.g. "mr_mime"
+fn member_key(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Training {
+    pub xp: u64,
+    pub friendship: u8,
+}
+
+impl Default for Training {
+    fn default() -> Self {
+        Training { xp: 0, friendship: BASE_FRIENDSHIP }
+    }
+}
+
+impl Training {
+    pub fn level(&self) -> u32 {
+        level_for_xp(self.xp)
+    }
+
+    /// XP still needed for the next level, or None at the level cap
+    pub fn xp_to_next(&self) -> Option<u64> {
+        let level = self.level();
+        (level < MAX_LEVEL).then(|| xp_for_level(level + 1) - self.xp)
+    }
+
+    /// Friendship as 0–5 hearts
+    pub fn hearts(&self) -> String {
+        let full = (self.friendship as usize * 5) / MAX_FRIENDSHIP as usize;
+        format!("{}{}", "♥".repeat(full), "♡".repeat(5 - full))
+    }
+}
+
+/// XP and friendship of battle-team Pokemon, plus the buddy. Progress is
+/// kept when a Pokemon leaves the team and picks up again if it comes back.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TeamTraining {
+    #[serde(default)]
+    pub members: BTreeMap<String, Training>,
+    /// Team member whose friendship grows as you play
+    #[serde(default)]
+    pub buddy: Option<String>,
+    /// Day the daily XP was last handed out
+    #[serde(default)]
+    pub last_daily: Option<NaiveDate>,
+}
+
+impl TeamTraining {
+    pub fn load() -> Self {
+        load_signed(&get_training_path(), "Team training").unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_training_path(), self)
+    }
+
+    pub fn get(&self, name: &str) -> Training {
+        self.members.get(&member_key(name)).copied().unwrap_or_default()
+    }
+
+    pub fn is_buddy(&self, name: &str) -> bool {
+        self.buddy.as_deref() == Some(member_key(name).as_str())
+    }
+
+    pub fn set_buddy(&mut self, name: Option<&str>) {
+        self.buddy = name.map(member_key);
+    }
+
+    /// Add XP to one Pokemon, returning its new level if it went up
+    pub fn gain_xp(&mut self, name: &str, xp: u64) -> Option<u32> {
+        let training = self.members.entry(member_key(name)).or_default();
+        let before = training.level();
+        training.xp += xp;
+        let after = training.level();
+        (after > before).then_some(after)
+    }
+
+    /// Add XP to team members, once per species even if a name repeats.
+    /// Returns each one that levelled up with its new level.
+    pub fn gain_team_xp(&mut self, names: &[String], xp: u64) -> Vec<(String, u32)> {
+        let mut rewarded = BTreeSet::new();
+        names
+            .iter()
+            .filter(|name| rewarded.insert(member_key(name)))
+            .filter_map(|name| self.gain_xp(name, xp).map(|level| (name.clone(), level)))
+            .collect()
+    }
+
+    fn befriend_buddy(&mut self, amount: u8) {
+        if let Some(buddy) = self.buddy.clone() {
+            let training = self.members.entry(buddy).or_default();
+            training.friendship = training.friendship.saturating_add(amount);
+        }
+    }
+
+    /// Progress follows a Pokemon when it evolves. If the new species already
+    /// has progress, the further trained of the two is kept.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        let (from, to) = (member_key(from), member_key(to));
+        if let Some(training) = self.members.remove(&from) {
+            let kept = self.members.entry(to.clone()).or_insert(training);
+            if training.xp > kept.xp {
+                *kept = training;
+            }
+        }
+        if self.buddy.as_deref() == Some(from.as_str()) {
+            self.buddy = Some(to);
+        }
+    }
+}
+
+/// Give XP to team members. Returns each one that levelled up with its new level.
+pub fn reward(names: &[String], xp: u64) -> Vec<(String, u32)> {
+    let mut training = TeamTraining::load();
+    let level_ups = training.gain_team_xp(names, xp);
+    let _ = training.save();
+    level_ups
+}
+
+/// Hand out the daily XP on the first terminal of the day. Cheap on every
+/// other terminal: the date is read without checking the signature, and an
+/// edited date can only skip a day's XP, never add one.
+pub fn daily_session() {
+    let path = get_training_path();
+    let today = Local::now().date_naive();
+    if let Ok(contents) = fs::read_to_string(&path) {
+        let last = serde_json::from_str::<serde_json::Value>(&contents)
+            .ok()
+            .and_then(|v| v["data"]["last_daily"].as_str().and_then(|d| d.parse::<NaiveDate>().ok()));
+        if last == Some(today) {
+            return;
+        }
+    }
+
+    let team = BattleTeam::load();
+    let mut training = TeamTraining::load();
+    let names: Vec<String> = team.pokemon.iter().map(|p| p.name.clone()).collect();
+    training.gain_team_xp(&names, DAILY_XP);
+    training.befriend_buddy(BUDDY_DAILY_FRIENDSHIP);
+    training.last_daily = Some(today);
+    let _ = training.save();
+    if training.buddy.is_some() {
+        refresh_prompt();
+    }
+}
+
+/// The buddy comes along for every encounter
+pub fn walk_buddy() {
+    if !get_training_path().exists() {
+        return;
+    }
+    let mut training = TeamTraining::load();
+    if training.buddy.is_none() {
+        return;
+    }
+    training.befriend_buddy(BUDDY_WALK_FRIENDSHIP);
+    let _ = training.save();
+    refresh_prompt();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xp_curve_matches_the_documented_levels() {
+        assert_eq!(xp_for_level(10), 1_000);
+        assert_eq!(xp_for_level(MAX_LEVEL), 100_000);
+        assert_eq!(level_for_xp(0), 1);
+        assert_eq!(level_for_xp(u64::MAX), MAX_LEVEL);
+    }
+
+    #[test]
+    fn levels_start_exactly_at_their_xp() {
+        for level in 2..=MAX_LEVEL {
+            assert_eq!(level_for_xp(xp_for_level(level)), level);
+            assert_eq!(level_for_xp(xp_for_level(level) - 1), level - 1);
+        }
+    }
+
+    #[test]
+    fn xp_to_next_stops_at_the_cap() {
+        let training = Training { xp: 1_000, ..Default::default() };
+        assert_eq!(training.xp_to_next(), Some(210));
+        let maxed = Training { xp: xp_for_level(MAX_LEVEL), ..Default::default() };
+        assert_eq!(maxed.xp_to_next(), None);
+    }
+
+    #[test]
+    fn effective_power_adds_levels_and_stats() {
+        let perfect = Ivs { attack: Ivs::MAX, defense: Ivs::MAX, stamina: Ivs::MAX };
+        let zero = Ivs { attack: 0, defense: 0, stamina: 0 };
+        assert_eq!(effective_power(100, 0, None), 100);
+        assert_eq!(effective_power(100, MAX_LEVEL, None), 200);
+        assert_eq!(effective_power(40, 50, Some(zero)), 60);
+        assert_eq!(effective_power(40, 0, Some(perfect)), 55);
+    }
+
+    #[test]
+    fn a_species_gains_xp_once_per_reward() {
+        let mut training = TeamTraining::default();
+        let names = vec!["pikachu".to_string(), "Pikachu".to_string(), "mr-mime".to_string()];
+        let level_ups = training.gain_team_xp(&names, 1_000);
+        assert_eq!(level_ups, [("pikachu".to_string(), 10), ("mr-mime".to_string(), 10)]);
+        assert_eq!(training.get("pikachu").xp, 1_000);
+        assert_eq!(training.get("mr_mime").xp, 1_000);
+    }
+
+    #[test]
+    fn evolving_keeps_the_further_trained_progress() {
+        let mut training = TeamTraining::default();
+        training.gain_xp("pichu", 5_000);
+        training.gain_xp("pikachu", 2_000);
+        training.set_buddy(Some("pichu"));
+        training.rename("pichu", "pikachu");
+        assert_eq!(training.get("pikachu").xp, 5_000);
+        assert!(!training.members.contains_key("pichu"));
+        assert!(training.is_buddy("pikachu"));
+    }
+}
@@ -0,0 +1,311 @@
+use colored::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::buddy::set_wild;
+use crate::catch::{
+    capture_chance, flee_chance, get_flee_rate, get_pokemon_catch_rate, verify_encounter_token,
+    Encounter, ThrowConditions,
+};
+use crate::display::color_type;
+use crate::encounter_log::{self, encounter_id, FightEnd, LogEvent};
+use crate::models::{form_name, BattleTeam, Pokedex, PokemonData, StatusCondition, POKEMON_DATA};
+use crate::roaming::RoamingState;
+use crate::storage::{get_wild_battle_path, load_signed, save_signed};
+
+// Share of the wild Pokemon's HP an even, neutral attack takes
+const BASE_DAMAGE: f32 = 0.25;
+
+/// What the team Pokemon does on its turn
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum FightMove {
+    /// Damage the wild Pokemon (can knock it out)
+    Attack,
+    /// Try to put it to sleep
+    Sleep,
+    /// Try to paralyze it
+    Paralyze,
+}
+
+/// HP and status of the current wild Pokemon, left behind for the next
+/// throw. Tied to the encounter token.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WildBattle {
+    pub token: String,
+    /// Remaining HP as a fraction of max HP
+    pub hp: f32,
+    #[serde(default)]
+    pub status: StatusCondition,
+    /// Turns fought so far; each raises the flee chance like a throw
+    pub turns: u32,
+    #[serde(default)]
+    pub fainted: bool,
+}
+
+impl WildBattle {
+    /// Battle state for this encounter, if the player has fought it
+    pub fn for_token(token: Option<&str>) -> Option<Self> {
+        let token = token?;
+        load_signed::<WildBattle>(&get_wild_battle_path(), "Wild battle").filter(|b| b.token == token)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        save_signed(&get_wild_battle_path(), self)
+    }
+
+    /// Forget the battle once the encounter is over
+    pub fn clear() {
+        let _ = fs::remove_file(get_wild_battle_path());
+        set_wild(None);
+    }
+
+    pub fn throw_conditions(&self) -> ThrowConditions {
+        ThrowConditions { hp_fraction: self.hp, status: self.status, ..Default::default() }
+    }
+}
+
+fn lookup<'a>(db: &'a HashMap<String, PokemonData>, name: &str) -> Option<&'a PokemonData> {
+    let normalized = name
+        .to_lowercase()
+        .replace("'", "")
+        .replace(".", "")
+        .replace(" ", "_")
+        .replace("-", "_");
+    db.get(&normalized)
+}
+
+/// Damage multiplier of one attacking type against one defending type
+fn type_multiplier(attack: &str, defend: &str) -> f32 {
+    let (strong, weak, immune): (&[&str], &[&str], &[&str]) = match attack {
+        "normal" => (&[], &["rock", "steel"], &["ghost"]),
+        "fire" => (&["grass", "ice", "bug", "steel"], &["fire", "water", "rock", "dragon"], &[]),
+        "water" => (&["fire", "ground", "rock"], &["water", "grass", "dragon"], &[]),
+        "electric" => (&["water", "flying"], &["electric", "grass", "dragon"], &["ground"]),
+        "grass" => (
+            &["water", "ground", "rock"],
+            &["fire", "grass", "poison", "flying", "bug", "dragon", "steel"],
+            &[],
+        ),
+        "ice" => (&["grass", "ground", "flying", "dragon"], &["fire", "water", "ice", "steel"], &[]),
+        "fighting" => (
+            &["normal", "ice", "rock", "dark", "steel"],
+            &["poison", "flying", "psychic", "bug", "fairy"],
+            &["ghost"],
+        ),
+        "poison" => (&["grass", "fairy"], &["poison", "ground", "rock", "ghost"], &["steel"]),
+        "ground" => (&["fire", "electric", "poison", "rock", "steel"], &["grass", "bug"], &["flying"]),
+        "flying" => (&["grass", "fighting", "bug"], &["electric", "rock", "steel"], &[]),
+        "psychic" => (&["fighting", "poison"], &["psychic", "steel"], &["dark"]),
+        "bug" => (
+            &["grass", "psychic", "dark"],
+            &["fire", "fighting", "poison", "flying", "ghost", "steel", "fairy"],
+            &[],
+        ),
+        "rock" => (&["fire", "ice", "flying", "bug"], &["fighting", "ground", "steel"], &[]),
+        "ghost" => (&["psychic", "ghost"], &["dark"], &["normal"]),
+        "dragon" => (&["dragon"], &["steel"], &["fairy"]),
+        "dark" => (&["psychic", "ghost"], &["fighting", "dark", "fairy"], &[]),
+        "steel" => (&["ice", "rock", "fairy"], &["fire", "water", "electric", "steel"], &[]),
+        "fairy" => (&["fighting", "dragon", "dark"], &["fire", "poison", "steel"], &[]),
+        _ => (&[], &[], &[]),
+    };
+
+    if immune.contains(&defend) {
+        0.0
+    } else if strong.contains(&defend) {
+        2.0
+    } else if weak.contains(&defend) {
+        0.5
+    } else {
+        1.0
+    }
+}
+
+/// The attacker's most effective type against the defender and its multiplier
+fn best_attack<'a>(attacker: &'a [String], defender: &[String]) -> (&'a str, f32) {
+    attacker
+        .iter()
+        .map(|t| (t.as_str(), defender.iter().map(|d| type_multiplier(t, d)).product::<f32>()))
+        .fold(("normal", -1.0), |best, next| if next.1 > best.1 { next } else { best })
+}
+
+fn hp_bar(hp: f32) -> String {
+    let width = 20;
+    let filled = (hp.clamp(0.0, 1.0) * width as f32).ceil() as usize;
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(width - filled));
+    if hp > 0.5 {
+        bar.green().to_string()
+    } else if hp > 0.2 {
+        bar.yellow().to_string()
+    } else {
+        bar.red().to_string()
+    }
+}
+
+/// One turn of the pre-catch battle: a team Pokemon attacks or inflicts a
+/// status, then the wild Pokemon may flee.
+pub fn fight_command(encounter: Encounter, with: Option<String>, action: FightMove, rng: &mut impl Rng) {
+    let Encounter { pokemon, form, token, attempt, .. } = encounter;
+
+    if !verify_encounter_token(&pokemon, form.as_deref(), token.as_deref()) {
+        return;
+    }
+    let token = token.unwrap_or_default();
+
+    let pokemon_db: HashMap<String, PokemonData> = match serde_json::from_str(POKEMON_DATA) {
+        Ok(data) => data,
+        Err(_) => {
+            eprintln!("{}", "Error: Could not load Pokemon database.".red());
+            return;
+        }
+    };
+    let full_name = form_name(&pokemon, form.as_deref());
+    let wild = match lookup(&pokemon_db, &pokemon) {
+        Some(data) => data.form(form.as_deref()),
+        None => {
+            eprintln!("{}", format!("Unknown Pokemon: {}", pokemon).red());
+            return;
+        }
+    };
+
+    // Pick the fighter from the battle team (the lead by default)
+    let team = BattleTeam::load();
+    if team.pokemon.is_empty() {
+        println!("{}", "Your battle team is empty! Add Pokemon from the PC with T.".red());
+        return;
+    }
+    let fighter = match &with {
+        Some(name) => match team.pokemon.iter().find(|p| p.name.eq_ignore_ascii_case(name)) {
+            Some(p) => p,
+            None => {
+                println!("{}", format!("{} is not on your battle team.", name).red());
+                return;
+            }
+        },
+        None => &team.pokemon[0],
+    };
+    let fighter_data = match lookup(&pokemon_db, &fighter.name) {
+        Some(data) => data,
+        None => {
+            eprintln!("{}", format!("Unknown Pokemon: {}", fighter.name).red());
+            return;
+        }
+    };
+
+    // Roaming legendaries bring the HP and status from their last appearance
+    let mut roaming = RoamingState::load();
+    if roaming.active(&pokemon).is_some_and(|r| !r.is_present(Some(&token))) {
+        return;
+    }
+    let roamer = roaming.active(&pokemon).map(|r| (r.hp, r.status));
+    let mut battle = WildBattle::for_token(Some(&token)).unwrap_or(WildBattle {
+        token: token.clone(),
+        hp: roamer.map(|(hp, _)| hp).unwrap_or(1.0),
+        status: roamer.map(|(_, status)| status).unwrap_or_default(),
+        turns: 0,
+        fainted: false,
+    });
+    if battle.fainted {
+        println!("{}", format!("The wild {} has already fainted.", full_name).red());
+        return;
+    }
+
+    println!("{}", format!("Go! {}!", fighter.name).cyan().bold());
+
+    match action {
+        FightMove::Attack => {
+            let (attack_type, effectiveness) = best_attack(&fighter_data.types, &wild.types);
+            println!("{} used a {} attack!", fighter.name, color_type(attack_type));
+
+            // Stronger team Pokemon hit harder, within limits
+            let power_ratio = (fighter_data.power_rank.max(1) as f32 / wild.power_rank.max(1) as f32).clamp(0.5, 2.0);
+            let damage = BASE_DAMAGE * power_ratio * effectiveness * rng.gen_range(0.85..=1.0);
+            battle.hp = (battle.hp - damage).max(0.0);
+
+            if effectiveness == 0.0 {
+                println!("{}", format!("It doesn't affect the wild {}...", full_name).dimmed());
+            } else if effectiveness > 1.0 {
+                println!("{}", "It's super effective!".green().bold());
+            } else if effectiveness < 1.0 {
+                println!("{}", "It's not very effective...".yellow());
+            }
+        }
+        FightMove::Sleep | FightMove::Paralyze => {
+            let (status, move_name, accuracy) = match action {
+                FightMove::Sleep => (StatusCondition::Sleep, "Hypnosis", 55.0),
+                _ => (StatusCondition::Paralysis, "Thunder Wave", 75.0),
+            };
+            println!("{} used {}!", fighter.name, move_name);
+
+            let immune = status == StatusCondition::Paralysis && wild.types.iter().any(|t| t == "electric");
+            if battle.status != StatusCondition::None {
+                println!("{}", format!("But the wild {} is already {}!", full_name, battle.status.display_name()).dimmed());
+            } else if immune || rng.gen_range(0.0..100.0) >= accuracy {
+                println!("{}", "But it failed!".dimmed());
+            } else {
+                battle.status = status;
+                println!("{}", format!("The wild {} is {}!", full_name, status.display_name()).yellow().bold());
+            }
+        }
+    }
+    battle.turns += 1;
+
+    // A roamer can't be knocked out
+    if roamer.is_some() && battle.hp < 0.01 {
+        battle.hp = 0.01;
+        println!("{}", format!("The wild {} endured the hit!", full_name).yellow().bold());
+    }
+
+    if battle.hp <= 0.0 {
+        println!();
+        println!("{}", format!("The wild {} fainted!", full_name).red().bold());
+        battle.fainted = true;
+        let _ = battle.save();
+        encounter_log::record(encounter_id(Some(&token)), LogEvent::Fight { end: FightEnd::Fainted });
+        return;
+    }
+
+    let status_label = if battle.status == StatusCondition::None {
+        String::new()
+    } else {
+        format!(" {}", battle.status.display_name().to_uppercase().magenta().bold())
+    };
+    println!("Wild {} HP {} {:.0}%{}", full_name, hp_bar(battle.hp), battle.hp * 100.0, status_label);
+
+    // Roamers flee after their first turn, keeping the damage for next time
+    if let Some(r) = roaming.active_mut(&pokemon) {
+        r.hp = battle.hp;
+        r.status = battle.status;
+        r.token = None;
+        if let Err(e) = roaming.save() {
+            eprintln!("{}", format!("Could not save roaming state: {}", e).red());
+        }
+        WildBattle::clear();
+        encounter_log::record(encounter_id(Some(&token)), LogEvent::Fight { end: FightEnd::Fled });
+        println!("{}", format!("The wild {} ran away!", full_name).red());
+        return;
+    }
+
+    // Each turn counts toward the rolling flee rate like a throw
+    let flee_rate = flee_chance(get_flee_rate(&pokemon), attempt + battle.turns - 1);
+    if rng.gen_range(0.0..100.0) < flee_rate {
+        WildBattle::clear();
+        encounter_log::record(encounter_id(Some(&token)), LogEvent::Fight { end: FightEnd::Fled });
+        println!("{}", format!("The wild {} ran away!", full_name).red());
+        return;
+    }
+
+    if let Err(e) = battle.save() {
+        eprintln!("{}", format!("Could not save battle: {}", e).red());
+        return;
+    }
+
+    let catch_rate = get_pokemon_catch_rate(&pokemon, form.as_deref());
+    let dex_caught = Pokedex::load().entries.values().filter(|e| e.caught).count();
+    println!(
+        "Catch chance: {}",
+        format!("{:.1}%", capture_chance(catch_rate, &battle.throw_conditions(), dex_caught)).bright_yellow().bold()
+    );
+}
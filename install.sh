@@ -100,35 +100,6 @@ if [[ ":$PATH:" != *":$HOME/.local/bin:"* ]]; then
     export PATH="$HOME/.local/bin:$PATH"
 fi
 
-# --- Install pokemon-colorscripts if not present ---
-if ! command -v pokemon-colorscripts &> /dev/null; then
-    echo ""
-    echo -e "${YELLOW}Installing pokemon-colorscripts (required for Pokemon sprites)...${NC}"
-
-    if ! command -v python3 &> /dev/null; then
-        echo -e "${RED}Python 3 is required for pokemon-colorscripts but not found.${NC}"
-        echo -e "${YELLOW}Please install Python 3 and re-run this installer.${NC}"
-    else
-        TMPDIR_PCS=$(mktemp -d)
-        trap "rm -rf $TMPDIR_PCS" EXIT
-
-        git clone https://gitlab.com/phoneybadger/pokemon-colorscripts.git "$TMPDIR_PCS/pokemon-colorscripts" 2>/dev/null
-
-        if [ -f "$TMPDIR_PCS/pokemon-colorscripts/install.sh" ]; then
-            echo -e "${YELLOW}Installing pokemon-colorscripts (may require sudo)...${NC}"
-            cd "$TMPDIR_PCS/pokemon-colorscripts"
-            sudo ./install.sh
-            cd - > /dev/null
-            echo -e "${GREEN}pokemon-colorscripts installed${NC}"
-        else
-            echo -e "${RED}Failed to clone pokemon-colorscripts.${NC}"
-            echo -e "${YELLOW}Install manually: https://gitlab.com/phoneybadger/pokemon-colorscripts${NC}"
-        fi
-    fi
-else
-    echo -e "${GREEN}pokemon-colorscripts already installed${NC}"
-fi
-
 # --- Set up shell functions ---
 echo ""
 echo -e "${YELLOW}Setting up shell functions...${NC}"
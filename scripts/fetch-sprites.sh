@@ -0,0 +1,18 @@
+#!/bin/bash
+# Fetch pokemon-colorscripts small sprites into cli/data/sprites so build.rs
+# can embed them in the binary. Only needed when building from source.
+set -e
+
+REPO_ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+DEST="$REPO_ROOT/cli/data/sprites"
+TMP=$(mktemp -d)
+trap "rm -rf $TMP" EXIT
+
+git clone --depth 1 https://gitlab.com/phoneybadger/pokemon-colorscripts.git "$TMP/pokemon-colorscripts"
+
+rm -rf "$DEST"
+mkdir -p "$DEST"
+cp -r "$TMP/pokemon-colorscripts/colorscripts/small/regular" "$DEST/regular"
+cp -r "$TMP/pokemon-colorscripts/colorscripts/small/shiny" "$DEST/shiny"
+
+echo "Fetched $(ls "$DEST/regular" | wc -l | tr -d ' ') sprites into $DEST"